@@ -0,0 +1,113 @@
+//! Audit log of tool calls (`--audit-log`)
+//!
+//! One JSON line is appended per `call_tool`. Lines are written by a
+//! background task so a slow or failing disk never delays or fails a call;
+//! write errors are only logged.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::error;
+
+/// Replacement for masked values
+const REDACTED: &str = "***";
+
+/// One audited tool call
+#[derive(Debug, Default, Serialize)]
+pub struct AuditEntry {
+    /// RFC 3339 time the call started
+    pub timestamp: String,
+    pub tool: String,
+    pub method: Option<&'static str>,
+    /// Request path with path parameters filled in
+    pub path: Option<String>,
+    pub arguments: serde_json::Value,
+    /// Upstream HTTP status (None when no response was received)
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    /// What stopped the call before it reached the API: `read_only`,
+    /// `method_policy`, `tool_filter`, `tag_filter`, `dry_run`,
+    /// `confirmation` or `rate_limit`
+    pub blocked: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Appends entries to the audit file
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    lines: mpsc::UnboundedSender<String>,
+    /// Argument names whose values are masked (case-insensitive)
+    redact_fields: Vec<String>,
+}
+
+impl AuditLog {
+    /// Open (or create) `path` for appending and start the writer task
+    pub fn open(path: &Path, redact_fields: &[String]) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+        let (lines, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(write_lines(
+            tokio::fs::File::from_std(file),
+            path.to_path_buf(),
+            receiver,
+        ));
+        Ok(Self {
+            lines,
+            redact_fields: redact_fields.iter().map(|f| f.to_lowercase()).collect(),
+        })
+    }
+
+    /// Queue an entry, masking its redacted arguments
+    pub fn record(&self, mut entry: AuditEntry) {
+        self.redact(&mut entry.arguments);
+        match serde_json::to_string(&entry) {
+            Ok(line) => {
+                // Only fails once the writer task is gone (runtime shutdown)
+                let _ = self.lines.send(line);
+            }
+            Err(e) => error!("Failed to serialize audit entry: {}", e),
+        }
+    }
+
+    fn redact(&self, value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(object) => {
+                for (key, child) in object.iter_mut() {
+                    if self.redact_fields.contains(&key.to_lowercase()) {
+                        *child = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact(child);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.redact(item);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn write_lines(
+    mut file: tokio::fs::File,
+    path: PathBuf,
+    mut lines: mpsc::UnboundedReceiver<String>,
+) {
+    while let Some(mut line) = lines.recv().await {
+        line.push('\n');
+        let written = async {
+            file.write_all(line.as_bytes()).await?;
+            file.flush().await
+        };
+        if let Err(e) = written.await {
+            error!("Failed to write audit log {}: {}", path.display(), e);
+        }
+    }
+}
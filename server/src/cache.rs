@@ -0,0 +1,112 @@
+//! In-memory cache of GET responses (`--cache-ttl`)
+//!
+//! Entries are keyed on the resolved path and query string and expire after
+//! the TTL. A successful write drops the cached GETs of the written path, of
+//! the paths below it and of the paths above it, since any of them may embed
+//! the changed object.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Entries kept unless --cache-max-entries says otherwise
+pub const DEFAULT_CACHE_MAX_ENTRIES: usize = 500;
+
+#[derive(Debug)]
+struct Entry {
+    stored: Instant,
+    path: String,
+    status: u16,
+    response: serde_json::Value,
+}
+
+/// A cached response and its age
+#[derive(Debug)]
+pub struct Hit {
+    pub status: u16,
+    pub response: serde_json::Value,
+    pub age: Duration,
+}
+
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries: max_entries.max(1),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Key of a GET of `path` with these query parameters (in any order)
+    pub fn key(path: &str, query: &HashMap<String, String>) -> String {
+        let mut pairs: Vec<_> = query.iter().collect();
+        pairs.sort();
+        let query: Vec<String> = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        format!("{}?{}", path, query.join("&"))
+    }
+
+    pub fn get(&self, key: &str) -> Option<Hit> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        let age = entry.stored.elapsed();
+        if age >= self.ttl {
+            entries.remove(key);
+            return None;
+        }
+        Some(Hit {
+            status: entry.status,
+            response: entry.response.clone(),
+            age,
+        })
+    }
+
+    /// Store a response, evicting expired entries then the oldest one when
+    /// the cache is full
+    pub fn insert(&self, key: String, path: String, status: u16, response: serde_json::Value) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.stored.elapsed() < self.ttl);
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            key,
+            Entry {
+                stored: Instant::now(),
+                path,
+                status,
+                response,
+            },
+        );
+    }
+
+    /// Drop the entries a write to `path` may have changed; returns how many
+    pub fn invalidate(&self, path: &str) -> usize {
+        let related = |cached: &str| is_within(cached, path) || is_within(path, cached);
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|_, entry| !related(&entry.path));
+        before - entries.len()
+    }
+}
+
+/// `path` is `ancestor` or below it, segment-wise
+fn is_within(path: &str, ancestor: &str) -> bool {
+    let ancestor = ancestor.trim_end_matches('/');
+    path.strip_prefix(ancestor)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
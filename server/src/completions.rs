@@ -0,0 +1,79 @@
+//! Argument completion (`completion/complete`) for tool parameters
+//!
+//! MCP only defines prompt and resource references, so a tool is addressed
+//! either by its name as a `ref/prompt` or by its `pangolin://endpoint/<tool>`
+//! resource URI.
+
+use rmcp::model::CompletionInfo;
+
+use crate::types::PangolinEndpoint;
+
+/// Allowed values of an endpoint argument: path/query parameter enums first,
+/// then request body property enums
+pub fn enum_values<'a>(endpoint: &'a PangolinEndpoint, argument: &str) -> Option<&'a [String]> {
+    endpoint
+        .path_params
+        .iter()
+        .chain(&endpoint.query_params)
+        .find(|p| p.name == argument)
+        .and_then(|p| p.enum_values.as_deref())
+        .or_else(|| {
+            endpoint
+                .request_body
+                .as_ref()?
+                .properties
+                .get(argument)?
+                .enum_values
+                .as_deref()
+        })
+}
+
+/// Candidates starting with the typed value (case-insensitive), capped at
+/// the protocol's maximum
+pub fn complete<'a>(
+    candidates: impl IntoIterator<Item = &'a String>,
+    typed: &str,
+) -> CompletionInfo {
+    let typed = typed.to_lowercase();
+    let values: Vec<String> = candidates
+        .into_iter()
+        .filter(|v| v.to_lowercase().starts_with(&typed))
+        .cloned()
+        .collect();
+
+    let total = values.len();
+    let has_more = total > CompletionInfo::MAX_VALUES;
+    CompletionInfo {
+        values: values
+            .into_iter()
+            .take(CompletionInfo::MAX_VALUES)
+            .collect(),
+        total: Some(total as u32),
+        has_more: Some(has_more),
+    }
+}
+
+/// Every string value of `field` found anywhere in a response (e.g. the
+/// `orgId`s of a `GET /orgs` listing, whatever its envelope)
+pub fn collect_field(value: &serde_json::Value, field: &str, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, child) in object {
+                match child {
+                    serde_json::Value::String(s) if key == field => {
+                        if !out.contains(s) {
+                            out.push(s.clone());
+                        }
+                    }
+                    _ => collect_field(child, field, out),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_field(item, field, out);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,135 @@
+//! JSON Patch (RFC 6902) and JSON Merge Patch (RFC 7386) for `--spec-patch`
+//!
+//! A patch document that is an array is read as a list of JSON Patch
+//! operations; any other document is a merge patch.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Apply a JSON Patch (array) or JSON Merge Patch (anything else) to `doc`
+pub fn apply_patch(doc: &mut Value, patch: &Value) -> Result<()> {
+    match patch {
+        Value::Array(operations) => {
+            for (i, operation) in operations.iter().enumerate() {
+                apply_operation(doc, operation)
+                    .with_context(|| format!("JSON Patch operation #{} failed", i))?;
+            }
+            Ok(())
+        }
+        _ => {
+            merge_patch(doc, patch);
+            Ok(())
+        }
+    }
+}
+
+/// RFC 7386: objects merge recursively, `null` removes a member, anything
+/// else replaces the target
+fn merge_patch(doc: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *doc = patch.clone();
+        return;
+    };
+    if !doc.is_object() {
+        *doc = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = doc {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn apply_operation(doc: &mut Value, operation: &Value) -> Result<()> {
+    let field = |name: &str| {
+        operation
+            .get(name)
+            .and_then(Value::as_str)
+            .with_context(|| format!("missing '{}'", name))
+    };
+    let value = || operation.get("value").cloned().context("missing 'value'");
+    let path = field("path")?;
+
+    match field("op")? {
+        "add" => add(doc, path, value()?),
+        "remove" => remove(doc, path).map(drop),
+        "replace" => {
+            let target = doc
+                .pointer_mut(path)
+                .with_context(|| format!("no value at '{}'", path))?;
+            *target = value()?;
+            Ok(())
+        }
+        "move" => {
+            let moved = remove(doc, field("from")?)?;
+            add(doc, path, moved)
+        }
+        "copy" => {
+            let from = field("from")?;
+            let copied = doc
+                .pointer(from)
+                .cloned()
+                .with_context(|| format!("no value at '{}'", from))?;
+            add(doc, path, copied)
+        }
+        "test" => {
+            if doc.pointer(path) != Some(&value()?) {
+                bail!("test failed: '{}' does not hold the expected value", path);
+            }
+            Ok(())
+        }
+        other => bail!("unknown op '{}'", other),
+    }
+}
+
+/// Split a JSON pointer into its parent pointer and unescaped last token
+fn split_pointer(path: &str) -> Result<(&str, String)> {
+    let (parent, last) = path
+        .rsplit_once('/')
+        .with_context(|| format!("invalid JSON pointer '{}'", path))?;
+    Ok((parent, last.replace("~1", "/").replace("~0", "~")))
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<()> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(());
+    }
+    let (parent, key) = split_pointer(path)?;
+    match doc.pointer_mut(parent) {
+        Some(Value::Object(object)) => {
+            object.insert(key, value);
+        }
+        Some(Value::Array(items)) => {
+            let index = if key == "-" {
+                items.len()
+            } else {
+                key.parse()
+                    .ok()
+                    .filter(|i| *i <= items.len())
+                    .with_context(|| format!("invalid array index in '{}'", path))?
+            };
+            items.insert(index, value);
+        }
+        _ => bail!("no object or array at '{}'", parent),
+    }
+    Ok(())
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value> {
+    let (parent, key) = split_pointer(path)?;
+    let removed = match doc.pointer_mut(parent) {
+        Some(Value::Object(object)) => object.shift_remove(&key),
+        Some(Value::Array(items)) => key
+            .parse()
+            .ok()
+            .filter(|i| *i < items.len())
+            .map(|i| items.remove(i)),
+        _ => None,
+    };
+    removed.with_context(|| format!("no value at '{}'", path))
+}
@@ -1,19 +1,49 @@
+mod audit;
+mod cache;
+mod completions;
+mod json_patch;
+mod mcp_logging;
+mod overlay;
 mod pangolin_client;
+mod prompts;
+mod rate_limit;
+mod redact;
+mod resources;
+mod search;
 mod service;
+mod shutdown;
+mod spec_diff;
+mod stats;
 mod swagger;
+mod truncate;
 mod types;
+mod workflows;
 
 use anyhow::{Context, Result};
 use clap::Parser;
 use rmcp::{transport::stdio, ServiceExt};
 use std::path::PathBuf;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn, Level};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
-use crate::service::PangolinService;
+use crate::cache::DEFAULT_CACHE_MAX_ENTRIES;
+use crate::mcp_logging::LogForwarder;
+use crate::pangolin_client::{
+    build_http_client, run_key_command, ClientConfig, DEFAULT_USER_AGENT,
+};
+use crate::service::{
+    BoolQueryStyle, PangolinService, ServiceConfig, DEFAULT_BATCH_MAX,
+    DEFAULT_INSTRUCTIONS_MAX_CHARS, DEFAULT_MAX_RESPONSE_BYTES,
+};
 use crate::swagger::SwaggerSpec;
+use crate::types::HttpMethod;
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(
     name = "mcp-pangolin",
     about = "MCP server for Pangolin Integration API",
@@ -26,72 +56,666 @@ use crate::swagger::SwaggerSpec;
     version
 )]
 struct Args {
-    /// Path to the OpenAPI/Swagger JSON specification file
-    #[arg(short, long, env = "PANGOLIN_OPENAPI_FILE")]
-    openapi: Option<PathBuf>,
+    /// Path to the OpenAPI/Swagger JSON specification file; repeat (or
+    /// comma-separate) to merge the paths of several files
+    #[arg(short, long, env = "PANGOLIN_OPENAPI_FILE", value_delimiter = ',')]
+    openapi: Vec<PathBuf>,
 
     /// Inline OpenAPI/Swagger JSON specification (alternative to --openapi file)
     #[arg(long, env = "PANGOLIN_OPENAPI_JSON")]
     openapi_json: Option<String>,
 
+    /// URL to fetch the OpenAPI/Swagger JSON specification from, sent with the API key
+    /// (e.g. https://pangolin.example.com/v1/openapi.json)
+    #[arg(long, env = "PANGOLIN_OPENAPI_URL")]
+    openapi_url: Option<String>,
+
+    /// Path to an OpenAPI Overlay JSON file applied on top of the loaded spec
+    #[arg(long, env = "PANGOLIN_OPENAPI_PATCH")]
+    openapi_patch: Option<PathBuf>,
+
+    /// Path to a JSON Patch (RFC 6902) or JSON Merge Patch (RFC 7386) file
+    /// applied to the spec after any overlay, e.g. to fix a missing required flag
+    #[arg(long, env = "PANGOLIN_SPEC_PATCH")]
+    spec_patch: Option<PathBuf>,
+
     /// Pangolin API key for authentication
-    #[arg(short = 'k', long, env = "PANGOLIN_API_KEY")]
+    #[arg(
+        short = 'k',
+        long,
+        env = "PANGOLIN_API_KEY",
+        required_unless_present_any = ["print_tools", "api_key_command"],
+        default_value = "",
+        hide_default_value = true
+    )]
     api_key: String,
 
+    /// Shell command printing a fresh API key (e.g. `vault read -field=key ...`);
+    /// re-run when the key expires or the API answers 401
+    #[arg(long, env = "PANGOLIN_API_KEY_COMMAND")]
+    api_key_command: Option<String>,
+
+    /// Seconds a key printed by --api-key-command is reused
+    #[arg(long, env = "PANGOLIN_API_KEY_TTL_SECS", default_value = "300")]
+    api_key_ttl_secs: u64,
+
     /// Base URL for the Pangolin API (e.g., https://pangolin.example.com/v1)
-    #[arg(short, long, env = "PANGOLIN_BASE_URL")]
+    #[arg(
+        short,
+        long,
+        env = "PANGOLIN_BASE_URL",
+        required_unless_present = "print_tools",
+        default_value = "http://localhost",
+        hide_default_value = true
+    )]
     base_url: String,
 
+    /// Path prefix prepended to every endpoint path when the API is mounted under
+    /// a prefix the spec doesn't include (e.g. /integration)
+    #[arg(long, env = "PANGOLIN_BASE_PATH")]
+    base_path: Option<String>,
+
     /// Enable read-only mode (only GET operations are allowed)
     #[arg(short, long, env = "PANGOLIN_READ_ONLY", default_value = "false")]
     read_only: bool,
+
+    /// Exceptions to the method-based read-only rule, as TOOL=allow|deny
+    /// (e.g. search_resources=allow,org_by_orgId_regenerate=deny)
+    #[arg(
+        long = "read-only-overrides",
+        env = "PANGOLIN_READ_ONLY_OVERRIDES",
+        value_delimiter = ',',
+        value_parser = parse_read_only_override
+    )]
+    read_only_overrides: Vec<(String, bool)>,
+
+    /// In read-only mode, answer calls to write tools with "Unknown tool"
+    /// instead of explaining that they are disabled
+    #[arg(long, env = "PANGOLIN_READ_ONLY_HIDE_WRITES", default_value = "false")]
+    read_only_hide_writes: bool,
+
+    /// Prefix prepended to every tool name (e.g. "pangolin_") to avoid collisions
+    /// with other MCP servers
+    #[arg(long, env = "PANGOLIN_TOOL_PREFIX")]
+    tool_prefix: Option<String>,
+
+    /// Request content type to use for an endpoint when it offers several,
+    /// as TOOL=CONTENT_TYPE (e.g. update_org_by_orgId=application/x-www-form-urlencoded)
+    #[arg(
+        long = "content-type-override",
+        env = "PANGOLIN_CONTENT_TYPE_OVERRIDES",
+        value_delimiter = ',',
+        value_parser = parse_key_value
+    )]
+    content_type_overrides: Vec<(String, String)>,
+
+    /// Log full HTTP requests and responses (headers and bodies, API key redacted)
+    /// at debug level; combine with RUST_LOG=debug
+    #[arg(long, env = "PANGOLIN_TRACE_HTTP", default_value = "false")]
+    trace_http: bool,
+
+    /// Maximum number of tools returned per tools/list page (default: all at once)
+    #[arg(long, env = "PANGOLIN_TOOLS_PAGE_SIZE")]
+    tools_page_size: Option<usize>,
+
+    /// User-Agent header sent to the Pangolin API (default: mcp-pangolin/<version>)
+    #[arg(long, env = "PANGOLIN_USER_AGENT")]
+    user_agent: Option<String>,
+
+    /// Consecutive upstream failures (network errors, 5xx) that open the circuit
+    /// breaker; calls then fail fast until the cooldown elapses (0 disables it)
+    #[arg(long, env = "PANGOLIN_BREAKER_THRESHOLD", default_value = "5")]
+    breaker_threshold: u32,
+
+    /// Window in seconds within which failures count as consecutive
+    #[arg(long, env = "PANGOLIN_BREAKER_WINDOW_SECS", default_value = "60")]
+    breaker_window_secs: u64,
+
+    /// Seconds the circuit breaker stays open before a trial request is allowed
+    #[arg(long, env = "PANGOLIN_BREAKER_COOLDOWN_SECS", default_value = "30")]
+    breaker_cooldown_secs: u64,
+
+    /// PEM file with an additional root certificate to trust, for Pangolin
+    /// deployments behind a private CA or a self-signed certificate
+    #[arg(long, env = "PANGOLIN_CA_CERT")]
+    ca_cert: Option<PathBuf>,
+
+    /// Disable TLS certificate verification (insecure; prefer --ca-cert)
+    #[arg(
+        long,
+        env = "PANGOLIN_DANGER_ACCEPT_INVALID_CERTS",
+        default_value = "false"
+    )]
+    danger_accept_invalid_certs: bool,
+
+    /// Proxy URL for Pangolin API requests and the --openapi-url fetch
+    /// (default: HTTPS_PROXY/HTTP_PROXY, honouring NO_PROXY)
+    #[arg(long, env = "PANGOLIN_PROXY")]
+    proxy: Option<String>,
+
+    /// Wrap successful responses as {"_status": <code>, "data": <body>} so
+    /// agents can tell 200 from 202 Accepted or 204 No Content
+    #[arg(long, env = "PANGOLIN_INCLUDE_STATUS", default_value = "false")]
+    include_status: bool,
+
+    /// Only expose tools whose generated name (without --tool-prefix) fully
+    /// matches one of these comma-separated regexes
+    #[arg(long, env = "PANGOLIN_ALLOW_TOOLS", value_delimiter = ',')]
+    allow_tools: Vec<String>,
+
+    /// Hide tools whose generated name fully matches one of these comma-separated
+    /// regexes (applied after --allow-tools)
+    #[arg(long, env = "PANGOLIN_DENY_TOOLS", value_delimiter = ',')]
+    deny_tools: Vec<String>,
+
+    /// Truncate tool results larger than this many bytes, dropping trailing
+    /// array elements first (0 disables truncation)
+    #[arg(long, env = "PANGOLIN_MAX_RESPONSE_BYTES", default_value_t = DEFAULT_MAX_RESPONSE_BYTES)]
+    max_response_bytes: usize,
+
+    /// Page size sent to GET endpoints whose page size query parameter
+    /// (limit, pageSize, per_page, ...) is omitted and has no documented default
+    #[arg(long, env = "PANGOLIN_DEFAULT_PAGE_SIZE")]
+    default_page_size: Option<u64>,
+
+    /// Expose one tool per tag (e.g. "site") taking the endpoint as an
+    /// `operation` argument, for clients limited in tool count
+    #[arg(long, env = "PANGOLIN_GROUP_BY_TAG", default_value = "false")]
+    group_by_tag: bool,
+
+    /// Expose only search_endpoints and call_endpoint (which calls any
+    /// endpoint by name), for clients limited to a few tools
+    #[arg(long, env = "PANGOLIN_GENERIC_MODE", default_value = "false")]
+    generic_mode: bool,
+
+    /// Seconds GET responses are reused before the API is called again
+    /// (0 disables the cache); writes drop the related cached responses
+    #[arg(long, env = "PANGOLIN_CACHE_TTL", default_value_t = 0)]
+    cache_ttl: u64,
+
+    /// Most GET responses kept in the cache
+    #[arg(long, env = "PANGOLIN_CACHE_MAX_ENTRIES", default_value_t = DEFAULT_CACHE_MAX_ENTRIES)]
+    cache_max_entries: usize,
+
+    /// JSON file defining composite tools that chain generated tools, feeding
+    /// earlier responses into later calls
+    #[arg(long, env = "PANGOLIN_WORKFLOWS")]
+    workflows: Option<PathBuf>,
+
+    /// Most calls accepted by one batch_call
+    #[arg(long, env = "PANGOLIN_BATCH_MAX", default_value_t = DEFAULT_BATCH_MAX)]
+    batch_max: usize,
+
+    /// How boolean query parameters are sent: true-false (flag=true),
+    /// 1-0 (flag=1) or presence (flag= when true, left out when false)
+    #[arg(
+        long,
+        env = "PANGOLIN_BOOL_QUERY_STYLE",
+        default_value = "true-false",
+        value_parser = parse_bool_query_style
+    )]
+    bool_query_style: BoolQueryStyle,
+
+    /// Length the server instructions sent on initialize are cut down to;
+    /// per-tag lines that do not fit are summarized (0 is unlimited)
+    #[arg(
+        long,
+        env = "PANGOLIN_INSTRUCTIONS_MAX_CHARS",
+        default_value_t = DEFAULT_INSTRUCTIONS_MAX_CHARS
+    )]
+    instructions_max_chars: usize,
+
+    /// Append one JSON line per tool call (tool, method, path, arguments,
+    /// status, duration) to this file
+    #[arg(long, env = "PANGOLIN_AUDIT_LOG")]
+    audit_log: Option<PathBuf>,
+
+    /// Comma-separated argument names whose values are masked in the audit log
+    #[arg(
+        long,
+        env = "PANGOLIN_AUDIT_REDACT_FIELDS",
+        value_delimiter = ',',
+        default_value = "password,pincode,passcode,secret,token,apiKey"
+    )]
+    audit_redact_fields: Vec<String>,
+
+    /// Comma-separated key names whose values are replaced with
+    /// "***REDACTED***" in tool results (case-insensitive, also matching keys
+    /// that contain them, e.g. apiKey matches x_api_key)
+    #[arg(
+        long,
+        env = "PANGOLIN_REDACT_FIELDS",
+        value_delimiter = ',',
+        default_value = "secret,token,password,passcode,apiKey,privateKey"
+    )]
+    redact_fields: Vec<String>,
+
+    /// Return API responses unmasked (ignores --redact-fields)
+    #[arg(long, env = "PANGOLIN_NO_REDACT", default_value = "false")]
+    no_redact: bool,
+
+    /// Confine the server to one organization: orgId is filled in and
+    /// dropped from tool schemas, other organizations are refused and every
+    /// endpoint without an orgId path parameter (GET /orgs, /site/{siteId},
+    /// ...) is hidden, since it could reach other organizations
+    #[arg(long, env = "PANGOLIN_ORG_ID")]
+    org_id: Option<String>,
+
+    /// Validate the spec at startup (unresolved $refs, duplicate
+    /// operationIds, incomplete parameters, path/parameter mismatches) and
+    /// refuse to start on any problem, listing them all
+    #[arg(long, env = "PANGOLIN_STRICT", default_value = "false")]
+    strict: bool,
+
+    /// Add "additionalProperties": false to generated tool input schemas so
+    /// clients reject arguments the tool does not declare
+    #[arg(long, env = "PANGOLIN_STRICT_TOOL_SCHEMAS", default_value = "false")]
+    strict_tool_schemas: bool,
+
+    /// Most write calls (POST, PUT, PATCH, DELETE) per minute across all
+    /// tools; further writes fail without reaching the API until capacity
+    /// returns (0 is unlimited, GETs and dry runs are never limited)
+    #[arg(long, env = "PANGOLIN_WRITE_RATE_LIMIT", default_value_t = 0)]
+    write_rate_limit: u32,
+
+    /// Accept a _base_url argument sending a single call to another server;
+    /// the API key is sent there too, so only enable for trusted agents
+    #[arg(
+        long,
+        env = "PANGOLIN_ALLOW_BASE_URL_OVERRIDE",
+        default_value = "false"
+    )]
+    allow_base_url_override: bool,
+
+    /// Never call the Pangolin API: tools return the HTTP request they would
+    /// send (API key masked)
+    #[arg(long, env = "PANGOLIN_DRY_RUN", default_value = "false")]
+    dry_run: bool,
+
+    /// Ask the user to confirm (via elicitation) calls to tools whose generated
+    /// name fully matches one of these comma-separated regexes; DELETE tools
+    /// always need confirmation
+    #[arg(long, env = "PANGOLIN_CONFIRM_TOOLS", value_delimiter = ',')]
+    confirm_tools: Vec<String>,
+
+    /// Give every DELETE tool a required boolean "confirm" argument; calls
+    /// are refused unless it is true (independent of elicitation)
+    #[arg(
+        long,
+        env = "PANGOLIN_REQUIRE_DELETE_CONFIRMATION",
+        default_value = "false"
+    )]
+    require_delete_confirmation: bool,
+
+    /// Do not offer the pangolin_get_openapi tool returning the OpenAPI spec
+    #[arg(long, env = "PANGOLIN_DISABLE_OPENAPI_TOOL", default_value = "false")]
+    disable_openapi_tool: bool,
+
+    /// Only expose tools using these comma-separated HTTP methods
+    /// (e.g. get,post,put); read-only mode still hides write methods
+    #[arg(
+        long,
+        env = "PANGOLIN_ALLOW_METHODS",
+        value_delimiter = ',',
+        value_parser = parse_http_method
+    )]
+    allow_methods: Vec<HttpMethod>,
+
+    /// Hide tools using these comma-separated HTTP methods (e.g. delete,patch)
+    #[arg(
+        long,
+        env = "PANGOLIN_DENY_METHODS",
+        value_delimiter = ',',
+        value_parser = parse_http_method
+    )]
+    deny_methods: Vec<HttpMethod>,
+
+    /// Maximum number of concurrent Pangolin API requests; further tool calls
+    /// queue until one finishes (default: unbounded)
+    #[arg(long, env = "PANGOLIN_MAX_CONCURRENCY")]
+    max_concurrency: Option<usize>,
+
+    /// Only expose tools tagged with one of these comma-separated tags
+    /// (case-insensitive; a tool with several tags needs just one)
+    #[arg(long, env = "PANGOLIN_EXPOSE_TAGS", value_delimiter = ',')]
+    expose_tags: Vec<String>,
+
+    /// Hide tools tagged with these comma-separated tags unless another of
+    /// their tags is exposed
+    #[arg(long, env = "PANGOLIN_HIDE_TAGS", value_delimiter = ',')]
+    hide_tags: Vec<String>,
+
+    /// Send tool arguments to the API without checking them against the input
+    /// schema (for specs that disagree with the live API)
+    #[arg(long, env = "PANGOLIN_NO_VALIDATE_ARGS", default_value = "false")]
+    no_validate_args: bool,
+
+    /// Complete orgId arguments from a cached GET /orgs call (clients supporting
+    /// completion/complete); enum-valued arguments always complete
+    #[arg(long, env = "PANGOLIN_DYNAMIC_COMPLETION", default_value = "false")]
+    dynamic_completion: bool,
+
+    /// Print the tools that would be exposed (names, descriptions, input schemas)
+    /// as JSON to stdout and exit without starting the server
+    #[arg(long)]
+    print_tools: bool,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize logging to stderr (NEVER stdout for stdio transport!)
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_writer(std::io::stderr)
-        .init();
+/// Arguments of the `diff` subcommand
+#[derive(Parser, Debug)]
+#[command(
+    name = "mcp-pangolin diff",
+    about = "Compare the endpoints (tools) of two OpenAPI/Swagger specifications"
+)]
+struct DiffArgs {
+    /// Path to the previous specification
+    old: PathBuf,
 
-    let args = Args::parse();
+    /// Path to the new specification
+    new: PathBuf,
 
-    info!("Starting MCP Pangolin server");
+    /// Print the diff as JSON instead of text
+    #[arg(long)]
+    json: bool,
+}
 
+/// Print the endpoint changes between two spec files
+fn run_diff(args: DiffArgs) -> Result<()> {
+    let load = |path: &PathBuf| {
+        SwaggerSpec::from_file(path.to_str().context("Invalid path")?)
+            .with_context(|| format!("Failed to load OpenAPI specification from {:?}", path))
+    };
+    let old = load(&args.old)?.extract_endpoints();
+    let new = load(&args.new)?.extract_endpoints();
+
+    let diff = spec_diff::diff_endpoints(&old, &new);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print!("{}", diff);
+    }
+    Ok(())
+}
+
+/// Parse a KEY=VALUE command-line pair
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", s))
+}
+
+/// Parse a TOOL=allow|deny read-only override
+fn parse_read_only_override(s: &str) -> Result<(String, bool), String> {
+    let (tool, policy) = parse_key_value(s)?;
+    match policy.as_str() {
+        "allow" => Ok((tool, true)),
+        "deny" => Ok((tool, false)),
+        _ => Err(format!("expected TOOL=allow or TOOL=deny, got '{}'", s)),
+    }
+}
+
+/// Parse an HTTP method name for --allow-methods/--deny-methods
+fn parse_http_method(s: &str) -> Result<HttpMethod, String> {
+    HttpMethod::from_name(s)
+        .ok_or_else(|| format!("expected one of get, post, put, delete, patch, got '{}'", s))
+}
+
+/// Parse a --bool-query-style value
+fn parse_bool_query_style(s: &str) -> Result<BoolQueryStyle, String> {
+    BoolQueryStyle::from_name(s)
+        .ok_or_else(|| format!("expected true-false, 1-0 or presence, got '{}'", s))
+}
+
+/// HTTP client options set on the command line
+fn client_config(args: &Args) -> ClientConfig {
+    ClientConfig {
+        trace_http: args.trace_http,
+        user_agent: args
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+        base_path: args.base_path.clone().unwrap_or_default(),
+        breaker_threshold: args.breaker_threshold,
+        breaker_window: Duration::from_secs(args.breaker_window_secs),
+        breaker_cooldown: Duration::from_secs(args.breaker_cooldown_secs),
+        ca_cert: args.ca_cert.clone(),
+        accept_invalid_certs: args.danger_accept_invalid_certs,
+        proxy: args.proxy.clone(),
+        api_key_command: args.api_key_command.clone(),
+        api_key_ttl: Duration::from_secs(args.api_key_ttl_secs),
+        include_status: args.include_status,
+        ..Default::default()
+    }
+}
+
+/// Load the OpenAPI spec from the configured source and apply the overlay
+/// and spec patch
+async fn load_spec(args: &Args) -> Result<SwaggerSpec> {
     // Load the OpenAPI spec
-    let spec = if let Some(openapi_path) = &args.openapi {
-        info!("Loading OpenAPI spec from file: {:?}", openapi_path);
-        SwaggerSpec::from_file(openapi_path.to_str().context("Invalid path")?)
-            .context("Failed to load OpenAPI specification from file")?
+    let mut spec = if let Some((first, others)) = args.openapi.split_first() {
+        let load = |openapi_path: &PathBuf| {
+            info!("Loading OpenAPI spec from file: {:?}", openapi_path);
+            SwaggerSpec::from_file(openapi_path.to_str().context("Invalid path")?).with_context(
+                || {
+                    format!(
+                        "Failed to load OpenAPI specification from file {}",
+                        openapi_path.display()
+                    )
+                },
+            )
+        };
+        let mut spec = load(first)?;
+        for openapi_path in others {
+            spec.merge(load(openapi_path)?).with_context(|| {
+                format!(
+                    "Failed to merge OpenAPI specification {}",
+                    openapi_path.display()
+                )
+            })?;
+        }
+        spec
     } else if let Some(openapi_json) = &args.openapi_json {
         info!("Loading OpenAPI spec from inline JSON");
         SwaggerSpec::from_json(openapi_json)
             .context("Failed to parse inline OpenAPI specification")?
+    } else if let Some(openapi_url) = &args.openapi_url {
+        info!("Loading OpenAPI spec from URL: {}", openapi_url);
+        // With --api-key-command, --api-key is empty: fetch the key it prints
+        let api_key = match &args.api_key_command {
+            Some(command) => Some(run_key_command(command).await?),
+            None => Some(args.api_key.clone()).filter(|key| !key.is_empty()),
+        };
+        // The spec is fetched with the same TLS, proxy and User-Agent options
+        // as API calls
+        let client = build_http_client(&client_config(args), openapi_url)?;
+        SwaggerSpec::from_url(&client, openapi_url, api_key.as_deref())
+            .await
+            .context("Failed to load OpenAPI specification from URL")?
     } else {
         anyhow::bail!(
-            "Either --openapi (file path) or --openapi-json (inline JSON) must be provided.\n\n\
+            "One of --openapi (file path), --openapi-json (inline JSON) or --openapi-url (URL) must be provided.\n\n\
              Examples:\n\
              \n\
              1. Load from file:\n\
                 mcp-pangolin --openapi pangolin-api.json --api-key YOUR_KEY --base-url https://api.example.com/v1\n\
              \n\
              2. Load from inline JSON:\n\
-                mcp-pangolin --openapi-json '{{...}}' --api-key YOUR_KEY --base-url https://api.example.com/v1"
+                mcp-pangolin --openapi-json '{{...}}' --api-key YOUR_KEY --base-url https://api.example.com/v1\n\
+             \n\
+             3. Load from URL:\n\
+                mcp-pangolin --openapi-url https://api.example.com/v1/openapi.json --api-key YOUR_KEY --base-url https://api.example.com/v1"
         );
     };
 
+    // Apply the overlay, if any, before endpoints are extracted
+    if let Some(patch_path) = &args.openapi_patch {
+        info!("Applying OpenAPI overlay from file: {:?}", patch_path);
+        let content =
+            std::fs::read_to_string(patch_path).context("Failed to read OpenAPI overlay file")?;
+        let overlay: serde_json::Value =
+            serde_json::from_str(&content).context("Failed to parse OpenAPI overlay JSON")?;
+        let touched = spec
+            .apply_overlay(&overlay)
+            .context("Failed to apply OpenAPI overlay")?;
+        info!("OpenAPI overlay touched {} node(s)", touched);
+    }
+
+    if let Some(patch_path) = &args.spec_patch {
+        info!("Applying spec patch from file: {:?}", patch_path);
+        let content =
+            std::fs::read_to_string(patch_path).context("Failed to read spec patch file")?;
+        let patch: serde_json::Value =
+            serde_json::from_str(&content).context("Failed to parse spec patch JSON")?;
+        spec.apply_patch(&patch)
+            .context("Failed to apply spec patch")?;
+    }
+
+    Ok(spec)
+}
+
+/// Refuse a spec with problems under --strict
+fn check_strict(args: &Args, spec: &SwaggerSpec) -> Result<()> {
+    if args.strict {
+        let problems = spec.validate();
+        if !problems.is_empty() {
+            anyhow::bail!(
+                "The OpenAPI spec has {} problem(s) (--strict):\n- {}",
+                problems.len(),
+                problems.join("\n- ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reload the spec whenever the process receives SIGHUP; connected clients
+/// are notified to re-fetch the tool list. A spec refused by the startup
+/// checks (including --strict) leaves the current one in place
+#[cfg(unix)]
+fn reload_on_sighup(args: Args, service: PangolinService) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).context("Failed to listen for SIGHUP")?;
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            info!("SIGHUP received, reloading OpenAPI specification");
+            let reloaded = load_spec(&args).await.and_then(|spec| {
+                check_strict(&args, &spec)?;
+                service.reload(&spec)
+            });
+            if let Err(e) = reloaded {
+                warn!(
+                    "Failed to reload OpenAPI specification, keeping the current one: {:#}",
+                    e
+                );
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logging to stderr (NEVER stdout for stdio transport!); this
+    // crate's events are also forwarded to the client once it sets a level
+    let log_forwarder = Arc::new(LogForwarder::default());
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(
+                    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+                ),
+        )
+        .with(
+            log_forwarder
+                .layer()
+                .with_filter(Targets::new().with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG)),
+        )
+        .init();
+
+    if std::env::args().nth(1).as_deref() == Some("diff") {
+        return run_diff(DiffArgs::parse_from(std::env::args().skip(1)));
+    }
+
+    let args = Args::parse();
+
+    info!("Starting MCP Pangolin server");
+
+    let spec = load_spec(&args).await?;
+
     info!(
         "Loaded OpenAPI spec: {} v{}",
         spec.info.title, spec.info.version
     );
 
+    check_strict(&args, &spec)?;
+
     // Create the MCP service
-    let service = PangolinService::new(spec, args.api_key, args.base_url, args.read_only)
+    #[cfg(unix)]
+    let reload_args = args.clone();
+    let client = client_config(&args);
+    let config = ServiceConfig {
+        read_only: args.read_only,
+        read_only_overrides: args.read_only_overrides.into_iter().collect(),
+        read_only_hide_writes: args.read_only_hide_writes,
+        tool_prefix: args.tool_prefix,
+        content_type_overrides: args.content_type_overrides.into_iter().collect(),
+        tools_page_size: args.tools_page_size,
+        log_forwarder,
+        dynamic_completion: args.dynamic_completion,
+        skip_arg_validation: args.no_validate_args,
+        allow_tools: args.allow_tools,
+        deny_tools: args.deny_tools,
+        expose_tags: args.expose_tags,
+        hide_tags: args.hide_tags,
+        dry_run: args.dry_run,
+        default_page_size: args.default_page_size,
+        group_by_tag: args.group_by_tag,
+        generic_mode: args.generic_mode,
+        cache_ttl: Some(Duration::from_secs(args.cache_ttl)),
+        cache_max_entries: Some(args.cache_max_entries),
+        batch_max: Some(args.batch_max),
+        instructions_max_chars: Some(args.instructions_max_chars),
+        bool_query_style: args.bool_query_style,
+        strict_tool_schemas: args.strict_tool_schemas,
+        write_rate_limit: Some(args.write_rate_limit),
+        allow_base_url_override: args.allow_base_url_override,
+        require_delete_confirmation: args.require_delete_confirmation,
+        disable_openapi_tool: args.disable_openapi_tool,
+        workflows: args
+            .workflows
+            .as_deref()
+            .map(workflows::load)
+            .transpose()?
+            .unwrap_or_default(),
+        audit_log: args.audit_log,
+        audit_redact_fields: args.audit_redact_fields,
+        redact_fields: if args.no_redact {
+            Vec::new()
+        } else {
+            args.redact_fields
+        },
+        org_id: args.org_id,
+        max_response_bytes: Some(args.max_response_bytes),
+        confirm_tools: args.confirm_tools,
+        allow_methods: args.allow_methods,
+        deny_methods: args.deny_methods,
+        max_concurrency: args.max_concurrency,
+        client,
+        ..Default::default()
+    };
+    let service = PangolinService::new(spec, args.api_key, args.base_url, config)
         .context("Failed to create Pangolin service")?;
 
+    if args.print_tools {
+        println!("{}", serde_json::to_string_pretty(&service.tools())?);
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    reload_on_sighup(reload_args, service.clone())?;
+
     // Start the stdio transport
     info!("Starting stdio transport...");
     let server = service
@@ -99,8 +723,14 @@ async fn main() -> Result<()> {
         .await
         .context("Failed to start MCP server")?;
 
-    // Wait for the server to complete
-    server.waiting().await?;
+    // Wait for the server to complete, or stop it on SIGINT/SIGTERM
+    let cancel = server.cancellation_token();
+    let (quit, _) =
+        shutdown::run_until_shutdown(server.waiting(), shutdown::termination_signal(), || {
+            cancel.cancel()
+        })
+        .await;
+    quit?;
 
     info!("MCP server stopped");
     Ok(())
@@ -0,0 +1,190 @@
+//! Forwarding of tracing events to the MCP client as `notifications/message`
+//!
+//! Most MCP clients hide the server's stderr, so once a client sets a level
+//! with `logging/setLevel`, events from this crate at or above that level are
+//! also sent over the protocol. Known secrets are masked before sending.
+
+use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::service::{Peer, RoleServer};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Placeholder sent in place of secret values
+const REDACTED: &str = "[REDACTED]";
+
+/// Field names whose values are never forwarded
+const SECRET_FIELDS: &[&str] = &["api_key", "authorization", "token", "password", "secret"];
+
+/// Connection between the tracing layer and the MCP client
+#[derive(Default)]
+pub struct LogForwarder {
+    peer: Mutex<Option<Peer<RoleServer>>>,
+    /// Minimum level to forward; nothing is sent until the client sets one
+    level: Mutex<Option<LoggingLevel>>,
+    /// Values masked wherever they appear in forwarded messages
+    secrets: Mutex<Vec<String>>,
+}
+
+impl fmt::Debug for LogForwarder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogForwarder")
+            .field("level", &self.level.lock().unwrap())
+            .finish_non_exhaustive()
+    }
+}
+
+impl LogForwarder {
+    /// Client that receives forwarded events
+    pub fn set_peer(&self, peer: Peer<RoleServer>) {
+        *self.peer.lock().unwrap() = Some(peer);
+    }
+
+    /// Minimum level of forwarded events
+    pub fn set_level(&self, level: LoggingLevel) {
+        *self.level.lock().unwrap() = Some(level);
+    }
+
+    /// Mask a value (e.g. the API key) in every forwarded message
+    pub fn add_secret(&self, secret: &str) {
+        let mut secrets = self.secrets.lock().unwrap();
+        if !secret.is_empty() && !secrets.iter().any(|s| s == secret) {
+            secrets.push(secret.to_string());
+        }
+    }
+
+    /// Tracing layer feeding this forwarder
+    #[allow(dead_code)]
+    pub fn layer(self: &Arc<Self>) -> McpLogLayer {
+        McpLogLayer {
+            forwarder: self.clone(),
+        }
+    }
+
+    fn redact(&self, text: &str) -> String {
+        self.secrets
+            .lock()
+            .unwrap()
+            .iter()
+            .fold(text.to_string(), |text, secret| {
+                text.replace(secret.as_str(), REDACTED)
+            })
+    }
+}
+
+/// Tracing layer sending this crate's events to the MCP client
+pub struct McpLogLayer {
+    forwarder: Arc<LogForwarder>,
+}
+
+impl<S: Subscriber> Layer<S> for McpLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        // Dependencies (rmcp itself in particular) are never forwarded, which
+        // also keeps sending a notification from producing more events
+        let target = event.metadata().target();
+        if !target.starts_with(env!("CARGO_CRATE_NAME")) {
+            return;
+        }
+
+        let level = mcp_level(event.metadata().level());
+        match *self.forwarder.level.lock().unwrap() {
+            Some(min) if severity(level) >= severity(min) => {}
+            _ => return,
+        }
+        let Some(peer) = self.forwarder.peer.lock().unwrap().clone() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut data = serde_json::Map::new();
+        data.insert(
+            "message".to_string(),
+            serde_json::Value::String(self.forwarder.redact(&visitor.message)),
+        );
+        for (name, value) in visitor.fields {
+            let value = if SECRET_FIELDS.iter().any(|s| name.eq_ignore_ascii_case(s)) {
+                REDACTED.into()
+            } else if let serde_json::Value::String(text) = value {
+                self.forwarder.redact(&text).into()
+            } else {
+                value
+            };
+            data.insert(name, value);
+        }
+
+        let notification = LoggingMessageNotificationParam {
+            level,
+            logger: Some(target.to_string()),
+            data: serde_json::Value::Object(data),
+        };
+        runtime.spawn(async move {
+            let _ = peer.notify_logging_message(notification).await;
+        });
+    }
+}
+
+/// Collects an event's message and fields
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(String, serde_json::Value)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.push((field.name().to_string(), value.into()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.push((field.name().to_string(), value.into()));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.push((field.name().to_string(), value.into()));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name().to_string(), value.into()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields
+                .push((field.name().to_string(), format!("{:?}", value).into()));
+        }
+    }
+}
+
+fn mcp_level(level: &Level) -> LoggingLevel {
+    match *level {
+        Level::ERROR => LoggingLevel::Error,
+        Level::WARN => LoggingLevel::Warning,
+        Level::INFO => LoggingLevel::Info,
+        _ => LoggingLevel::Debug,
+    }
+}
+
+fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
@@ -0,0 +1,299 @@
+//! Minimal OpenAPI Overlay support
+//!
+//! Applies the `actions` of an [OpenAPI Overlay](https://spec.openapis.org/overlay/v1.0.0.html)
+//! document to a raw spec. Each action selects nodes with a JSONPath `target`
+//! and either deep-merges an `update` value into them or removes them.
+//!
+//! Only the JSONPath subset needed to address spec nodes is supported:
+//! `$`, `.name`, `['name']`, `[index]` and the `*` / `[*]` wildcards.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// A single JSONPath segment
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/// A concrete step from the root to a selected node
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+/// A parsed JSONPath expression, also used to filter tool results
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath(Vec<Segment>);
+
+#[allow(dead_code)]
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        parse_path(path).map(Self)
+    }
+
+    /// Selects at most one node (no wildcards)
+    pub fn is_singular(&self) -> bool {
+        !self.0.contains(&Segment::Wildcard)
+    }
+
+    /// Every node the expression selects, in document order
+    pub fn select<'a>(&self, doc: &'a Value) -> Vec<&'a Value> {
+        select(doc, &self.0)
+            .iter()
+            .filter_map(|steps| {
+                steps.iter().try_fold(doc, |node, step| match step {
+                    Step::Key(key) => node.get(key.as_str()),
+                    Step::Index(index) => node.get(*index),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Apply all overlay actions to the document, returning the number of nodes touched
+pub fn apply_overlay(doc: &mut Value, overlay: &Value) -> Result<usize> {
+    let actions = overlay
+        .get("actions")
+        .and_then(|a| a.as_array())
+        .context("Overlay must contain an 'actions' array")?;
+
+    let mut touched = 0;
+
+    for (i, action) in actions.iter().enumerate() {
+        let target = action
+            .get("target")
+            .and_then(|t| t.as_str())
+            .with_context(|| format!("Overlay action #{} has no 'target'", i))?;
+        let segments = parse_path(target)
+            .with_context(|| format!("Invalid target in overlay action #{}", i))?;
+        let mut matches = select(doc, &segments);
+
+        if action.get("remove").and_then(|r| r.as_bool()) == Some(true) {
+            // Remove deepest/last matches first so array indices stay valid
+            matches.reverse();
+            for steps in &matches {
+                remove_node(doc, steps);
+            }
+        } else if let Some(update) = action.get("update") {
+            for steps in &matches {
+                if let Some(node) = node_mut(doc, steps) {
+                    merge(node, update);
+                }
+            }
+        }
+
+        touched += matches.len();
+    }
+
+    Ok(touched)
+}
+
+/// Parse a JSONPath expression into segments
+fn parse_path(path: &str) -> Result<Vec<Segment>> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .context("JSONPath must start with '$'")?;
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' => {
+                i += 1;
+                let start = i;
+                while i < chars.len() && chars[i] != '.' && chars[i] != '[' {
+                    i += 1;
+                }
+                let name: String = chars[start..i].iter().collect();
+                match name.as_str() {
+                    "" => anyhow::bail!("Empty name in JSONPath '{}'", path),
+                    "*" => segments.push(Segment::Wildcard),
+                    _ => segments.push(Segment::Key(name)),
+                }
+            }
+            '[' => {
+                let end = (i..chars.len())
+                    .find(|&j| chars[j] == ']' && !in_quotes(&chars[i + 1..j]))
+                    .with_context(|| format!("Unclosed '[' in JSONPath '{}'", path))?;
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = inner.trim();
+                if inner == "*" {
+                    segments.push(Segment::Wildcard);
+                } else if let Some(name) = unquote(inner) {
+                    segments.push(Segment::Key(name));
+                } else if let Ok(index) = inner.parse::<usize>() {
+                    segments.push(Segment::Index(index));
+                } else {
+                    anyhow::bail!("Unsupported JSONPath selector '[{}]'", inner);
+                }
+                i = end + 1;
+            }
+            c => anyhow::bail!("Unexpected '{}' in JSONPath '{}'", c, path),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Whether a bracket body is still inside an open quoted string
+fn in_quotes(chars: &[char]) -> bool {
+    let quote = match chars.iter().find(|c| !c.is_whitespace()) {
+        Some(q @ ('\'' | '"')) => *q,
+        _ => return false,
+    };
+    chars.iter().filter(|&&c| c == quote).count() % 2 == 1
+}
+
+fn unquote(s: &str) -> Option<String> {
+    ['\'', '"'].iter().find_map(|q| {
+        s.strip_prefix(*q)
+            .and_then(|s| s.strip_suffix(*q))
+            .map(String::from)
+    })
+}
+
+/// Resolve segments to the concrete step lists of every matching node
+fn select(doc: &Value, segments: &[Segment]) -> Vec<Vec<Step>> {
+    let mut current: Vec<(Vec<Step>, &Value)> = vec![(Vec::new(), doc)];
+
+    for segment in segments {
+        let mut next = Vec::new();
+        for (steps, node) in current {
+            match (segment, node) {
+                (Segment::Key(key), Value::Object(map)) => {
+                    if let Some(child) = map.get(key) {
+                        next.push((with_step(&steps, Step::Key(key.clone())), child));
+                    }
+                }
+                (Segment::Index(index), Value::Array(items)) => {
+                    if let Some(child) = items.get(*index) {
+                        next.push((with_step(&steps, Step::Index(*index)), child));
+                    }
+                }
+                (Segment::Wildcard, Value::Object(map)) => {
+                    for (key, child) in map {
+                        next.push((with_step(&steps, Step::Key(key.clone())), child));
+                    }
+                }
+                (Segment::Wildcard, Value::Array(items)) => {
+                    for (index, child) in items.iter().enumerate() {
+                        next.push((with_step(&steps, Step::Index(index)), child));
+                    }
+                }
+                _ => {}
+            }
+        }
+        current = next;
+    }
+
+    current.into_iter().map(|(steps, _)| steps).collect()
+}
+
+fn with_step(steps: &[Step], step: Step) -> Vec<Step> {
+    let mut steps = steps.to_vec();
+    steps.push(step);
+    steps
+}
+
+fn node_mut<'a>(doc: &'a mut Value, steps: &[Step]) -> Option<&'a mut Value> {
+    steps.iter().try_fold(doc, |node, step| match step {
+        Step::Key(key) => node.get_mut(key.as_str()),
+        Step::Index(index) => node.get_mut(*index),
+    })
+}
+
+fn remove_node(doc: &mut Value, steps: &[Step]) {
+    let Some((last, parent_steps)) = steps.split_last() else {
+        return;
+    };
+    match (node_mut(doc, parent_steps), last) {
+        (Some(Value::Object(map)), Step::Key(key)) => {
+            map.shift_remove(key);
+        }
+        (Some(Value::Array(items)), Step::Index(index)) if *index < items.len() => {
+            items.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+/// Deep-merge an update into a node: objects merge recursively, arrays
+/// are appended to, and anything else is replaced
+fn merge(node: &mut Value, update: &Value) {
+    match (node, update) {
+        (Value::Object(target), Value::Object(source)) => {
+            for (key, value) in source {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(target), Value::Array(source)) => {
+            target.extend(source.iter().cloned());
+        }
+        (Value::Array(target), value) => target.push(value.clone()),
+        (node, value) => *node = value.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("$.paths['/org/{orgId}'].get").unwrap(),
+            vec![
+                Segment::Key("paths".to_string()),
+                Segment::Key("/org/{orgId}".to_string()),
+                Segment::Key("get".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_path("$.paths.*[\"delete\"].parameters[0]").unwrap(),
+            vec![
+                Segment::Key("paths".to_string()),
+                Segment::Wildcard,
+                Segment::Key("delete".to_string()),
+                Segment::Key("parameters".to_string()),
+                Segment::Index(0),
+            ]
+        );
+        assert!(parse_path("paths.get").is_err());
+        assert!(parse_path("$.paths[?(@.get)]").is_err());
+    }
+
+    #[test]
+    fn test_apply_overlay_update_and_remove() {
+        let mut doc = json!({
+            "paths": {
+                "/org": {"put": {"description": "Create"}, "get": {"description": "List"}},
+                "/site": {"delete": {"description": "Delete"}}
+            }
+        });
+        let overlay = json!({
+            "overlay": "1.0.0",
+            "actions": [
+                {"target": "$.paths['/org'].get", "update": {"description": "Updated", "tags": ["Org"]}},
+                {"target": "$.paths.*.delete", "remove": true}
+            ]
+        });
+
+        let touched = apply_overlay(&mut doc, &overlay).unwrap();
+        assert_eq!(touched, 2);
+        assert_eq!(doc["paths"]["/org"]["get"]["description"], "Updated");
+        assert_eq!(doc["paths"]["/org"]["get"]["tags"], json!(["Org"]));
+        assert!(doc["paths"]["/site"].get("delete").is_none());
+    }
+}
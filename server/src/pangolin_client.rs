@@ -1,40 +1,379 @@
 //! HTTP client for making Pangolin API calls
 
 use anyhow::{Context, Result};
-use reqwest::Client;
+use base64::Engine;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, NoProxy, Proxy, Request, Response, StatusCode};
 use std::collections::HashMap;
-use tracing::debug;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 use url::Url;
 
-use crate::swagger::build_url;
+use crate::swagger::{build_url, join_base_path};
 use crate::types::HttpMethod;
 
+/// Placeholder logged in place of secret header values
+const REDACTED: &str = "[REDACTED]";
+
+/// User-Agent sent unless overridden
+pub const DEFAULT_USER_AGENT: &str = concat!("mcp-pangolin/", env!("CARGO_PKG_VERSION"));
+
+/// Response headers read as the upstream request ID, in order of preference
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-correlation-id", "request-id"];
+
+/// Successful answer of the Pangolin API
+#[derive(Debug, Clone)]
+pub struct ApiResponse {
+    /// HTTP status code
+    pub status: u16,
+    #[allow(dead_code)]
+    pub method: HttpMethod,
+    /// Path of the request URL, e.g. "/v1/site/42"
+    pub path: String,
+    /// Parsed body; empty bodies are described instead (`empty_success`)
+    pub body: serde_json::Value,
+    /// Time from sending the request to reading the whole response
+    #[allow(dead_code)]
+    pub elapsed: Duration,
+    /// Requests sent, counting the retry after an API key refresh
+    pub attempts: u32,
+}
+
+/// Non-2xx answer of the Pangolin API, kept typed so the status and body
+/// reach the service layer
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Pangolin API error ({status}): {message}")]
+pub struct PangolinApiError {
+    pub status: StatusCode,
+    pub method: HttpMethod,
+    /// Request path with path parameters filled in
+    pub path: String,
+    /// `message` or `error` of a JSON body, else the body text
+    pub message: String,
+    /// Parsed body when JSON
+    pub details: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+    /// Time from sending the request to reading the whole response
+    pub elapsed: Duration,
+    /// Requests sent, counting the retry after an API key refresh
+    pub attempts: u32,
+}
+
+impl PangolinApiError {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "status": self.status.as_u16(),
+            "method": self.method.as_str(),
+            "path": self.path,
+            "message": self.message,
+            "details": self.details,
+            "request_id": self.request_id,
+        })
+    }
+}
+
+/// Call refused without being sent because the circuit breaker is open
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "Pangolin API unavailable: circuit breaker open after {failures} consecutive failures, retrying in {retry_in_secs}s"
+)]
+pub struct BreakerOpen {
+    pub failures: u32,
+    pub retry_in_secs: u64,
+}
+
+/// Options for the Pangolin HTTP client
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Name of the header carrying the API key
+    pub auth_header: String,
+    /// Log full request/response details (headers and bodies) at debug level
+    pub trace_http: bool,
+    /// User-Agent header sent with every request
+    pub user_agent: String,
+    /// Prefix prepended to every endpoint path (e.g. "/integration")
+    pub base_path: String,
+    /// Consecutive upstream failures that open the circuit breaker (0 disables it)
+    pub breaker_threshold: u32,
+    /// Window within which failures must occur to count as consecutive
+    pub breaker_window: Duration,
+    /// How long calls fail fast once the breaker is open
+    pub breaker_cooldown: Duration,
+    /// PEM file with an extra root certificate to trust (private CA, self-signed)
+    pub ca_cert: Option<PathBuf>,
+    /// Skip TLS certificate verification entirely
+    pub accept_invalid_certs: bool,
+    /// Proxy for every request, overriding `HTTPS_PROXY`/`HTTP_PROXY`
+    /// (`NO_PROXY` still applies)
+    pub proxy: Option<String>,
+    /// Shell command printing the API key, re-run once the key is older than
+    /// `api_key_ttl` or rejected with 401
+    pub api_key_command: Option<String>,
+    /// How long a key printed by `api_key_command` is reused
+    pub api_key_ttl: Duration,
+    /// Told of every key `api_key_command` prints, e.g. to mask it in logs
+    pub key_observer: Option<KeyObserver>,
+    /// Wrap successful responses as `{"_status": <code>, "data": <body>}` so
+    /// callers can tell 200 from 202 or 204
+    pub include_status: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            auth_header: "Authorization".to_string(),
+            trace_http: false,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            base_path: String::new(),
+            breaker_threshold: 5,
+            breaker_window: Duration::from_secs(60),
+            breaker_cooldown: Duration::from_secs(30),
+            ca_cert: None,
+            accept_invalid_certs: false,
+            proxy: None,
+            api_key_command: None,
+            api_key_ttl: Duration::from_secs(300),
+            key_observer: None,
+            include_status: false,
+        }
+    }
+}
+
+/// Callback receiving each API key obtained from `api_key_command`
+#[derive(Clone)]
+pub struct KeyObserver(pub Arc<dyn Fn(&str) + Send + Sync>);
+
+impl fmt::Debug for KeyObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyObserver")
+    }
+}
+
+/// Body standing in for an empty successful response, saying what happened,
+/// e.g. "Deleted site 42 (HTTP 204)", so the call is not retried to make sure
+fn empty_success(
+    method: HttpMethod,
+    path: &str,
+    path_params: &HashMap<String, String>,
+    request_path: &str,
+    status: u16,
+) -> serde_json::Value {
+    let verb = match method {
+        HttpMethod::Get => "Fetched",
+        HttpMethod::Delete => "Deleted",
+        _ if status == 201 => "Created",
+        _ => "Updated",
+    };
+
+    // The last path segment names the resource: "/site/{siteId}" is "site 42",
+    // "/org/{orgId}/site" is "site"
+    let mut segments = path.split('/').filter(|s| !s.is_empty()).rev();
+    let target = match segments.next() {
+        Some(last) => match last.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(name) => {
+                let value = path_params.get(name).map_or(last, String::as_str);
+                match segments.next() {
+                    Some(kind) if !kind.starts_with('{') => format!("{} {}", kind, value),
+                    _ => value.to_string(),
+                }
+            }
+            None => last.to_string(),
+        },
+        None => request_path.to_string(),
+    };
+
+    serde_json::json!({
+        "status": "success",
+        "httpStatus": status,
+        "method": method.as_str(),
+        "path": request_path,
+        "message": format!("{} {} (HTTP {})", verb, target, status),
+    })
+}
+
+/// Media types whose body is returned as text rather than base64
+fn is_textual(media_type: &str) -> bool {
+    media_type.starts_with("text/")
+        || [
+            "json",
+            "xml",
+            "yaml",
+            "csv",
+            "javascript",
+            "x-www-form-urlencoded",
+        ]
+        .iter()
+        .any(|kind| media_type.contains(kind))
+}
+
+/// Circuit breaker state shared by all clones of a client
+#[derive(Debug, Default)]
+struct BreakerState {
+    /// Consecutive failures in the current window
+    failures: u32,
+    /// When the first failure of the current window happened
+    window_start: Option<Instant>,
+    /// When the breaker opened; `None` while closed
+    opened_at: Option<Instant>,
+    /// A trial call is in flight after the cooldown
+    half_open: bool,
+}
+
+/// Admission of one call by the circuit breaker; a half-open trial that is
+/// dropped without an outcome (cancelled, or failed before reaching the API)
+/// frees the trial slot so the next call can try again
+struct BreakerTrial<'a> {
+    client: &'a PangolinClient,
+    /// This call is the half-open trial and its outcome is not recorded yet
+    pending: bool,
+}
+
+impl BreakerTrial<'_> {
+    /// Record the outcome of the call (network errors and 5xx are failures)
+    fn record(&mut self, success: bool) {
+        self.pending = false;
+        self.client.breaker_record(success);
+    }
+}
+
+impl Drop for BreakerTrial<'_> {
+    fn drop(&mut self) {
+        if self.pending {
+            debug!("Circuit breaker trial ended without an outcome, allowing another");
+            self.client.breaker.lock().unwrap().half_open = false;
+        }
+    }
+}
+
+/// HTTP client with the timeout, User-Agent, TLS and proxy options of
+/// `config`, for requests to `target`
+pub fn build_http_client(config: &ClientConfig, target: &str) -> Result<Client> {
+    let mut builder = Client::builder()
+        .timeout(std::time::Duration::from_secs(60))
+        .user_agent(&config.user_agent);
+
+    if let Some(path) = &config.ca_cert {
+        let pem = std::fs::read(path)
+            .with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+        let cert = Certificate::from_pem(&pem)
+            .with_context(|| format!("Invalid CA certificate {}", path.display()))?;
+        builder = builder.add_root_certificate(cert);
+    }
+
+    // Without an explicit proxy, reqwest honours HTTP(S)_PROXY and NO_PROXY
+    if let Some(url) = &config.proxy {
+        let proxy = Proxy::all(url)
+            .with_context(|| format!("Invalid proxy URL {}", url))?
+            .no_proxy(NoProxy::from_env());
+        builder = builder.proxy(proxy);
+    }
+
+    if config.accept_invalid_certs {
+        warn!(
+            "TLS certificate verification is DISABLED: connections to {} can be intercepted",
+            target
+        );
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder.build().context("Failed to build HTTP client")
+}
+
+/// Run `api_key_command` and return the API key it prints
+pub async fn run_key_command(command: &str) -> Result<String> {
+    debug!("Running --api-key-command to obtain an API key");
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .await
+        .context("Failed to run --api-key-command")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "--api-key-command failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let value = String::from_utf8(output.stdout)
+        .context("--api-key-command printed invalid UTF-8")?
+        .trim()
+        .to_string();
+    if value.is_empty() {
+        anyhow::bail!("--api-key-command printed no API key");
+    }
+    Ok(value)
+}
+
+/// API key in use, with when `api_key_command` last produced it
+#[derive(Debug, Default)]
+struct ApiKey {
+    value: String,
+    fetched_at: Option<Instant>,
+}
+
 /// HTTP client for making Pangolin API calls
 #[derive(Debug, Clone)]
 pub struct PangolinClient {
     client: Client,
     base_url: String,
-    api_key: String,
+    /// Held across a refresh so concurrent calls run the command only once
+    api_key: Arc<tokio::sync::Mutex<ApiKey>>,
+    config: ClientConfig,
+    breaker: Arc<Mutex<BreakerState>>,
 }
 
 impl PangolinClient {
     /// Create a new Pangolin client
-    pub fn new(base_url: &str, api_key: String) -> Result<Self> {
+    pub fn new(base_url: &str, api_key: String, config: ClientConfig) -> Result<Self> {
         // Validate the URL
         Url::parse(base_url).context("Invalid base URL")?;
 
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
-            .build()
-            .context("Failed to build HTTP client")?;
+        let client = build_http_client(&config, base_url)?;
 
         Ok(Self {
             client,
             base_url: base_url.to_string(),
-            api_key,
+            api_key: Arc::new(tokio::sync::Mutex::new(ApiKey {
+                value: api_key,
+                fetched_at: None,
+            })),
+            config,
+            breaker: Arc::default(),
+        })
+    }
+
+    /// The same client sending to another server (`_base_url`); the key and
+    /// connection pool are shared, the circuit breaker is not
+    #[allow(dead_code)]
+    pub fn with_base_url(&self, base_url: &str) -> Result<Self> {
+        let url = Url::parse(base_url).context("Invalid base URL")?;
+        if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+            anyhow::bail!("Base URL must be an http(s) URL with a host");
+        }
+        Ok(Self {
+            base_url: base_url.to_string(),
+            breaker: Arc::default(),
+            ..self.clone()
         })
     }
 
+    /// Full URL of an endpoint with its path parameters filled in
+    pub fn url(&self, path: &str, path_params: &HashMap<String, String>) -> String {
+        let path = join_base_path(&self.config.base_path, path);
+        build_url(&self.base_url, &path, path_params)
+    }
+
+    /// Path of `url` below the server origin, e.g. "/v1/org/acme"
+    #[allow(dead_code)]
+    pub fn path(&self, path: &str, path_params: &HashMap<String, String>) -> String {
+        let url = self.url(path, path_params);
+        Url::parse(&url).map_or(url, |url| url.path().to_string())
+    }
+
     /// Call a Pangolin API endpoint
     pub async fn call(
         &self,
@@ -43,13 +382,200 @@ impl PangolinClient {
         path_params: HashMap<String, String>,
         query_params: HashMap<String, String>,
         body: Option<serde_json::Value>,
+        content_type: &str,
+    ) -> Result<ApiResponse> {
+        let request = self.build_request(
+            method,
+            path,
+            &path_params,
+            &query_params,
+            body,
+            content_type,
+        )?;
+        debug!(
+            "Calling Pangolin API: {} {}",
+            method.as_str(),
+            request.url()
+        );
+
+        // Send the request, unless the breaker is open; a rejected key from
+        // --api-key-command is refreshed and the request retried once
+        let mut trial = self.breaker_admit()?;
+        let started = Instant::now();
+        let mut attempts = 1;
+        let mut response = self.send(&request, false, &mut trial).await?;
+        if response.status() == StatusCode::UNAUTHORIZED && self.config.api_key_command.is_some() {
+            warn!("Pangolin API rejected the API key, refreshing it with --api-key-command");
+            attempts += 1;
+            response = self.send(&request, true, &mut trial).await?;
+        }
+
+        let status = response.status();
+        trial.record(!status.is_server_error());
+        let response_headers = response.headers().clone();
+        let response_type = response_headers
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_lowercase());
+        let bytes = response.bytes().await.context("Failed to read response")?;
+        let elapsed = started.elapsed();
+        let binary = match response_type.as_deref() {
+            Some(media_type) => !is_textual(media_type),
+            None => std::str::from_utf8(&bytes).is_err(),
+        };
+        let text = if binary {
+            String::new()
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+
+        debug!(
+            status = status.as_u16(),
+            "Response status: {}, body length: {}",
+            status,
+            bytes.len()
+        );
+
+        if self.config.trace_http {
+            debug!(
+                "HTTP response: {}\n  headers: {}\n  body: {}",
+                status,
+                self.redact_headers(&response_headers),
+                if binary {
+                    format!("<{} bytes of binary data>", bytes.len())
+                } else {
+                    text.clone()
+                }
+            );
+        }
+
+        if !status.is_success() {
+            // Try to parse error response as JSON for better error messages
+            let details = serde_json::from_str::<serde_json::Value>(&text).ok();
+            let message = details
+                .as_ref()
+                .and_then(|v| {
+                    v.get("message")
+                        .or_else(|| v.get("error"))
+                        .and_then(|m| m.as_str())
+                        .map(|s| s.to_string())
+                })
+                .unwrap_or(text.clone());
+            let request_id = REQUEST_ID_HEADERS.iter().find_map(|name| {
+                response_headers
+                    .get(*name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+            });
+
+            return Err(PangolinApiError {
+                status,
+                method,
+                path: request.url().path().to_string(),
+                message,
+                details,
+                request_id,
+                elapsed,
+                attempts,
+            }
+            .into());
+        }
+
+        // JSON (or untyped) bodies are parsed, falling back to a string; other
+        // text types stay text and binary types are base64-encoded
+        let request_path = request.url().path().to_string();
+        let json: serde_json::Value = if bytes.is_empty() {
+            empty_success(method, path, &path_params, &request_path, status.as_u16())
+        } else if binary {
+            let media_type = response_type
+                .as_deref()
+                .unwrap_or("application/octet-stream");
+            serde_json::json!({
+                "contentType": media_type,
+                "encoding": "base64",
+                "data": base64::engine::general_purpose::STANDARD.encode(&bytes),
+                "note": format!("Binary {} response ({} bytes), base64-encoded", media_type, bytes.len()),
+            })
+        } else if response_type.as_deref().is_none_or(|t| t.contains("json")) {
+            serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text))
+        } else {
+            serde_json::Value::String(text)
+        };
+
+        let body = if self.config.include_status {
+            serde_json::json!({
+                "_status": status.as_u16(),
+                "data": json,
+            })
+        } else {
+            json
+        };
+        Ok(ApiResponse {
+            status: status.as_u16(),
+            method,
+            path: request_path,
+            body,
+            elapsed,
+            attempts,
+        })
+    }
+
+    /// The request `call` would send, rendered as JSON (method, URL with query
+    /// string, headers with the API key masked, body) without sending it
+    #[allow(dead_code)]
+    pub fn preview(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        path_params: HashMap<String, String>,
+        query_params: HashMap<String, String>,
+        body: Option<serde_json::Value>,
+        content_type: &str,
     ) -> Result<serde_json::Value> {
-        // Build the URL with path parameters
-        let url = build_url(&self.base_url, path, &path_params);
+        let request = self.build_request(
+            method,
+            path,
+            &path_params,
+            &query_params,
+            body,
+            content_type,
+        )?;
+
+        let mut headers = serde_json::Map::new();
+        headers.insert(self.config.auth_header.clone(), REDACTED.into());
+        for (name, value) in request.headers() {
+            headers.insert(
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes())
+                    .into_owned()
+                    .into(),
+            );
+        }
+        let body = request.body().and_then(|b| b.as_bytes()).map(|bytes| {
+            let text = String::from_utf8_lossy(bytes);
+            serde_json::from_str(&text).unwrap_or_else(|_| serde_json::Value::String(text.into()))
+        });
 
-        debug!("Calling Pangolin API: {} {}", method.as_str(), url);
+        Ok(serde_json::json!({
+            "method": method.as_str(),
+            "url": request.url().as_str(),
+            "headers": headers,
+            "body": body,
+        }))
+    }
+
+    /// Build a request (without the API key, which `send` adds)
+    fn build_request(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        path_params: &HashMap<String, String>,
+        query_params: &HashMap<String, String>,
+        body: Option<serde_json::Value>,
+        content_type: &str,
+    ) -> Result<Request> {
+        let url = self.url(path, path_params);
 
-        // Build the request
         let mut request = match method {
             HttpMethod::Get => self.client.get(&url),
             HttpMethod::Post => self.client.post(&url),
@@ -58,54 +584,198 @@ impl PangolinClient {
             HttpMethod::Patch => self.client.patch(&url),
         };
 
-        // Add Bearer token authentication
-        request = request.header("Authorization", format!("Bearer {}", self.api_key));
-
         // Add query parameters
         if !query_params.is_empty() {
-            request = request.query(&query_params);
+            request = request.query(query_params);
         }
 
-        // Add JSON body if present
+        // Add the body if present, encoded for the endpoint's content type
         if let Some(body) = body {
-            request = request
-                .header("Content-Type", "application/json")
-                .json(&body);
+            request = request.header("Content-Type", content_type);
+            request = if content_type == "application/x-www-form-urlencoded" {
+                request.form(&form_fields(&body))
+            } else if content_type.contains("json") {
+                request.body(body.to_string())
+            } else {
+                match body {
+                    serde_json::Value::String(text) => request.body(text),
+                    other => request.body(other.to_string()),
+                }
+            };
         }
 
-        // Send the request
-        let response = request
-            .send()
-            .await
-            .context("Failed to send request to Pangolin API")?;
+        request.build().context("Failed to build request")
+    }
 
-        let status = response.status();
-        let text = response.text().await.context("Failed to read response")?;
+    /// Send a copy of the request with the current (or a refreshed) API key
+    async fn send(
+        &self,
+        request: &Request,
+        refresh_key: bool,
+        trial: &mut BreakerTrial<'_>,
+    ) -> Result<Response> {
+        let mut request = request.try_clone().context("Failed to build request")?;
+        let api_key = self.api_key(refresh_key).await?;
+        let name = HeaderName::from_bytes(self.config.auth_header.as_bytes())
+            .context("Invalid auth header name")?;
+        let value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .context("API key is not a valid header value")?;
+        request.headers_mut().insert(name, value);
 
-        debug!("Response status: {}, body length: {}", status, text.len());
+        if self.config.trace_http {
+            debug!(
+                "HTTP request: {} {}\n  headers: {}\n  body: {}",
+                request.method(),
+                request.url(),
+                self.redact_headers(request.headers()),
+                request
+                    .body()
+                    .and_then(|b| b.as_bytes())
+                    .map(String::from_utf8_lossy)
+                    .unwrap_or_default()
+            );
+        }
 
-        if !status.is_success() {
-            // Try to parse error response as JSON for better error messages
-            let error_msg = serde_json::from_str::<serde_json::Value>(&text)
-                .ok()
-                .and_then(|v| {
-                    v.get("message")
-                        .or_else(|| v.get("error"))
-                        .and_then(|m| m.as_str())
-                        .map(|s| s.to_string())
-                })
-                .unwrap_or(text.clone());
+        match self.client.execute(request).await {
+            Ok(response) => Ok(response),
+            Err(e) => {
+                trial.record(false);
+                Err(e).context("Failed to send request to Pangolin API")
+            }
+        }
+    }
 
-            anyhow::bail!("Pangolin API error ({}): {}", status, error_msg);
+    /// Current API key, running `api_key_command` when there is none yet, it
+    /// expired or `refresh` is set
+    async fn api_key(&self, refresh: bool) -> Result<String> {
+        let mut key = self.api_key.lock().await;
+        let Some(command) = &self.config.api_key_command else {
+            return Ok(key.value.clone());
+        };
+
+        let fresh = key
+            .fetched_at
+            .is_some_and(|at| at.elapsed() < self.config.api_key_ttl);
+        if fresh && !refresh {
+            return Ok(key.value.clone());
         }
 
-        // Try to parse as JSON, fallback to string value
-        let json: serde_json::Value = if text.is_empty() {
-            serde_json::json!({"status": "success"})
-        } else {
-            serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text))
+        let value = run_key_command(command).await?;
+        if let Some(observer) = &self.config.key_observer {
+            (observer.0)(&value);
+        }
+
+        *key = ApiKey {
+            value: value.clone(),
+            fetched_at: Some(Instant::now()),
+        };
+        Ok(value)
+    }
+
+    /// Fail fast while the breaker is open; let a single trial call through once
+    /// the cooldown has elapsed
+    fn breaker_admit(&self) -> Result<BreakerTrial<'_>> {
+        let admitted = |pending| BreakerTrial {
+            client: self,
+            pending,
         };
+        if self.config.breaker_threshold == 0 {
+            return Ok(admitted(false));
+        }
+
+        let mut state = self.breaker.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(admitted(false));
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed >= self.config.breaker_cooldown && !state.half_open {
+            debug!("Circuit breaker half-open, sending a trial request");
+            state.half_open = true;
+            return Ok(admitted(true));
+        }
+
+        let retry_in = self.config.breaker_cooldown.saturating_sub(elapsed);
+        Err(BreakerOpen {
+            failures: state.failures,
+            retry_in_secs: retry_in.as_secs().max(1),
+        }
+        .into())
+    }
+
+    /// Record the outcome of an upstream call (network errors and 5xx are failures)
+    fn breaker_record(&self, success: bool) {
+        if self.config.breaker_threshold == 0 {
+            return;
+        }
+
+        let mut state = self.breaker.lock().unwrap();
+        if success {
+            if state.opened_at.is_some() {
+                warn!("Pangolin API recovered, closing circuit breaker");
+            }
+            *state = BreakerState::default();
+            return;
+        }
+
+        if state.half_open {
+            // Trial call failed: stay open for another cooldown
+            state.half_open = false;
+            state.opened_at = Some(Instant::now());
+            return;
+        }
 
-        Ok(json)
+        let in_window = state
+            .window_start
+            .is_some_and(|start| start.elapsed() <= self.config.breaker_window);
+        if in_window {
+            state.failures += 1;
+        } else {
+            state.failures = 1;
+            state.window_start = Some(Instant::now());
+        }
+
+        if state.failures >= self.config.breaker_threshold && state.opened_at.is_none() {
+            warn!(
+                "Pangolin API failed {} times in a row, opening circuit breaker for {}s",
+                state.failures,
+                self.config.breaker_cooldown.as_secs()
+            );
+            state.opened_at = Some(Instant::now());
+        }
+    }
+
+    /// Render headers for logging, masking the auth header's value
+    fn redact_headers(&self, headers: &HeaderMap) -> String {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if name.as_str().eq_ignore_ascii_case(&self.config.auth_header) {
+                    REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                format!("{}: {}", name, value)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 }
+
+/// Flatten a JSON object body into form fields (nested values are sent as JSON)
+fn form_fields(body: &serde_json::Value) -> Vec<(String, String)> {
+    let Some(object) = body.as_object() else {
+        return Vec::new();
+    };
+
+    object
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
@@ -0,0 +1,293 @@
+//! Workflow prompts guiding an agent through multi-step Pangolin tasks
+//!
+//! Prompt templates reference endpoints as `[[METHOD /path]]` and arguments
+//! as `{{name}}`; both are substituted when the prompt is rendered, so tool
+//! names always match the ones this server exposes. The getting-started
+//! prompt is generated from the loaded spec instead.
+
+use rmcp::model::{JsonObject, Prompt, PromptArgument};
+use std::fmt::Write;
+
+use crate::types::PangolinEndpoint;
+
+/// Name of the overview prompt generated from the loaded spec
+pub const GETTING_STARTED: &str = "pangolin_getting_started";
+
+/// Tool names listed per tag before the rest are summarized
+const TOOLS_PER_TAG: usize = 8;
+
+/// Typical setup sequence, shown with whichever steps have a tool
+const SETUP_STEPS: &[(&str, &str, &str)] = &[
+    ("Create an organization", "PUT", "/org"),
+    (
+        "Create a site (the tunnel into a network)",
+        "PUT",
+        "/org/{orgId}/site",
+    ),
+    (
+        "Create a resource (the service to expose)",
+        "PUT",
+        "/org/{orgId}/resource",
+    ),
+    (
+        "Point the resource at an upstream host",
+        "PUT",
+        "/resource/{resourceId}/target",
+    ),
+    (
+        "Restrict access with rules",
+        "PUT",
+        "/resource/{resourceId}/rule",
+    ),
+];
+
+/// A declared prompt argument
+pub struct WorkflowArgument {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub required: bool,
+}
+
+/// A parameterized workflow prompt
+pub struct WorkflowPrompt {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub arguments: &'static [WorkflowArgument],
+    template: &'static str,
+}
+
+const ORG_ID: WorkflowArgument = WorkflowArgument {
+    name: "orgId",
+    description: "Organization ID",
+    required: true,
+};
+
+/// Prompts served by `prompts/list`
+pub const WORKFLOWS: &[WorkflowPrompt] = &[
+    WorkflowPrompt {
+        name: "onboard_new_resource",
+        description: "Expose a new service through Pangolin: site, resource, target and rules",
+        arguments: &[
+            ORG_ID,
+            WorkflowArgument {
+                name: "domain",
+                description: "Full domain the resource is served on (e.g. app.example.com)",
+                required: true,
+            },
+            WorkflowArgument {
+                name: "siteId",
+                description: "Existing site to route through (a new site is created if omitted)",
+                required: false,
+            },
+        ],
+        template: "Onboard a new proxied resource in organization '{{orgId}}' served on '{{domain}}'.\n\
+\n\
+Follow these steps in order, checking each result before moving on:\n\
+1. Site (given: {{siteId}}): if no site was given, list sites with [[GET /org/{orgId}/sites]] and reuse a suitable one, or create one with [[PUT /org/{orgId}/site]].\n\
+2. Resource: check [[GET /org/{orgId}/resources]] that '{{domain}}' is not already served, then create the resource with [[PUT /org/{orgId}/resource]].\n\
+3. Target: add the upstream host and port the resource forwards to with [[PUT /resource/{resourceId}/target]].\n\
+4. Rules: add any access rules (allow or deny by IP, CIDR or path) with [[PUT /resource/{resourceId}/rule]].\n\
+5. Confirm the final configuration with [[GET /resource/{resourceId}]] and [[GET /resource/{resourceId}/targets]].\n\
+\n\
+Ask the user for any missing value (upstream host/port, protocol, rules) instead of guessing.",
+    },
+    WorkflowPrompt {
+        name: "audit_exposed_resources",
+        description: "Review every resource of an organization for unintended public exposure",
+        arguments: &[ORG_ID],
+        template: "Audit the resources exposed by organization '{{orgId}}'.\n\
+\n\
+1. List resources with [[GET /org/{orgId}/resources]].\n\
+2. For each resource, fetch its details with [[GET /resource/{resourceId}]], its targets with [[GET /resource/{resourceId}/targets]] and its rules with [[GET /resource/{resourceId}/rules]].\n\
+3. Flag resources that are publicly reachable without authentication (SSO disabled, no password, PIN or whitelist), rules allowing broad CIDRs, and targets pointing at unexpected hosts.\n\
+\n\
+Report findings as a table (resource, domain, issue, suggested fix). Do not change anything; this is a read-only review.",
+    },
+    WorkflowPrompt {
+        name: "rotate_site",
+        description: "Replace a site with a fresh one and move its targets over",
+        arguments: &[
+            ORG_ID,
+            WorkflowArgument {
+                name: "siteId",
+                description: "Site to retire",
+                required: true,
+            },
+        ],
+        template: "Rotate site '{{siteId}}' in organization '{{orgId}}'.\n\
+\n\
+1. Record the current site's settings with [[GET /site/{siteId}]].\n\
+2. Create the replacement site with [[PUT /org/{orgId}/site]], reusing the recorded name and type, and give the user the new credentials to deploy.\n\
+3. List resources with [[GET /org/{orgId}/resources]] and their targets with [[GET /resource/{resourceId}/targets]]; point every target of site '{{siteId}}' at the new site with [[POST /target/{targetId}]].\n\
+4. Once the user confirms the new site is online and traffic flows, delete the old site with [[DELETE /site/{siteId}]].\n\
+\n\
+Never delete the old site before the user confirms the new one works.",
+    },
+];
+
+impl WorkflowPrompt {
+    /// MCP prompt listing entry
+    pub fn to_prompt(&self) -> Prompt {
+        let arguments = self
+            .arguments
+            .iter()
+            .map(|arg| PromptArgument {
+                name: arg.name.to_string(),
+                title: None,
+                description: Some(arg.description.to_string()),
+                required: Some(arg.required),
+            })
+            .collect();
+        Prompt::new(self.name, Some(self.description), Some(arguments))
+    }
+
+    /// Required arguments absent (or empty) in `args`
+    pub fn missing_arguments(&self, args: &JsonObject) -> Vec<&'static str> {
+        self.arguments
+            .iter()
+            .filter(|arg| arg.required && argument_value(args, arg.name).is_none())
+            .map(|arg| arg.name)
+            .collect()
+    }
+
+    /// Render the prompt text, resolving `[[METHOD /path]]` to tool names
+    /// with `tool_name` and `{{name}}` to argument values
+    pub fn render(
+        &self,
+        args: &JsonObject,
+        tool_name: impl Fn(&str, &str) -> Option<String>,
+    ) -> String {
+        let mut text = String::with_capacity(self.template.len());
+        let mut rest = self.template;
+        while let Some(start) = rest.find("[[") {
+            let Some(end) = rest[start..].find("]]") else {
+                break;
+            };
+            text.push_str(&rest[..start]);
+            let reference = &rest[start + 2..start + end];
+            let (method, path) = reference.split_once(' ').unwrap_or(("", reference));
+            match tool_name(method, path) {
+                Some(name) => text.push_str(&format!("`{}`", name)),
+                None => text.push_str(&format!("(no tool available for {})", reference)),
+            }
+            rest = &rest[start + end + 2..];
+        }
+        text.push_str(rest);
+
+        for arg in self.arguments {
+            let value =
+                argument_value(args, arg.name).unwrap_or_else(|| "(not provided)".to_string());
+            text = text.replace(&format!("{{{{{}}}}}", arg.name), &value);
+        }
+        text
+    }
+}
+
+/// Listing entry for the getting-started prompt
+pub fn getting_started_prompt() -> Prompt {
+    Prompt::new(
+        GETTING_STARTED,
+        Some("Overview of the available Pangolin tools by tag, with the usual setup workflow"),
+        None,
+    )
+}
+
+/// Guide to the given endpoints: tools grouped by tag (in first-seen order),
+/// the setup steps that have a tool, and pointers to the other prompts
+pub fn getting_started(
+    endpoints: &[&PangolinEndpoint],
+    read_only: bool,
+    tool_name: impl Fn(&PangolinEndpoint) -> String,
+) -> String {
+    let mut tags: Vec<(&str, Vec<String>)> = Vec::new();
+    for endpoint in endpoints {
+        let tag = endpoint.tags.first().map_or("Other", String::as_str);
+        match tags.iter_mut().find(|(name, _)| *name == tag) {
+            Some((_, tools)) => tools.push(tool_name(endpoint)),
+            None => tags.push((tag, vec![tool_name(endpoint)])),
+        }
+    }
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "This server exposes {} Pangolin API tools{}.\n",
+        endpoints.len(),
+        if read_only {
+            " in read-only mode (write operations are unavailable)"
+        } else {
+            ""
+        }
+    );
+
+    let _ = writeln!(text, "## Tools by tag\n");
+    for (tag, tools) in &tags {
+        let mut names: Vec<String> = tools
+            .iter()
+            .take(TOOLS_PER_TAG)
+            .map(|t| format!("`{}`", t))
+            .collect();
+        if tools.len() > TOOLS_PER_TAG {
+            names.push(format!("and {} more", tools.len() - TOOLS_PER_TAG));
+        }
+        let _ = writeln!(
+            text,
+            "- **{}** ({} tool{}): {}",
+            tag,
+            tools.len(),
+            if tools.len() == 1 { "" } else { "s" },
+            names.join(", ")
+        );
+    }
+
+    let steps: Vec<(&str, String)> = SETUP_STEPS
+        .iter()
+        .filter_map(|(step, method, path)| {
+            let endpoint = endpoints
+                .iter()
+                .find(|e| e.method.as_str() == *method && e.path == *path)?;
+            Some((*step, tool_name(endpoint)))
+        })
+        .collect();
+    if !steps.is_empty() {
+        let _ = writeln!(text, "\n## Setting up access to a service\n");
+        for (i, (step, tool)) in steps.iter().enumerate() {
+            let _ = writeln!(text, "{}. {} with `{}`", i + 1, step, tool);
+        }
+    }
+
+    let _ = writeln!(text, "\n## Tips\n");
+    let _ = writeln!(
+        text,
+        "- IDs (orgId, siteId, resourceId, ...) come from the list tools; never guess them."
+    );
+    let _ = writeln!(
+        text,
+        "- Read `pangolin://endpoint/<tool name>` for a tool's full parameter, body and response reference."
+    );
+    let _ = writeln!(
+        text,
+        "- Guided workflows: {}.",
+        WORKFLOWS
+            .iter()
+            .map(|p| format!("`{}`", p.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    text
+}
+
+/// Find a workflow prompt by name
+pub fn find(name: &str) -> Option<&'static WorkflowPrompt> {
+    WORKFLOWS.iter().find(|p| p.name == name)
+}
+
+/// Non-empty argument value as text
+fn argument_value(args: &JsonObject, name: &str) -> Option<String> {
+    match args.get(name)? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) if s.trim().is_empty() => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
@@ -0,0 +1,54 @@
+//! Token bucket capping write calls (`--write-rate-limit`)
+//!
+//! The bucket holds up to one minute's worth of calls and refills
+//! continuously, one token every `60s / limit`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+pub struct WriteRateLimit {
+    per_minute: u32,
+    bucket: Mutex<Bucket>,
+}
+
+impl WriteRateLimit {
+    pub fn per_minute(per_minute: u32) -> Self {
+        let per_minute = per_minute.max(1);
+        Self {
+            per_minute,
+            bucket: Mutex::new(Bucket {
+                tokens: per_minute as f64,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.per_minute
+    }
+
+    /// Take a token, or return how long until the next one is available
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let per_token = 60.0 / self.per_minute as f64;
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let refilled = now.duration_since(bucket.updated).as_secs_f64() / per_token;
+        bucket.tokens = (bucket.tokens + refilled).min(self.per_minute as f64);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) * per_token))
+        }
+    }
+}
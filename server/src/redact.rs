@@ -0,0 +1,56 @@
+//! Masking of secrets in API responses (`--redact-fields`)
+//!
+//! A key matches when its name, lowercased and without `_`/`-`, contains one
+//! of the configured names normalized the same way: `apiKey` masks `apiKey`,
+//! `api_key` and `X-API-Key`, `token` masks `accessToken`.
+
+/// Replacement for masked values
+pub const REDACTED: &str = "***REDACTED***";
+
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    names: Vec<String>,
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl Redactor {
+    pub fn new(names: &[String]) -> Self {
+        Self {
+            names: names
+                .iter()
+                .map(|name| normalize(name))
+                .filter(|name| !name.is_empty())
+                .collect(),
+        }
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        let key = normalize(key);
+        self.names.iter().any(|name| key.contains(name.as_str()))
+    }
+
+    /// Mask matching values anywhere in `value`; returns how many were masked
+    pub fn redact(&self, value: &mut serde_json::Value) -> usize {
+        match value {
+            serde_json::Value::Object(object) => object
+                .iter_mut()
+                .map(|(key, child)| {
+                    if self.matches(key) {
+                        *child = serde_json::Value::String(REDACTED.to_string());
+                        1
+                    } else {
+                        self.redact(child)
+                    }
+                })
+                .sum(),
+            serde_json::Value::Array(items) => items.iter_mut().map(|item| self.redact(item)).sum(),
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! MCP resources exposed by the Pangolin server
+//!
+//! Resources carry documentation that is useful to an agent but is not
+//! callable, such as the server status, the payload schemas of outbound
+//! webhooks and the full reference of each endpoint.
+
+use rmcp::model::{
+    AnnotateAble, RawResource, RawResourceTemplate, Resource, ResourceContents, ResourceTemplate,
+};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::types::{
+    EndpointParameter, HttpMethod, PangolinEndpoint, ParameterType, PropertySchema, WebhookDoc,
+    ARRAY_BODY, FREE_FORM_BODY,
+};
+
+/// URI scheme of every resource served by this server
+pub const URI_SCHEME: &str = "pangolin://";
+
+/// URI prefix for webhook documentation resources
+pub const WEBHOOK_URI_PREFIX: &str = "pangolin://webhook/";
+
+/// URI prefix for endpoint documentation resources
+pub const ENDPOINT_URI_PREFIX: &str = "pangolin://endpoint/";
+
+/// URI of the server status resource
+pub const STATUS_URI: &str = "pangolin://status";
+
+/// Listing entry for the server status resource
+pub fn status_resource() -> Resource {
+    let mut resource = RawResource::new(STATUS_URI, "status".to_string());
+    resource.description =
+        Some("Server status: mode, base URL, API version and endpoint counts".to_string());
+    resource.mime_type = Some("application/json".to_string());
+    resource.no_annotation()
+}
+
+/// Render the server status document as JSON
+pub fn status_contents(status: &serde_json::Value) -> ResourceContents {
+    ResourceContents::TextResourceContents {
+        uri: STATUS_URI.to_string(),
+        mime_type: Some("application/json".to_string()),
+        text: serde_json::to_string_pretty(status).unwrap_or_else(|_| status.to_string()),
+        meta: None,
+    }
+}
+
+/// Resource URI for a webhook
+pub fn webhook_uri(webhook: &WebhookDoc) -> String {
+    format!("{}{}", WEBHOOK_URI_PREFIX, webhook.name)
+}
+
+/// Convert a webhook to its MCP resource listing entry
+pub fn webhook_to_resource(webhook: &WebhookDoc) -> Resource {
+    let mut resource = RawResource::new(webhook_uri(webhook), webhook.name.clone());
+    resource.description = Some(format!(
+        "[{} webhook] {}",
+        webhook.method.as_str(),
+        webhook.description
+    ));
+    resource.mime_type = Some("application/json".to_string());
+    resource.no_annotation()
+}
+
+/// Render a webhook's documentation (description and payload schema) as JSON
+pub fn webhook_contents(webhook: &WebhookDoc) -> ResourceContents {
+    let doc = serde_json::json!({
+        "name": webhook.name,
+        "method": webhook.method.as_str(),
+        "description": webhook.description,
+        "tags": webhook.tags,
+        "payloadSchema": webhook.payload_schema,
+    });
+
+    ResourceContents::TextResourceContents {
+        uri: webhook_uri(webhook),
+        mime_type: Some("application/json".to_string()),
+        text: serde_json::to_string_pretty(&doc).unwrap_or_else(|_| doc.to_string()),
+        meta: None,
+    }
+}
+
+/// Whether a GET endpoint can be read as a resource template
+///
+/// Only endpoints whose parameters are all path parameters qualify, so the
+/// URI carries every input.
+pub fn is_templatable(endpoint: &PangolinEndpoint) -> bool {
+    endpoint.method == HttpMethod::Get
+        && !endpoint.path_params.is_empty()
+        && endpoint.query_params.is_empty()
+        && endpoint.request_body.is_none()
+}
+
+/// Resource URI template mirroring an endpoint path (`/org/{orgId}` ->
+/// `pangolin://org/{orgId}`)
+pub fn endpoint_uri_template(endpoint: &PangolinEndpoint) -> String {
+    format!("{}{}", URI_SCHEME, endpoint.path.trim_start_matches('/'))
+}
+
+/// Convert a GET endpoint to its MCP resource template entry
+pub fn endpoint_to_resource_template(
+    endpoint: &PangolinEndpoint,
+    tool_name: &str,
+) -> ResourceTemplate {
+    RawResourceTemplate {
+        uri_template: endpoint_uri_template(endpoint),
+        name: tool_name.to_string(),
+        title: None,
+        description: Some(endpoint.description.clone()),
+        mime_type: Some("application/json".to_string()),
+    }
+    .no_annotation()
+}
+
+/// Match a resource URI against an endpoint path template, returning the
+/// path parameters on success
+pub fn match_uri_template(endpoint_path: &str, uri: &str) -> Option<HashMap<String, String>> {
+    let uri_path = uri.strip_prefix(URI_SCHEME)?;
+    let template: Vec<&str> = endpoint_path.trim_start_matches('/').split('/').collect();
+    let segments: Vec<&str> = uri_path.split('/').collect();
+    if template.len() != segments.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    for (pattern, segment) in template.iter().zip(&segments) {
+        match pattern.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+            Some(name) if !segment.is_empty() => {
+                params.insert(name.to_string(), segment.to_string());
+            }
+            Some(_) => return None,
+            None if pattern == segment => {}
+            None => return None,
+        }
+    }
+    Some(params)
+}
+
+/// Resource URI for an endpoint, keyed by its exposed tool name
+pub fn endpoint_uri(tool_name: &str) -> String {
+    format!("{}{}", ENDPOINT_URI_PREFIX, tool_name)
+}
+
+/// Convert an endpoint to its MCP resource listing entry
+pub fn endpoint_to_resource(
+    endpoint: &PangolinEndpoint,
+    tool_name: &str,
+    blocked: bool,
+) -> Resource {
+    let mut resource = RawResource::new(endpoint_uri(tool_name), tool_name.to_string());
+    let blocked = if blocked {
+        " (blocked in read-only mode)"
+    } else {
+        ""
+    };
+    resource.description = Some(format!(
+        "Reference for {} {}{}",
+        endpoint.method.as_str(),
+        endpoint.path,
+        blocked
+    ));
+    resource.mime_type = Some("text/markdown".to_string());
+    resource.no_annotation()
+}
+
+/// Render an endpoint's full reference (parameters, body, responses) as markdown
+///
+/// `blocked` marks write endpoints that cannot be called in read-only mode.
+pub fn endpoint_contents(
+    endpoint: &PangolinEndpoint,
+    tool_name: &str,
+    blocked: bool,
+) -> ResourceContents {
+    ResourceContents::TextResourceContents {
+        uri: endpoint_uri(tool_name),
+        mime_type: Some("text/markdown".to_string()),
+        text: render_endpoint(endpoint, tool_name, blocked),
+        meta: None,
+    }
+}
+
+/// Full reference of an endpoint followed by an example call, as returned by
+/// the `describe_endpoint` tool
+pub fn describe_endpoint(endpoint: &PangolinEndpoint, tool_name: &str) -> String {
+    let mut doc = render_endpoint(endpoint, tool_name, false);
+    let example = serde_json::json!({
+        "name": tool_name,
+        "arguments": example_arguments(endpoint),
+    });
+    let _ = writeln!(
+        doc,
+        "\n## Example\n\n```json\n{}\n```",
+        serde_json::to_string_pretty(&example).unwrap_or_default()
+    );
+    doc
+}
+
+/// Arguments of a minimal call: every path parameter plus the required query
+/// parameters and body fields, filled from examples, defaults or enums
+pub fn example_arguments(
+    endpoint: &PangolinEndpoint,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut arguments = serde_json::Map::new();
+    let params = endpoint
+        .path_params
+        .iter()
+        .chain(endpoint.query_params.iter().filter(|p| p.required));
+    for param in params {
+        let value = param
+            .example
+            .clone()
+            .or_else(|| param.default_value.clone())
+            .or_else(|| Some(param.enum_values.as_ref()?.first()?.clone().into()))
+            .unwrap_or_else(|| placeholder(&param.name, &param.param_type));
+        arguments.insert(param.name.clone(), value);
+    }
+
+    if let Some(ref body) = endpoint.request_body {
+        if body.free_form {
+            arguments.insert(FREE_FORM_BODY.to_string(), serde_json::json!({}));
+        } else if body.array_body {
+            arguments.insert(ARRAY_BODY.to_string(), serde_json::json!([]));
+        }
+        for (field, prop) in body
+            .properties
+            .iter()
+            .filter(|(f, _)| body.required.contains(f))
+        {
+            let argument = body
+                .renamed
+                .iter()
+                .find(|(_, renamed)| *renamed == field)
+                .map_or(field, |(argument, _)| argument);
+            let value = prop
+                .const_value
+                .clone()
+                .or_else(|| prop.example.clone())
+                .or_else(|| prop.default_value.clone())
+                .or_else(|| Some(prop.enum_values.as_ref()?.first()?.clone().into()))
+                .unwrap_or_else(|| placeholder(field, &prop.param_type));
+            arguments.insert(argument.clone(), value);
+        }
+    }
+    arguments
+}
+
+/// Stand-in value of the right type for an argument without an example
+fn placeholder(name: &str, param_type: &ParameterType) -> serde_json::Value {
+    match param_type {
+        ParameterType::String => format!("<{}>", name).into(),
+        ParameterType::Integer | ParameterType::Number => 1.into(),
+        ParameterType::Boolean => true.into(),
+        ParameterType::Array => serde_json::json!([]),
+        ParameterType::Object => serde_json::json!({}),
+    }
+}
+
+fn render_endpoint(endpoint: &PangolinEndpoint, tool_name: &str, blocked: bool) -> String {
+    let mut doc = String::new();
+    let _ = writeln!(doc, "# {}\n", tool_name);
+    let _ = writeln!(doc, "`{} {}`\n", endpoint.method.as_str(), endpoint.path);
+    if blocked {
+        let _ = writeln!(
+            doc,
+            "> **Blocked:** write operations are disabled (server is in read-only mode).\n"
+        );
+    }
+    let _ = writeln!(doc, "{}\n", endpoint.description);
+    if !endpoint.tags.is_empty() {
+        let _ = writeln!(doc, "Tags: {}\n", endpoint.tags.join(", "));
+    }
+
+    render_parameters(&mut doc, "Path parameters", &endpoint.path_params);
+    render_parameters(&mut doc, "Query parameters", &endpoint.query_params);
+
+    if let Some(ref body) = endpoint.request_body {
+        let _ = writeln!(doc, "## Request body (`{}`)\n", body.content_type);
+        let _ = writeln!(
+            doc,
+            "| Name | Type | Required | Description | Constraints |"
+        );
+        let _ = writeln!(
+            doc,
+            "|------|------|----------|-------------|-------------|"
+        );
+        for (name, prop) in &body.properties {
+            let _ = writeln!(
+                doc,
+                "| `{}` | {} | {} | {} | {} |",
+                name,
+                property_type(prop),
+                yes_no(body.required.contains(name)),
+                cell(prop.description.as_deref().unwrap_or("")),
+                cell(&property_constraints(prop).join("; "))
+            );
+        }
+        let _ = writeln!(doc);
+    }
+
+    if !endpoint.responses.is_empty() {
+        let _ = writeln!(doc, "## Responses\n");
+        for response in &endpoint.responses {
+            let _ = writeln!(
+                doc,
+                "- `{}` {}",
+                response.status,
+                response.description.as_deref().unwrap_or("")
+            );
+            if let Some(ref schema) = response.schema {
+                let schema = serde_json::to_string_pretty(schema).unwrap_or_default();
+                let _ = writeln!(doc, "\n```json\n{}\n```", schema);
+            }
+        }
+    }
+
+    doc
+}
+
+fn render_parameters(doc: &mut String, title: &str, params: &[EndpointParameter]) {
+    if params.is_empty() {
+        return;
+    }
+    let _ = writeln!(doc, "## {}\n", title);
+    let _ = writeln!(
+        doc,
+        "| Name | Type | Required | Description | Constraints |"
+    );
+    let _ = writeln!(
+        doc,
+        "|------|------|----------|-------------|-------------|"
+    );
+    for param in params {
+        let mut constraints = Vec::new();
+        if let Some(ref format) = param.format {
+            constraints.push(format!("format: {}", format));
+        }
+        if let Some(ref values) = param.enum_values {
+            constraints.push(format!("one of: {}", values.join(", ")));
+        }
+        if let Some(ref default) = param.default_value {
+            constraints.push(format!("default: {}", default));
+        }
+        if let Some(ref example) = param.example {
+            constraints.push(format!("example: {}", example));
+        }
+        let _ = writeln!(
+            doc,
+            "| `{}` | {} | {} | {} | {} |",
+            param.name,
+            param.param_type.to_json_schema_type(),
+            yes_no(param.required),
+            cell(param.description.as_deref().unwrap_or("")),
+            cell(&constraints.join("; "))
+        );
+    }
+    let _ = writeln!(doc);
+}
+
+fn property_type(prop: &PropertySchema) -> String {
+    let base = match prop.items {
+        Some(ref items) => format!("array<{}>", items.param_type.to_json_schema_type()),
+        None => prop.param_type.to_json_schema_type().to_string(),
+    };
+    if prop.nullable {
+        format!("{} \\| null", base)
+    } else {
+        base
+    }
+}
+
+/// Human-readable constraints of a body property
+fn property_constraints(prop: &PropertySchema) -> Vec<String> {
+    let mut constraints = Vec::new();
+    if let Some(ref format) = prop.format {
+        constraints.push(format!("format: {}", format));
+    }
+    if let Some(ref values) = prop.enum_values {
+        constraints.push(format!("one of: {}", values.join(", ")));
+    }
+    if let Some(ref value) = prop.const_value {
+        constraints.push(format!("always {}", value));
+    }
+    if let Some(ref default) = prop.default_value {
+        constraints.push(format!("default: {}", default));
+    }
+    if let Some(ref min) = prop.minimum {
+        constraints.push(format!("min: {}", min));
+    }
+    if let Some(ref max) = prop.maximum {
+        constraints.push(format!("max: {}", max));
+    }
+    if let Some(multiple_of) = prop.multiple_of {
+        constraints.push(format!("multiple of: {}", multiple_of));
+    }
+    if let Some(min) = prop.min_length {
+        constraints.push(format!("min length: {}", min));
+    }
+    if let Some(max) = prop.max_length {
+        constraints.push(format!("max length: {}", max));
+    }
+    if let Some(ref pattern) = prop.pattern {
+        constraints.push(format!("pattern: `{}`", pattern));
+    }
+    if let Some(min) = prop.min_items {
+        constraints.push(format!("min items: {}", min));
+    }
+    if let Some(max) = prop.max_items {
+        constraints.push(format!("max items: {}", max));
+    }
+    if prop.unique_items {
+        constraints.push("unique items".to_string());
+    }
+    if let Some(ref example) = prop.example {
+        constraints.push(format!("example: {}", example));
+    }
+    constraints
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+/// Escape text for a markdown table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
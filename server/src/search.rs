@@ -0,0 +1,83 @@
+//! Free-text endpoint search behind the built-in `search_endpoints` tool
+//!
+//! Each query token is matched against the tool name, path, tags and
+//! description; whole-word matches outweigh substring matches, and the name
+//! weighs most.
+
+use crate::types::PangolinEndpoint;
+
+/// Lowercase words of `text`, splitting on punctuation and camelCase
+fn tokens(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            previous_lower = false;
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        previous_lower = c.is_lowercase() || c.is_numeric();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Relevance of `endpoint` (exposed as `name`) for `query`; 0 is no match
+pub fn score(endpoint: &PangolinEndpoint, name: &str, query: &str) -> u32 {
+    let query_tokens = tokens(query);
+    if query_tokens.is_empty() {
+        return 0;
+    }
+
+    let name_lower = name.to_lowercase();
+    let name_tokens = tokens(name);
+    let path = endpoint.path.to_lowercase();
+    let tags = endpoint.tags.join(" ").to_lowercase();
+    let tag_tokens = tokens(&tags);
+    let description = endpoint.description.to_lowercase();
+    let description_tokens = tokens(&description);
+
+    let mut total = 0;
+    for token in &query_tokens {
+        total += if name_tokens.contains(token) {
+            5
+        } else if name_lower.contains(token.as_str()) {
+            3
+        } else {
+            0
+        };
+        total += if tag_tokens.contains(token) {
+            3
+        } else if tags.contains(token.as_str()) {
+            1
+        } else {
+            0
+        };
+        if path.contains(token.as_str()) {
+            total += 2;
+        }
+        total += if description_tokens.contains(token) {
+            2
+        } else if description.contains(token.as_str()) {
+            1
+        } else {
+            0
+        };
+    }
+
+    // The query spelling out the tool name beats any scattered match
+    let compact: String = query_tokens.concat();
+    if name_tokens.concat() == compact {
+        total += 20;
+    }
+    total
+}
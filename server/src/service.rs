@@ -1,29 +1,542 @@
 //! MCP Service for Pangolin Integration API
 
-use crate::pangolin_client::PangolinClient;
-use crate::swagger::SwaggerSpec;
-use crate::types::PangolinEndpoint;
+use crate::audit::{AuditEntry, AuditLog};
+use crate::cache::{ResponseCache, DEFAULT_CACHE_MAX_ENTRIES};
+use crate::completions;
+use crate::mcp_logging::LogForwarder;
+use crate::overlay::JsonPath;
+use crate::pangolin_client::{
+    ApiResponse, BreakerOpen, ClientConfig, KeyObserver, PangolinApiError, PangolinClient,
+};
+use crate::prompts;
+use crate::rate_limit::WriteRateLimit;
+use crate::redact::Redactor;
+use crate::resources;
+use crate::search;
+use crate::stats::{CallOutcome, ServerStats};
+use crate::swagger::{ExtractOptions, SwaggerSpec, SPEC_METHODS};
+use crate::truncate;
+use crate::types::{
+    EndpointParameter, HttpMethod, PangolinEndpoint, ParameterType, PropertySchema,
+    RequestBodySchema, VariantKind, WebhookDoc, ARRAY_BODY, FREE_FORM_BODY,
+};
+use crate::workflows::{self, Workflow};
+use regex::Regex;
 use rmcp::handler::server::ServerHandler;
 use rmcp::model::*;
-use rmcp::service::{RequestContext, RoleServer};
+use rmcp::service::{NotificationContext, Peer, RequestContext, RoleServer};
 use rmcp::ErrorData;
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{Semaphore, SemaphorePermit};
 use tracing::{debug, info, warn};
 
+/// Runtime options for the MCP service
+#[derive(Debug, Clone, Default)]
+pub struct ServiceConfig {
+    /// Read-only mode flag
+    pub read_only: bool,
+    /// Per-tool exceptions to the method-based read-only rule (tool name ->
+    /// allowed), e.g. allowing a POST search or blocking a mutating GET
+    pub read_only_overrides: HashMap<String, bool>,
+    /// Answer calls to tools blocked by read-only mode as if the tools did
+    /// not exist, instead of explaining why they are disabled
+    pub read_only_hide_writes: bool,
+    /// Namespace prepended to every exposed tool name (e.g. "pangolin_")
+    pub tool_prefix: Option<String>,
+    /// Request content type to use per endpoint (tool name -> content type)
+    pub content_type_overrides: HashMap<String, String>,
+    /// Maximum number of tools per `tools/list` page (None returns every tool at once)
+    pub tools_page_size: Option<usize>,
+    /// Interval between progress notifications while an API call is pending
+    /// (None uses `DEFAULT_PROGRESS_INTERVAL`)
+    pub progress_interval: Option<Duration>,
+    /// Forwards log events to the client once it sets a logging level
+    pub log_forwarder: Arc<LogForwarder>,
+    /// Complete `orgId` arguments from a cached `GET /orgs` call
+    pub dynamic_completion: bool,
+    /// Send arguments to the API without checking them against the input schema
+    pub skip_arg_validation: bool,
+    /// Only expose tools whose generated name fully matches one of these regexes
+    pub allow_tools: Vec<String>,
+    /// Never expose tools whose generated name fully matches one of these regexes
+    pub deny_tools: Vec<String>,
+    /// Only expose tools with at least one of these tags (case-insensitive)
+    pub expose_tags: Vec<String>,
+    /// Never expose tools through these tags (case-insensitive)
+    pub hide_tags: Vec<String>,
+    /// Return the HTTP request each tool call would send instead of sending it
+    pub dry_run: bool,
+    /// Size above which responses are truncated (None uses
+    /// `DEFAULT_MAX_RESPONSE_BYTES`, 0 never truncates)
+    pub max_response_bytes: Option<usize>,
+    /// Also ask the user to confirm tools whose generated name fully matches
+    /// one of these regexes (DELETE tools always need confirmation)
+    pub confirm_tools: Vec<String>,
+    /// Only expose tools using one of these HTTP methods (empty allows all)
+    pub allow_methods: Vec<HttpMethod>,
+    /// Never expose tools using these HTTP methods
+    pub deny_methods: Vec<HttpMethod>,
+    /// Maximum number of concurrent Pangolin API requests; excess calls
+    /// wait for a free slot (None or 0 is unbounded)
+    pub max_concurrency: Option<usize>,
+    /// Page size sent when a GET call omits a page size parameter (`limit`,
+    /// `pageSize`, ...) that has no documented default
+    pub default_page_size: Option<u64>,
+    /// Most calls accepted by one `batch_call` (None uses `DEFAULT_BATCH_MAX`)
+    pub batch_max: Option<usize>,
+    /// Composite tools chaining generated tools (`--workflows`)
+    pub workflows: Vec<Workflow>,
+    /// Expose one tool per tag, taking the endpoint as an `operation`
+    /// argument, instead of one tool per endpoint
+    pub group_by_tag: bool,
+    /// Expose only `search_endpoints` and `call_endpoint`
+    pub generic_mode: bool,
+    /// How long GET responses are reused (None or zero disables the cache)
+    pub cache_ttl: Option<Duration>,
+    /// Most cached GET responses (None uses `DEFAULT_CACHE_MAX_ENTRIES`)
+    pub cache_max_entries: Option<usize>,
+    /// File receiving one JSON line per tool call
+    pub audit_log: Option<PathBuf>,
+    /// Argument names masked in the audit log (case-insensitive)
+    pub audit_redact_fields: Vec<String>,
+    /// Response keys whose values are masked before results are returned
+    /// (matched case-insensitively within key names; empty masks nothing)
+    pub redact_fields: Vec<String>,
+    /// How boolean query parameters are written
+    pub bool_query_style: BoolQueryStyle,
+    /// Close generated input schemas with `"additionalProperties": false`
+    pub strict_tool_schemas: bool,
+    /// Most write calls (POST, PUT, PATCH, DELETE) per minute across all
+    /// tools (None or 0 is unlimited)
+    pub write_rate_limit: Option<u32>,
+    /// Accept a `_base_url` argument sending a single call to another server
+    pub allow_base_url_override: bool,
+    /// DELETE tools require a `confirm` argument set to true
+    pub require_delete_confirmation: bool,
+    /// Leave out the built-in tool returning the OpenAPI spec
+    pub disable_openapi_tool: bool,
+    /// Length the `get_info` instructions are cut down to (None uses
+    /// `DEFAULT_INSTRUCTIONS_MAX_CHARS`)
+    pub instructions_max_chars: Option<usize>,
+    /// Organization every call is confined to: `orgId` path parameters are
+    /// filled with it and endpoints spanning organizations are hidden
+    pub org_id: Option<String>,
+    /// HTTP client options
+    pub client: ClientConfig,
+}
+
+/// How boolean query parameters are written (`--bool-query-style`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoolQueryStyle {
+    /// `flag=true` / `flag=false`
+    #[default]
+    TrueFalse,
+    /// `flag=1` / `flag=0`
+    Numeric,
+    /// `flag=` when true, left out when false
+    Presence,
+}
+
+impl BoolQueryStyle {
+    #[allow(dead_code)]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "true-false" => Some(Self::TrueFalse),
+            "1-0" | "numeric" => Some(Self::Numeric),
+            "presence" => Some(Self::Presence),
+            _ => None,
+        }
+    }
+
+    /// Query value for `flag`; None leaves the parameter out
+    fn render(self, flag: bool) -> Option<String> {
+        match self {
+            Self::TrueFalse => Some(flag.to_string()),
+            Self::Numeric => Some(if flag { "1" } else { "0" }.to_string()),
+            Self::Presence => flag.then(String::new),
+        }
+    }
+}
+
+/// Delay over which successive tool set changes collapse into one
+/// `tools/list_changed` notification
+const LIST_CHANGED_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Interval between progress notifications while an API call is pending
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Argument confirming a destructive call when the client cannot be asked
+const CONFIRM_ARG: &str = "_confirm";
+
+/// Path parameter filled by --org-id
+const ORG_ID_PARAM: &str = "orgId";
+
+/// Name of the built-in usage statistics tool (before --tool-prefix)
+const STATS_TOOL: &str = "get_server_stats";
+
+/// Name of the built-in endpoint search tool (before --tool-prefix)
+const SEARCH_TOOL: &str = "search_endpoints";
+
+/// Name of the built-in endpoint reference tool (before --tool-prefix)
+const DESCRIBE_TOOL: &str = "describe_endpoint";
+
+/// Query parameter names (case-insensitive) recognized as a page size by
+/// `--default-page-size`
+const PAGE_SIZE_PARAMS: [&str; 7] = [
+    "limit",
+    "pagesize",
+    "page_size",
+    "perpage",
+    "per_page",
+    "maxresults",
+    "max_results",
+];
+
+/// Name of the built-in tool running several calls at once (before --tool-prefix)
+const BATCH_TOOL: &str = "batch_call";
+
+/// Name of the `--generic-mode` tool calling any endpoint by name (before
+/// --tool-prefix)
+const CALL_TOOL: &str = "call_endpoint";
+
+/// Name of the built-in tool returning the OpenAPI spec (before --tool-prefix)
+const OPENAPI_TOOL: &str = "pangolin_get_openapi";
+
+const BUILTIN_TOOLS: [&str; 6] = [
+    STATS_TOOL,
+    SEARCH_TOOL,
+    DESCRIBE_TOOL,
+    BATCH_TOOL,
+    CALL_TOOL,
+    OPENAPI_TOOL,
+];
+
+/// Built-in tools listed in `--generic-mode`
+const GENERIC_TOOLS: [&str; 2] = [SEARCH_TOOL, CALL_TOOL];
+
+/// Calls accepted by one `batch_call` unless --batch-max says otherwise
+pub const DEFAULT_BATCH_MAX: usize = 20;
+
+/// Length of the `get_info` instructions unless --instructions-max-chars
+/// says otherwise
+pub const DEFAULT_INSTRUCTIONS_MAX_CHARS: usize = 4000;
+
+/// Tool names listed per tag in the instructions
+const TAG_EXAMPLE_TOOLS: usize = 3;
+
+/// Matches returned by `search_endpoints` unless it is given a limit
+const DEFAULT_SEARCH_RESULTS: usize = 10;
+
+const MAX_SEARCH_RESULTS: usize = 50;
+
+/// Argument selecting the part of the response to return
+const FILTER_ARG: &str = "_filter";
+
+/// Argument listing the response keys to keep
+const FIELDS_ARG: &str = "_fields";
+
+/// Argument choosing among the request content types an operation accepts
+const CONTENT_TYPE_ARG: &str = "_content_type";
+
+/// Argument every DELETE tool requires set to true under
+/// --require-delete-confirmation
+const DELETE_CONFIRM_ARG: &str = "confirm";
+
+/// Argument sending one call to another server (`--allow-base-url-override`)
+const BASE_URL_ARG: &str = "_base_url";
+
+/// Response size above which tool results are truncated
+pub const DEFAULT_MAX_RESPONSE_BYTES: usize = 256 * 1024;
+
+/// Argument asking for the request a call would send instead of sending it
+const DRY_RUN_ARG: &str = "_dry_run";
+
+/// Argument skipping (and refreshing) the cached GET response
+const NO_CACHE_ARG: &str = "_no_cache";
+
+/// How long organization IDs fetched for completion are reused
+const ORG_COMPLETION_TTL: Duration = Duration::from_secs(60);
+
+/// Everything extracted from the spec, swapped as a whole on reload
+struct Catalog {
+    /// Available endpoints parsed from Swagger spec
+    endpoints: Vec<PangolinEndpoint>,
+    /// Outbound webhooks documented in the spec (exposed as resources)
+    webhooks: Vec<WebhookDoc>,
+    api_version: String,
+    /// The loaded spec, as returned by `pangolin_get_openapi`
+    spec: serde_json::Value,
+}
+
+impl Catalog {
+    fn from_spec(spec: &SwaggerSpec, options: &ExtractOptions) -> Self {
+        Self {
+            endpoints: spec.extract_endpoints_with(options),
+            webhooks: spec.extract_webhooks(),
+            api_version: spec.info.version.clone(),
+            spec: serde_json::to_value(spec).unwrap_or_default(),
+        }
+    }
+}
+
+/// Refuse a catalog that --allow-tools selects nothing from, or in which a
+/// workflow clashes with a tool name or calls an unknown tool
+fn check_catalog(
+    catalog: &Catalog,
+    tool_filter: &ToolFilter,
+    workflows: &[Workflow],
+) -> anyhow::Result<()> {
+    if !tool_filter.allow.is_empty()
+        && !catalog
+            .endpoints
+            .iter()
+            .any(|e| tool_filter.allows(&e.name))
+    {
+        anyhow::bail!(
+            "--allow-tools matches none of the {} tools generated from the spec",
+            catalog.endpoints.len()
+        );
+    }
+
+    for workflow in workflows {
+        if BUILTIN_TOOLS.contains(&workflow.name.as_str())
+            || catalog.endpoints.iter().any(|e| e.name == workflow.name)
+        {
+            anyhow::bail!("Workflow '{}' has the name of another tool", workflow.name);
+        }
+        for step in &workflow.steps {
+            if !catalog.endpoints.iter().any(|e| e.name == step.tool) {
+                anyhow::bail!(
+                    "Workflow '{}' step '{}' uses unknown tool '{}'",
+                    workflow.name,
+                    step.id,
+                    step.tool
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compile tool name patterns; they must match the whole name, so
+/// `org_by_orgId` does not also select `update_org_by_orgId`
+fn compile_tool_patterns(patterns: &[String], flag: &str) -> anyhow::Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|e| anyhow::anyhow!("Invalid {} pattern '{}': {}", flag, pattern, e))
+        })
+        .collect()
+}
+
+/// Allow/deny patterns matched against generated tool names (before the
+/// tool prefix); deny wins over allow
+#[derive(Clone, Default)]
+struct ToolFilter {
+    allow: Vec<Regex>,
+    deny: Vec<Regex>,
+}
+
+impl ToolFilter {
+    fn new(allow: &[String], deny: &[String]) -> anyhow::Result<Self> {
+        Ok(Self {
+            allow: compile_tool_patterns(allow, "--allow-tools")?,
+            deny: compile_tool_patterns(deny, "--deny-tools")?,
+        })
+    }
+
+    fn allows(&self, name: &str) -> bool {
+        self.allow.is_empty() || self.allow.iter().any(|re| re.is_match(name))
+    }
+
+    fn denies(&self, name: &str) -> bool {
+        self.deny.iter().any(|re| re.is_match(name))
+    }
+
+    fn permits(&self, name: &str) -> bool {
+        self.allows(name) && !self.denies(name)
+    }
+
+    /// Active patterns, as given on the command line
+    fn describe(&self) -> Vec<String> {
+        let patterns = |res: &[Regex]| {
+            res.iter()
+                .map(|re| {
+                    let source = re.as_str();
+                    source
+                        .strip_prefix("^(?:")
+                        .and_then(|p| p.strip_suffix(")$"))
+                        .unwrap_or(source)
+                        .to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut active = Vec::new();
+        if !self.allow.is_empty() {
+            active.push(format!("tools allowed: {}", patterns(&self.allow)));
+        }
+        if !self.deny.is_empty() {
+            active.push(format!("tools denied: {}", patterns(&self.deny)));
+        }
+        active
+    }
+}
+
+/// Tags whose tools are exposed; an endpoint is exposed when any of its
+/// tags is allowed
+#[derive(Clone, Default)]
+struct TagFilter {
+    expose: Vec<String>,
+    hide: Vec<String>,
+}
+
+impl TagFilter {
+    fn new(expose: &[String], hide: &[String]) -> Self {
+        let normalize = |tags: &[String]| tags.iter().map(|t| t.trim().to_lowercase()).collect();
+        Self {
+            expose: normalize(expose),
+            hide: normalize(hide),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        !self.expose.is_empty() || !self.hide.is_empty()
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut active = Vec::new();
+        if !self.expose.is_empty() {
+            active.push(format!("tags exposed: {}", self.expose.join(", ")));
+        }
+        if !self.hide.is_empty() {
+            active.push(format!("tags hidden: {}", self.hide.join(", ")));
+        }
+        active
+    }
+
+    fn allows_tag(&self, tag: &str) -> bool {
+        let tag = tag.to_lowercase();
+        (self.expose.is_empty() || self.expose.contains(&tag)) && !self.hide.contains(&tag)
+    }
+
+    fn permits(&self, tags: &[String]) -> bool {
+        // Untagged endpoints only survive when no tag is required
+        if tags.is_empty() {
+            return self.expose.is_empty();
+        }
+        tags.iter().any(|tag| self.allows_tag(tag))
+    }
+}
+
+/// HTTP methods whose tools are exposed; deny wins over allow
+#[derive(Clone, Default)]
+struct MethodPolicy {
+    allow: Vec<HttpMethod>,
+    deny: Vec<HttpMethod>,
+}
+
+impl MethodPolicy {
+    fn permits(&self, method: HttpMethod) -> bool {
+        (self.allow.is_empty() || self.allow.contains(&method)) && !self.deny.contains(&method)
+    }
+}
+
+/// Organization IDs fetched for `orgId` completion
+struct CachedOrgIds {
+    fetched_at: Instant,
+    ids: Vec<String>,
+}
+
 /// MCP Service for Pangolin Integration API
 #[derive(Clone)]
 pub struct PangolinService {
     /// Pangolin HTTP client
     client: Arc<PangolinClient>,
-    /// Available endpoints parsed from Swagger spec
-    endpoints: Arc<Vec<PangolinEndpoint>>,
+    /// Current spec contents; readers take a snapshot with `catalog()`
+    catalog: Arc<RwLock<Arc<Catalog>>>,
+    /// Options used to (re-)extract endpoints
+    extract_options: ExtractOptions,
     /// Read-only mode flag
     read_only: bool,
-    /// Server info
-    api_version: String,
+    /// Per-tool exceptions to the method-based read-only rule
+    read_only_overrides: HashMap<String, bool>,
+    /// Blocked tools are reported as unknown
+    read_only_hide_writes: bool,
+    /// Prefix prepended to tool names
+    tool_prefix: String,
+    /// Maximum number of tools per `tools/list` page
+    tools_page_size: Option<usize>,
+    /// Interval between progress notifications for pending calls
+    progress_interval: Duration,
     base_url: String,
+    /// Connected client, notified when the tool set changes
+    peer: Arc<Mutex<Option<Peer<RoleServer>>>>,
+    /// A `tools/list_changed` notification is scheduled
+    list_changed_pending: Arc<AtomicBool>,
+    /// Forwards log events to the client as `notifications/message`
+    log_forwarder: Arc<LogForwarder>,
+    /// Complete `orgId` arguments from the live organization list
+    dynamic_completion: bool,
+    /// Organization IDs last fetched for completion
+    org_ids: Arc<Mutex<Option<CachedOrgIds>>>,
+    /// Send arguments without checking them against the input schema
+    skip_arg_validation: bool,
+    /// `--allow-tools`/`--deny-tools` patterns
+    tool_filter: ToolFilter,
+    /// `--expose-tags`/`--hide-tags` lists
+    tag_filter: TagFilter,
+    /// `--allow-methods`/`--deny-methods` lists
+    method_policy: MethodPolicy,
+    /// `--confirm-tools` patterns
+    confirm_tools: Vec<Regex>,
+    /// Never send requests, only render them
+    dry_run: bool,
+    /// Size above which responses are truncated (None never truncates)
+    max_response_bytes: Option<usize>,
+    /// Slots for in-flight Pangolin API requests (None is unbounded)
+    upstream_slots: Option<Arc<Semaphore>>,
+    /// Record of every tool call (`--audit-log`)
+    audit_log: Option<AuditLog>,
+    /// Per-tool usage counters
+    stats: Arc<ServerStats>,
+    /// Page size sent when a GET call omits its page size parameter
+    default_page_size: Option<u64>,
+    /// One tool per tag instead of one per endpoint
+    group_by_tag: bool,
+    /// Only `search_endpoints` and `call_endpoint` are listed
+    generic_mode: bool,
+    /// Recent GET responses (`--cache-ttl`)
+    cache: Option<Arc<ResponseCache>>,
+    /// Most calls accepted by one `batch_call`
+    batch_max: usize,
+    /// Composite tools, listed after the generated ones
+    workflows: Arc<Vec<Workflow>>,
+    /// Masks secrets in responses (`--redact-fields`)
+    redactor: Redactor,
+    /// The only organization calls may touch (`--org-id`)
+    org_id: Option<String>,
+    /// Length the `get_info` instructions are cut down to
+    instructions_max_chars: usize,
+    /// How boolean query parameters are written
+    bool_query_style: BoolQueryStyle,
+    /// Generated input schemas reject unknown arguments
+    strict_tool_schemas: bool,
+    /// Budget of write calls shared by every tool (`--write-rate-limit`)
+    write_limit: Option<Arc<WriteRateLimit>>,
+    /// Calls may name another server with `_base_url`
+    allow_base_url_override: bool,
+    /// DELETE tools require `confirm: true`
+    require_delete_confirmation: bool,
+    /// `pangolin_get_openapi` is not offered
+    disable_openapi_tool: bool,
 }
 
 impl PangolinService {
@@ -32,350 +545,3947 @@ impl PangolinService {
         spec: SwaggerSpec,
         api_key: String,
         base_url: String,
-        read_only: bool,
+        config: ServiceConfig,
     ) -> anyhow::Result<Self> {
-        let client = PangolinClient::new(&base_url, api_key)?;
-        let endpoints = spec.extract_endpoints();
-
-        let available_count = if read_only {
-            endpoints
-                .iter()
-                .filter(|e| !e.method.is_write_operation())
-                .count()
-        } else {
-            endpoints.len()
+        let read_only = config.read_only;
+        config.log_forwarder.add_secret(&api_key);
+        // Keys from --api-key-command are masked as soon as they are fetched
+        let log_forwarder = config.log_forwarder.clone();
+        let client_config = ClientConfig {
+            key_observer: Some(KeyObserver(Arc::new(move |key| {
+                log_forwarder.add_secret(key)
+            }))),
+            ..config.client
         };
+        let client = PangolinClient::new(&base_url, api_key, client_config)?;
+        let extract_options = ExtractOptions {
+            content_type_overrides: config.content_type_overrides,
+        };
+        let catalog = Catalog::from_spec(&spec, &extract_options);
 
-        info!(
-            "Loaded {} endpoints from Swagger spec ({} available in current mode)",
-            endpoints.len(),
-            available_count
-        );
+        let tool_filter = ToolFilter::new(&config.allow_tools, &config.deny_tools)?;
+        let confirm_tools = compile_tool_patterns(&config.confirm_tools, "--confirm-tools")?;
+        check_catalog(&catalog, &tool_filter, &config.workflows)?;
 
         if read_only {
             info!("Running in READ-ONLY mode - write operations are disabled");
         }
+        for name in config.read_only_overrides.keys() {
+            if !catalog.endpoints.iter().any(|e| &e.name == name) {
+                warn!("Read-only override for unknown tool: {}", name);
+            }
+        }
 
-        Ok(Self {
+        let service = Self {
             client: Arc::new(client),
-            endpoints: Arc::new(endpoints),
+            catalog: Arc::new(RwLock::new(Arc::new(catalog))),
+            extract_options,
             read_only,
-            api_version: spec.info.version.clone(),
+            read_only_overrides: config.read_only_overrides,
+            read_only_hide_writes: config.read_only_hide_writes,
+            tool_prefix: config.tool_prefix.unwrap_or_default(),
+            tools_page_size: config.tools_page_size.filter(|size| *size > 0),
+            progress_interval: config
+                .progress_interval
+                .filter(|interval| !interval.is_zero())
+                .unwrap_or(DEFAULT_PROGRESS_INTERVAL),
             base_url,
-        })
+            peer: Arc::default(),
+            list_changed_pending: Arc::default(),
+            log_forwarder: config.log_forwarder,
+            dynamic_completion: config.dynamic_completion,
+            org_ids: Arc::default(),
+            skip_arg_validation: config.skip_arg_validation,
+            tool_filter,
+            tag_filter: TagFilter::new(&config.expose_tags, &config.hide_tags),
+            method_policy: MethodPolicy {
+                allow: config.allow_methods,
+                deny: config.deny_methods,
+            },
+            confirm_tools,
+            dry_run: config.dry_run,
+            max_response_bytes: match config.max_response_bytes {
+                Some(0) => None,
+                Some(max) => Some(max),
+                None => Some(DEFAULT_MAX_RESPONSE_BYTES),
+            },
+            upstream_slots: config
+                .max_concurrency
+                .filter(|max| *max > 0)
+                .map(|max| Arc::new(Semaphore::new(max))),
+            audit_log: config
+                .audit_log
+                .map(|path| AuditLog::open(&path, &config.audit_redact_fields))
+                .transpose()?,
+            stats: Arc::default(),
+            batch_max: config
+                .batch_max
+                .filter(|max| *max > 0)
+                .unwrap_or(DEFAULT_BATCH_MAX),
+            workflows: Arc::new(config.workflows),
+            default_page_size: config.default_page_size.filter(|size| *size > 0),
+            group_by_tag: config.group_by_tag,
+            generic_mode: config.generic_mode,
+            cache: config.cache_ttl.filter(|ttl| !ttl.is_zero()).map(|ttl| {
+                let max_entries = config
+                    .cache_max_entries
+                    .unwrap_or(DEFAULT_CACHE_MAX_ENTRIES);
+                Arc::new(ResponseCache::new(ttl, max_entries))
+            }),
+            redactor: Redactor::new(&config.redact_fields),
+            org_id: config.org_id.filter(|id| !id.is_empty()),
+            instructions_max_chars: config
+                .instructions_max_chars
+                .unwrap_or(DEFAULT_INSTRUCTIONS_MAX_CHARS),
+            bool_query_style: config.bool_query_style,
+            strict_tool_schemas: config.strict_tool_schemas,
+            write_limit: config
+                .write_rate_limit
+                .filter(|limit| *limit > 0)
+                .map(|limit| Arc::new(WriteRateLimit::per_minute(limit))),
+            allow_base_url_override: config.allow_base_url_override,
+            require_delete_confirmation: config.require_delete_confirmation,
+            disable_openapi_tool: config.disable_openapi_tool,
+        };
+        service.log_catalog();
+        Ok(service)
     }
 
-    /// Get available endpoints (filtered by read-only mode if enabled)
-    pub fn get_available_endpoints(&self) -> Vec<&PangolinEndpoint> {
-        if self.read_only {
-            self.endpoints
-                .iter()
-                .filter(|e| !e.method.is_write_operation())
-                .collect()
-        } else {
-            self.endpoints.iter().collect()
-        }
+    /// Replace the endpoints with those of a new spec and notify the client;
+    /// a spec failing the startup checks leaves the current one in place
+    #[allow(dead_code)]
+    pub fn reload(&self, spec: &SwaggerSpec) -> anyhow::Result<()> {
+        let catalog = Catalog::from_spec(spec, &self.extract_options);
+        check_catalog(&catalog, &self.tool_filter, &self.workflows)?;
+        *self.catalog.write().unwrap() = Arc::new(catalog);
+        self.log_catalog();
+        self.notify_tool_list_changed();
+        Ok(())
     }
 
-    /// Find an endpoint by name
-    fn find_endpoint(&self, name: &str) -> Option<&PangolinEndpoint> {
-        self.endpoints.iter().find(|e| e.name == name)
+    /// Snapshot of the current spec contents
+    fn catalog(&self) -> Arc<Catalog> {
+        self.catalog.read().unwrap().clone()
     }
 
-    /// Convert PangolinEndpoint to MCP Tool definition
-    fn endpoint_to_mcp(&self, endpoint: &PangolinEndpoint) -> Tool {
-        let mut properties = serde_json::Map::new();
-        let mut required = Vec::new();
+    fn log_catalog(&self) {
+        let catalog = self.catalog();
+        info!(
+            "Loaded {} endpoints from Swagger spec ({} available in current mode)",
+            catalog.endpoints.len(),
+            self.available_endpoints(&catalog).len()
+        );
 
-        // Add path parameters
-        for param in &endpoint.path_params {
-            let mut prop = serde_json::Map::new();
-            prop.insert(
-                "type".to_string(),
-                serde_json::Value::String(param.param_type.to_json_schema_type().to_string()),
-            );
-            if let Some(ref desc) = param.description {
-                prop.insert(
-                    "description".to_string(),
-                    serde_json::Value::String(desc.clone()),
-                );
-            }
-            if let Some(ref default) = param.default_value {
-                prop.insert("default".to_string(), default.clone());
-            }
-            properties.insert(param.name.clone(), serde_json::Value::Object(prop));
-            if param.required {
-                required.push(param.name.clone());
-            }
+        let filter = &self.tool_filter;
+        if !filter.allow.is_empty() {
+            let removed = catalog
+                .endpoints
+                .iter()
+                .filter(|e| !filter.allows(&e.name))
+                .count();
+            info!("--allow-tools removed {} tools", removed);
+        }
+        if !filter.deny.is_empty() {
+            let removed = catalog
+                .endpoints
+                .iter()
+                .filter(|e| filter.allows(&e.name) && filter.denies(&e.name))
+                .count();
+            info!("--deny-tools removed {} tools", removed);
+        }
+        if self.tag_filter.is_active() {
+            let removed = catalog
+                .endpoints
+                .iter()
+                .filter(|e| !self.tag_filter.permits(&e.tags))
+                .count();
+            info!("--expose-tags/--hide-tags removed {} tools", removed);
         }
 
-        // Add query parameters
-        for param in &endpoint.query_params {
-            let mut prop = serde_json::Map::new();
-            prop.insert(
-                "type".to_string(),
-                serde_json::Value::String(param.param_type.to_json_schema_type().to_string()),
+        if !catalog.webhooks.is_empty() {
+            info!(
+                "Loaded {} webhook docs from Swagger spec",
+                catalog.webhooks.len()
             );
-            if let Some(ref desc) = param.description {
-                prop.insert(
-                    "description".to_string(),
-                    serde_json::Value::String(desc.clone()),
-                );
-            }
-            if let Some(ref default) = param.default_value {
-                prop.insert("default".to_string(), default.clone());
-            }
-            properties.insert(param.name.clone(), serde_json::Value::Object(prop));
-            if param.required {
-                required.push(param.name.clone());
-            }
         }
+    }
 
-        // Add request body properties
-        if let Some(ref body) = endpoint.request_body {
-            for (name, prop) in &body.properties {
-                let mut schema_prop = serde_json::Map::new();
-                schema_prop.insert(
-                    "type".to_string(),
-                    serde_json::Value::String(prop.param_type.to_json_schema_type().to_string()),
-                );
-                if let Some(ref desc) = prop.description {
-                    schema_prop.insert(
-                        "description".to_string(),
-                        serde_json::Value::String(desc.clone()),
-                    );
-                }
-                if let Some(ref default) = prop.default_value {
-                    schema_prop.insert("default".to_string(), default.clone());
-                }
-                if let Some(ref enum_vals) = prop.enum_values {
-                    let enum_arr: Vec<serde_json::Value> = enum_vals
-                        .iter()
-                        .map(|s| serde_json::Value::String(s.clone()))
-                        .collect();
-                    schema_prop.insert("enum".to_string(), serde_json::Value::Array(enum_arr));
-                }
-                properties.insert(name.clone(), serde_json::Value::Object(schema_prop));
-            }
+    /// Schedule a `tools/list_changed` notification, merging changes that
+    /// happen within `LIST_CHANGED_DEBOUNCE` into one
+    fn notify_tool_list_changed(&self) {
+        if self.list_changed_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
 
-            // Add required fields from body
-            for req_field in &body.required {
-                if !required.contains(req_field) {
-                    required.push(req_field.clone());
-                }
+        let pending = self.list_changed_pending.clone();
+        let peer = self.peer.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(LIST_CHANGED_DEBOUNCE).await;
+            pending.store(false, Ordering::SeqCst);
+
+            let Some(peer) = peer.lock().unwrap().clone() else {
+                return;
+            };
+            debug!("Notifying client that the tool list changed");
+            if let Err(e) = peer.notify_tool_list_changed().await {
+                warn!("Failed to send tools/list_changed notification: {}", e);
             }
-        }
+        });
+    }
 
-        let mut schema = serde_json::Map::new();
-        schema.insert(
-            "type".to_string(),
-            serde_json::Value::String("object".to_string()),
-        );
-        schema.insert(
-            "properties".to_string(),
-            serde_json::Value::Object(properties),
-        );
+    /// Wait for a free concurrency slot (`--max-concurrency`), held until
+    /// the returned permit is dropped
+    async fn upstream_permit(&self) -> anyhow::Result<Option<SemaphorePermit<'_>>> {
+        Ok(match &self.upstream_slots {
+            Some(slots) => Some(slots.acquire().await?),
+            None => None,
+        })
+    }
 
-        if !required.is_empty() {
-            let required_arr: Vec<serde_json::Value> = required
-                .iter()
-                .map(|s| serde_json::Value::String(s.clone()))
-                .collect();
-            schema.insert(
-                "required".to_string(),
-                serde_json::Value::Array(required_arr),
-            );
-        }
+    /// Call the Pangolin API once a concurrency slot is free
+    async fn call_api(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        path_params: HashMap<String, String>,
+        query_params: HashMap<String, String>,
+        body: Option<serde_json::Value>,
+        content_type: &str,
+    ) -> anyhow::Result<ApiResponse> {
+        let _permit = self.upstream_permit().await?;
+        self.client
+            .call(method, path, path_params, query_params, body, content_type)
+            .await
+    }
 
-        // Build description with method and tags
-        let mut desc = format!("[{}] {}", endpoint.method.as_str(), endpoint.description);
-        if !endpoint.tags.is_empty() {
-            desc.push_str(&format!(" (Tags: {})", endpoint.tags.join(", ")));
-        }
+    /// Await an upstream call, sending a progress notification every
+    /// `progress_interval` until it resolves
+    async fn with_progress<T>(
+        &self,
+        call: impl Future<Output = T>,
+        token: ProgressToken,
+        peer: &Peer<RoleServer>,
+    ) -> T {
+        let started = Instant::now();
+        let mut ticker = tokio::time::interval(self.progress_interval);
+        ticker.tick().await;
+        tokio::pin!(call);
 
-        Tool {
-            name: Cow::Owned(endpoint.name.clone()),
-            description: Some(Cow::Owned(desc)),
-            input_schema: Arc::new(schema),
-            annotations: None,
-            icons: None,
-            meta: None,
-            output_schema: None,
-            title: None,
+        loop {
+            tokio::select! {
+                outcome = &mut call => return outcome,
+                _ = ticker.tick() => {
+                    let elapsed = started.elapsed().as_secs_f64();
+                    let notification = ProgressNotificationParam {
+                        progress_token: token.clone(),
+                        progress: elapsed,
+                        total: None,
+                        message: Some(format!(
+                            "Waiting for Pangolin API... {}s elapsed",
+                            elapsed.round()
+                        )),
+                    };
+                    if let Err(e) = peer.notify_progress(notification).await {
+                        debug!("Failed to send progress notification: {}", e);
+                    }
+                }
+            }
         }
     }
-}
 
-impl ServerHandler for PangolinService {
-    fn get_info(&self) -> ServerInfo {
-        let mode = if self.read_only {
-            "read-only"
-        } else {
-            "read-write"
-        };
+    /// Tool definitions exposed in the current mode, in listing order
+    pub fn tools(&self) -> Vec<Tool> {
+        let catalog = self.catalog();
+        self.available_endpoints(&catalog)
+            .into_iter()
+            .map(|e| self.endpoint_to_mcp(e))
+            .collect()
+    }
 
-        ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
-            server_info: Implementation {
-                name: "mcp-pangolin".to_string(),
-                version: env!("CARGO_PKG_VERSION").to_string(),
-                icons: None,
-                title: None,
-                website_url: None,
-            },
-            instructions: Some(format!(
-                "Pangolin Integration API server.\n\
-                 Connected to: {}\n\
-                 API version: {}\n\
-                 Mode: {}\n\
-                 Available tools: {}\n\n\
-                 Use these tools to manage your Pangolin resources including organizations, sites, resources, roles, users, and more.",
-                self.base_url,
-                self.api_version,
-                mode,
-                self.get_available_endpoints().len()
-            )),
-        }
+    /// Tools of `--group-by-tag` mode: one per tag of the available
+    /// endpoints, listing them as operations
+    fn tag_group_tools(&self) -> Vec<Tool> {
+        let catalog = self.catalog();
+        tag_groups(self.available_endpoints(&catalog))
+            .into_iter()
+            .map(|(group, operations)| {
+                let names: Vec<&str> = operations.iter().map(|e| e.name.as_str()).collect();
+                let lines: Vec<String> = operations
+                    .iter()
+                    .map(|e| {
+                        format!(
+                            "- {}: {} ({} {})",
+                            e.name,
+                            e.description.lines().next().unwrap_or_default(),
+                            e.method.as_str(),
+                            e.path
+                        )
+                    })
+                    .collect();
+                let tag = operations[0].tags.first().map_or("Untagged", String::as_str);
+                let schema = serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "operation": {
+                            "type": "string",
+                            "enum": names,
+                            "description": "Endpoint to call",
+                        },
+                        "arguments": {
+                            "type": "object",
+                            "description": "Arguments of the operation, as listed by describe_endpoint",
+                        },
+                    },
+                    "required": ["operation"],
+                });
+                let read_only = operations.iter().all(|e| e.method == HttpMethod::Get);
+                Tool {
+                    name: Cow::Owned(format!("{}{}", self.tool_prefix, group)),
+                    description: Some(Cow::Owned(format!(
+                        "{} operations:\n{}",
+                        tag,
+                        lines.join("\n")
+                    ))),
+                    input_schema: Arc::new(schema.as_object().cloned().unwrap_or_default()),
+                    annotations: Some(if read_only {
+                        ToolAnnotations::new().read_only(true).open_world(true)
+                    } else {
+                        ToolAnnotations::new().read_only(false).open_world(true)
+                    }),
+                    icons: None,
+                    meta: None,
+                    output_schema: None,
+                    title: None,
+                }
+            })
+            .collect()
     }
 
-    async fn list_tools(
+    /// Call of the endpoint named by the `operation` of a tag tool; the
+    /// endpoint's own checks then run as for a direct call
+    fn tag_group_request(
         &self,
-        _request: Option<PaginatedRequestParam>,
-        _context: RequestContext<RoleServer>,
-    ) -> Result<ListToolsResult, ErrorData> {
-        let available = self.get_available_endpoints();
-        debug!("Listing {} tools", available.len());
+        tool_name: &str,
+        operations: &[&PangolinEndpoint],
+        mut args: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CallToolRequestParam, ErrorData> {
+        let operation = required_string_argument(&args, "operation")?;
+        let Some(endpoint) = operations.iter().find(|e| e.name == operation) else {
+            let available: Vec<&str> = operations.iter().map(|e| e.name.as_str()).collect();
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "Unknown operation '{}' for {}; expected one of: {}",
+                    operation,
+                    tool_name,
+                    available.join(", ")
+                ),
+                None,
+            ));
+        };
+        let arguments = take_object_argument(&mut args, "arguments")?;
+        Ok(CallToolRequestParam {
+            name: Cow::Owned(self.tool_name(endpoint)),
+            arguments,
+        })
+    }
 
-        let tools: Vec<Tool> = available.iter().map(|e| self.endpoint_to_mcp(e)).collect();
+    /// Endpoints exposed as tools (filtered by read-only mode, the method
+    /// policy, the allow/deny patterns and the exposed tags)
+    fn available_endpoints<'a>(&self, catalog: &'a Catalog) -> Vec<&'a PangolinEndpoint> {
+        catalog
+            .endpoints
+            .iter()
+            .filter(|e| {
+                !self.is_blocked(e)
+                    && self.method_policy.permits(e.method)
+                    && self.tool_filter.permits(&e.name)
+                    && self.tag_filter.permits(&e.tags)
+                    && !self.is_out_of_scope(e)
+            })
+            .collect()
+    }
 
-        Ok(ListToolsResult {
-            tools,
-            next_cursor: None,
-            meta: None,
-        })
+    /// Find a listed tool's endpoint by its exposed name (including the tool
+    /// prefix); tools hidden in the current mode are not found
+    fn find_endpoint<'a>(&self, catalog: &'a Catalog, name: &str) -> Option<&'a PangolinEndpoint> {
+        self.available_endpoints(catalog)
+            .into_iter()
+            .find(|e| self.tool_name(e) == name)
     }
 
-    async fn call_tool(
-        &self,
-        request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
-    ) -> Result<CallToolResult, ErrorData> {
-        let tool_name = request.name.as_ref();
-        debug!("Calling tool: {}", tool_name);
+    /// Listed tools whose name is a near miss for `name`: equal ignoring case
+    /// and separators, or one edit away, after dropping a `get`/`list` verb
+    /// (GET tools carry no verb)
+    fn similar_tools(&self, catalog: &Catalog, name: &str) -> Vec<String> {
+        let wanted = normalize_tool_name(name);
+        let wanted = ["list", "get", "fetch"]
+            .iter()
+            .find_map(|verb| wanted.strip_prefix(verb).filter(|rest| !rest.is_empty()))
+            .map_or(wanted.clone(), str::to_string);
+        let close = |candidate: &str| {
+            let candidate = normalize_tool_name(candidate);
+            candidate == wanted || edit_distance(&candidate, &wanted) <= 1
+        };
 
-        // Find the endpoint
-        let endpoint = self.find_endpoint(tool_name).ok_or_else(|| {
-            ErrorData::invalid_params(format!("Unknown tool: {}", tool_name), None)
-        })?;
+        self.available_endpoints(catalog)
+            .into_iter()
+            .filter(|e| close(&e.name) || close(&self.tool_name(e)))
+            .map(|e| self.tool_name(e))
+            .collect()
+    }
 
-        // Check read-only mode for write operations
-        if self.read_only && endpoint.method.is_write_operation() {
+    /// Find any endpoint by tool name, including those hidden in the current
+    /// mode (their reference docs stay readable), except the write tools that
+    /// --read-only-hide-writes makes look nonexistent
+    fn find_documented_endpoint<'a>(
+        &self,
+        catalog: &'a Catalog,
+        name: &str,
+    ) -> Option<&'a PangolinEndpoint> {
+        catalog
+            .endpoints
+            .iter()
+            .find(|e| self.tool_name(e) == name && self.is_documented(e))
+    }
+
+    /// Whether an endpoint's reference docs are listed and readable
+    fn is_documented(&self, endpoint: &PangolinEndpoint) -> bool {
+        !(self.read_only_hide_writes && self.is_blocked(endpoint))
+    }
+
+    /// Organization IDs for `orgId` completion, cached for `ORG_COMPLETION_TTL`
+    async fn org_ids(&self) -> Vec<String> {
+        if let Some(org_id) = &self.org_id {
+            return vec![org_id.clone()];
+        }
+        if let Some(cached) = &*self.org_ids.lock().unwrap() {
+            if cached.fetched_at.elapsed() < ORG_COMPLETION_TTL {
+                return cached.ids.clone();
+            }
+        }
+
+        let response = self
+            .call_api(
+                HttpMethod::Get,
+                "/orgs",
+                HashMap::new(),
+                HashMap::new(),
+                None,
+                "application/json",
+            )
+            .await
+            .map(|response| response.body);
+        match response {
+            Ok(orgs) => {
+                let mut ids = Vec::new();
+                completions::collect_field(&orgs, "orgId", &mut ids);
+                *self.org_ids.lock().unwrap() = Some(CachedOrgIds {
+                    fetched_at: Instant::now(),
+                    ids: ids.clone(),
+                });
+                ids
+            }
+            Err(e) => {
+                debug!("Failed to fetch organizations for completion: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Whether calls to an endpoint are refused in the current mode
+    fn is_blocked(&self, endpoint: &PangolinEndpoint) -> bool {
+        self.read_only
+            && self
+                .read_only_overrides
+                .get(&endpoint.name)
+                .map_or(endpoint.method.is_write_operation(), |allowed| !allowed)
+    }
+
+    /// Whether an endpoint may reach beyond the organization set by --org-id:
+    /// only endpoints addressed by `orgId` can be held to it, since objects
+    /// reached by their own ID (`/site/{siteId}`) may belong to any organization
+    fn is_out_of_scope(&self, endpoint: &PangolinEndpoint) -> bool {
+        self.org_id.is_some() && !is_org_addressed(endpoint)
+    }
+
+    /// Refuse an `orgId` other than the one set by --org-id
+    fn check_org_scope(&self, org_id: &str) -> Result<(), ErrorData> {
+        match &self.org_id {
+            Some(scoped) if scoped != org_id => Err(ErrorData::invalid_params(
+                format!(
+                    "This server is scoped to organization '{}'; orgId '{}' is not allowed",
+                    scoped, org_id
+                ),
+                Some(serde_json::json!({ "orgId": scoped })),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Error for a tool defined by the spec but hidden by the current
+    /// configuration, naming the option responsible
+    fn disabled_tool(&self, endpoint: &PangolinEndpoint) -> ErrorData {
+        let tool_name = self.tool_name(endpoint);
+        let (disabled_by, hint) = if self.is_blocked(endpoint) {
             warn!(
                 "Blocked write operation in read-only mode: {} {}",
                 endpoint.method.as_str(),
                 endpoint.path
             );
-            return Ok(CallToolResult {
-                content: vec![Content::text(format!(
-                    "Error: Write operation '{}' is not allowed in read-only mode. \
-                     The server is configured with PANGOLIN_READ_ONLY=true.",
-                    tool_name
-                ))],
-                is_error: Some(true),
-                meta: None,
-                structured_content: None,
-            });
+            (
+                "read_only",
+                format!(
+                    "it is disabled in read-only mode; restart without --read-only or add --read-only-overrides {}=allow",
+                    endpoint.name
+                ),
+            )
+        } else if !self.method_policy.permits(endpoint.method) {
+            warn!(
+                "Blocked {} operation denied by the method policy: {}",
+                endpoint.method.as_str(),
+                endpoint.path
+            );
+            (
+                "method_policy",
+                format!(
+                    "{} tools are disabled by --allow-methods/--deny-methods",
+                    endpoint.method.as_str()
+                ),
+            )
+        } else if !self.tool_filter.permits(&endpoint.name) {
+            (
+                "tool_filter",
+                "it is excluded by --allow-tools/--deny-tools".to_string(),
+            )
+        } else if self.is_out_of_scope(endpoint) {
+            (
+                "org_scope",
+                format!(
+                    "it is not addressed by orgId, so it could reach other organizations, while --org-id limits this server to '{}'",
+                    self.org_id.as_deref().unwrap_or_default()
+                ),
+            )
+        } else {
+            let tags = if endpoint.tags.is_empty() {
+                "it has no tags".to_string()
+            } else {
+                format!("its tags ({}) are not exposed", endpoint.tags.join(", "))
+            };
+            (
+                "tag_filter",
+                format!("{} by --expose-tags/--hide-tags", tags),
+            )
+        };
+
+        ErrorData::invalid_params(
+            format!("Tool disabled: {} exists but {}", tool_name, hint),
+            Some(serde_json::json!({ "tool": tool_name, "disabledBy": disabled_by })),
+        )
+    }
+
+    /// Whether an endpoint takes the `confirm` argument of
+    /// --require-delete-confirmation
+    fn requires_delete_confirm(&self, endpoint: &PangolinEndpoint) -> bool {
+        self.require_delete_confirmation && endpoint.method == HttpMethod::Delete
+    }
+
+    /// Whether calls to an endpoint need the user's approval first
+    fn needs_confirmation(&self, endpoint: &PangolinEndpoint) -> bool {
+        endpoint.method == HttpMethod::Delete
+            || self
+                .confirm_tools
+                .iter()
+                .any(|re| re.is_match(&endpoint.name))
+    }
+
+    /// Ask the user to approve a destructive call through elicitation, or
+    /// require `_confirm: true` from clients that cannot be asked
+    async fn confirm_call(
+        &self,
+        tool_name: &str,
+        endpoint: &PangolinEndpoint,
+        url: &str,
+        args: &HashMap<String, serde_json::Value>,
+        confirmed_by_argument: bool,
+        peer: &Peer<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let operation = format!("{} {}", endpoint.method.as_str(), url);
+
+        if !peer.supports_elicitation() {
+            if confirmed_by_argument {
+                info!(tool = %tool_name, "Confirmed {} with {}", operation, CONFIRM_ARG);
+                return Ok(());
+            }
+            info!(tool = %tool_name, "Refused unconfirmed {}", operation);
+            return Err(ErrorData::invalid_params(
+                format!(
+                    "{} ({}) needs confirmation, and this client cannot prompt the user for it. \
+                     Check with the user, then call it again with \"{}\": true",
+                    tool_name, operation, CONFIRM_ARG
+                ),
+                Some(serde_json::json!({ "confirmationRequired": true, "operation": operation })),
+            ));
         }
 
-        // Extract parameters from arguments
-        let args: HashMap<String, serde_json::Value> = match request.arguments {
-            Some(map) => map.into_iter().collect(),
-            None => HashMap::new(),
+        let mut arguments: Vec<_> = args.iter().collect();
+        arguments.sort_by(|a, b| a.0.cmp(b.0));
+        let arguments: Vec<String> = arguments
+            .into_iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect();
+        let message = format!(
+            "Allow {} to run {}?\nArguments: {}",
+            tool_name,
+            operation,
+            if arguments.is_empty() {
+                "none".to_string()
+            } else {
+                arguments.join(", ")
+            }
+        );
+        let schema = ElicitationSchema::builder()
+            .required_bool_with("confirm", |b| b.description("Run this operation"))
+            .build_unchecked();
+
+        info!(tool = %tool_name, "Asking the user to confirm {}", operation);
+        let response = peer
+            .create_elicitation(CreateElicitationRequestParam {
+                message,
+                requested_schema: schema,
+            })
+            .await;
+        let decision = match response {
+            Ok(result) => match result.action {
+                ElicitationAction::Accept
+                    if result
+                        .content
+                        .as_ref()
+                        .and_then(|c| c.get("confirm"))
+                        .and_then(|c| c.as_bool())
+                        == Some(true) =>
+                {
+                    info!(tool = %tool_name, "User confirmed {}", operation);
+                    return Ok(());
+                }
+                ElicitationAction::Accept | ElicitationAction::Decline => "declined",
+                ElicitationAction::Cancel => "cancelled",
+            },
+            Err(e) => {
+                warn!(tool = %tool_name, "Confirmation request for {} failed: {}", operation, e);
+                "failed"
+            }
         };
+        info!(tool = %tool_name, "User {} {}", decision, operation);
+        Err(ErrorData::invalid_request(
+            format!(
+                "{} ({}) was not run: confirmation {}",
+                tool_name, operation, decision
+            ),
+            Some(serde_json::json!({ "confirmation": decision, "operation": operation })),
+        ))
+    }
 
-        // Separate path params, query params, and body params
-        let mut path_params: HashMap<String, String> = HashMap::new();
-        let mut query_params: HashMap<String, String> = HashMap::new();
-        let mut body_params: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    /// HTTP methods callable in the current mode, e.g. "GET, POST, PUT"
+    /// (read-only mode wins over `--allow-methods`)
+    fn method_summary(&self) -> String {
+        let methods: Vec<&str> = HttpMethod::ALL
+            .into_iter()
+            .filter(|m| self.method_policy.permits(*m))
+            .filter(|m| !(self.read_only && m.is_write_operation()))
+            .map(|m| m.as_str())
+            .collect();
+        if methods.is_empty() {
+            "none".to_string()
+        } else {
+            methods.join(", ")
+        }
+    }
 
-        // Extract path parameters
-        for param in &endpoint.path_params {
-            if let Some(value) = args.get(&param.name) {
-                path_params.insert(param.name.clone(), value_to_string(value));
-            } else if param.required {
+    /// One line per tag with its tool count and first few tool names, e.g.
+    /// "- Site (4): sites, site_by_siteId, create_site, ...and 1 more"; a
+    /// tool counts once under each of its tags, untagged tools come last
+    fn tag_lines(&self, catalog: &Catalog) -> Vec<String> {
+        let mut tools: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        let mut untagged = Vec::new();
+        for endpoint in self.available_endpoints(catalog) {
+            if endpoint.tags.is_empty() {
+                untagged.push(self.tool_name(endpoint));
+            }
+            for tag in &endpoint.tags {
+                tools
+                    .entry(tag.as_str())
+                    .or_default()
+                    .push(self.tool_name(endpoint));
+            }
+        }
+        tools
+            .into_iter()
+            .chain((!untagged.is_empty()).then_some(("untagged", untagged)))
+            .map(|(tag, names)| {
+                let mut examples = names[..names.len().min(TAG_EXAMPLE_TOOLS)].join(", ");
+                if names.len() > TAG_EXAMPLE_TOOLS {
+                    examples.push_str(&format!(
+                        ", ...and {} more",
+                        names.len() - TAG_EXAMPLE_TOOLS
+                    ));
+                }
+                format!("- {} ({}): {}", tag, names.len(), examples)
+            })
+            .collect()
+    }
+
+    /// Active tool filters, e.g. "tags exposed: site; tools denied: delete_.*"
+    fn filter_summary(&self) -> String {
+        let active = [self.tag_filter.describe(), self.tool_filter.describe()].concat();
+        if active.is_empty() {
+            "none".to_string()
+        } else {
+            active.join("; ")
+        }
+    }
+
+    /// `get_info` instructions, within --instructions-max-chars
+    fn instructions(&self, catalog: &Catalog) -> String {
+        let mode = if self.read_only {
+            "read-only"
+        } else {
+            "read-write"
+        };
+        let head = format!(
+            "Pangolin Integration API server.\n\
+             Connected to: {}\n\
+             API version: {}\n\
+             Mode: {}\n\
+             Organization: {}\n\
+             Allowed HTTP methods: {}\n\
+             Filters: {}\n\
+             Available tools: {}\n\
+             Tools by tag:\n",
+            self.base_url,
+            catalog.api_version,
+            mode,
+            match &self.org_id {
+                Some(org_id) => format!(
+                    "scoped to '{}' (orgId is filled in; other organizations are refused)",
+                    org_id
+                ),
+                None => "any".to_string(),
+            },
+            self.method_summary(),
+            self.filter_summary(),
+            self.available_endpoints(catalog).len(),
+        );
+        let tail = format!(
+            "Dry run: {}\n\
+             Server status: read pangolin://status\n\
+             Webhook docs: {} (readable as MCP resources)\n\
+             Endpoint reference: read pangolin://endpoint/<tool name> for full parameter, body and response docs\n\
+             Prompts: pangolin_getting_started (overview), onboard_new_resource, audit_exposed_resources, rotate_site\n\n\
+             Use these tools to manage your Pangolin resources including organizations, sites, resources, roles, users, and more.",
+            if self.dry_run {
+                "ON, no request is sent; every tool returns the HTTP request it would send"
+            } else {
+                "pass \"_dry_run\": true to any tool to get the HTTP request it would send without sending it"
+            },
+            catalog.webhooks.len()
+        );
+        fit_instructions(
+            &head,
+            &self.tag_lines(catalog),
+            &tail,
+            self.instructions_max_chars,
+        )
+    }
+
+    /// Status document served as the `pangolin://status` resource
+    fn status(&self) -> serde_json::Value {
+        let catalog = self.catalog();
+        let available = self.available_endpoints(&catalog).len();
+        serde_json::json!({
+            "server": "mcp-pangolin",
+            "version": env!("CARGO_PKG_VERSION"),
+            "mode": if self.read_only { "read-only" } else { "read-write" },
+            "baseUrl": self.base_url,
+            "apiVersion": catalog.api_version,
+            "toolPrefix": self.tool_prefix,
+            "endpoints": catalog.endpoints.len(),
+            "availableEndpoints": available,
+            "filteredEndpoints": catalog.endpoints.len() - available,
+            "webhooks": catalog.webhooks.len(),
+        })
+    }
+
+    /// Usage statistics returned by the built-in stats tool
+    fn server_stats(&self) -> serde_json::Value {
+        let catalog = self.catalog();
+        let available = self.available_endpoints(&catalog).len();
+        serde_json::json!({
+            "uptimeSecs": self.stats.uptime().as_secs(),
+            "mode": if self.read_only { "read-only" } else { "read-write" },
+            "endpoints": catalog.endpoints.len(),
+            "availableEndpoints": available,
+            "filteredEndpoints": catalog.endpoints.len() - available,
+            "tools": self.stats.tools_summary(),
+        })
+    }
+
+    /// Exposed tools for the API at large rather than one endpoint; only
+    /// --allow-tools and --deny-tools hide them, since they never call the
+    /// Pangolin API
+    fn builtin_tools(&self) -> Vec<Tool> {
+        let search_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to look for in tool names, paths, tags and descriptions",
+                },
+                "method": {
+                    "type": "string",
+                    "enum": HttpMethod::ALL.iter().map(|m| m.as_str()).collect::<Vec<_>>(),
+                },
+                "tag": {"type": "string"},
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_SEARCH_RESULTS,
+                    "default": DEFAULT_SEARCH_RESULTS,
+                },
+            },
+            "required": ["query"],
+        });
+        [
+            (
+                STATS_TOOL,
+                "Usage statistics of this MCP server: calls, errors, calls blocked by read-only mode and latency per tool, plus uptime and endpoint counts. Never calls the Pangolin API",
+                serde_json::json!({"type": "object", "properties": {}}),
+            ),
+            (
+                SEARCH_TOOL,
+                "Find the tool for a task among the available ones: returns the best matches for a free-text query with their names and descriptions. Never calls the Pangolin API",
+                search_schema,
+            ),
+            (
+                DESCRIBE_TOOL,
+                "Full reference of a tool: every parameter with its type, constraints, defaults and allowed values, the request body, documented responses and an example call. Never calls the Pangolin API",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "Name of the tool to describe"},
+                    },
+                    "required": ["name"],
+                }),
+            ),
+            (
+                BATCH_TOOL,
+                "Run several tool calls in one request, e.g. reading many sites. Entries are checked like normal calls and run in order (all at once when every entry is a GET); a failed entry does not stop the others unless stop_on_error is set",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "calls": {
+                            "type": "array",
+                            "minItems": 1,
+                            "maxItems": self.batch_max,
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "tool": {"type": "string"},
+                                    "arguments": {"type": "object"},
+                                },
+                                "required": ["tool"],
+                            },
+                        },
+                        "stop_on_error": {
+                            "type": "boolean",
+                            "description": "Skip the remaining entries after the first failure",
+                            "default": false,
+                        },
+                    },
+                    "required": ["calls"],
+                }),
+            ),
+            (
+                OPENAPI_TOOL,
+                "The OpenAPI spec this server was generated from, as JSON, limited to the operations of the available tools, e.g. to look up response shapes. Give a path to get only that path's operations. Never calls the Pangolin API",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "Spec path to return alone, e.g. /org/{orgId}"},
+                    },
+                }),
+            ),
+            (
+                CALL_TOOL,
+                "Call a Pangolin API endpoint by name. First find it with search_endpoints, which returns each endpoint's name, method, path and description; then call it here with that name and its arguments. Arguments are checked as for a dedicated tool, and read-only mode and the tool filters apply",
+                serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "Endpoint name, as returned by search_endpoints"},
+                        "arguments": {"type": "object", "description": "Path, query and body arguments of the endpoint"},
+                    },
+                    "required": ["name"],
+                }),
+            ),
+        ]
+        .into_iter()
+        .filter(|(name, _, _)| {
+            if self.generic_mode {
+                GENERIC_TOOLS.contains(name)
+            } else {
+                *name != CALL_TOOL
+            }
+        })
+        .filter(|(name, _, _)| *name != OPENAPI_TOOL || !self.disable_openapi_tool)
+        .filter(|(name, _, _)| self.tool_filter.permits(name))
+        .map(|(name, description, schema)| Tool {
+            name: Cow::Owned(format!("{}{}", self.tool_prefix, name)),
+            description: Some(Cow::Borrowed(description)),
+            input_schema: Arc::new(schema.as_object().cloned().unwrap_or_default()),
+            annotations: Some(if name == BATCH_TOOL || name == CALL_TOOL {
+                ToolAnnotations::new().read_only(false).open_world(true)
+            } else {
+                ToolAnnotations::new()
+                    .read_only(true)
+                    .idempotent(true)
+                    .open_world(false)
+            }),
+            icons: None,
+            meta: None,
+            output_schema: None,
+            title: None,
+        })
+        .collect()
+    }
+
+    /// Available endpoints ranked for a `search_endpoints` query
+    fn search_endpoints(
+        &self,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, ErrorData> {
+        let query = required_string_argument(args, "query")?;
+        let method = string_argument(args, "method")?
+            .map(|m| {
+                HttpMethod::from_name(m).ok_or_else(|| {
+                    ErrorData::invalid_params(format!("Unknown HTTP method: {}", m), None)
+                })
+            })
+            .transpose()?;
+        let tag = string_argument(args, "tag")?;
+        let limit = match args.get("limit") {
+            None | Some(serde_json::Value::Null) => DEFAULT_SEARCH_RESULTS,
+            Some(value) => value
+                .as_u64()
+                .filter(|n| (1..=MAX_SEARCH_RESULTS as u64).contains(n))
+                .ok_or_else(|| {
+                    ErrorData::invalid_params(
+                        format!(
+                            "Argument 'limit' must be an integer between 1 and {}, got {}",
+                            MAX_SEARCH_RESULTS, value
+                        ),
+                        None,
+                    )
+                })? as usize,
+        };
+
+        let catalog = self.catalog();
+        let mut matches: Vec<_> = self
+            .available_endpoints(&catalog)
+            .into_iter()
+            .filter(|e| method.is_none_or(|m| e.method == m))
+            .filter(|e| tag.is_none_or(|t| e.tags.iter().any(|et| et.eq_ignore_ascii_case(t))))
+            .map(|e| (search::score(e, &self.tool_name(e), query), e))
+            .filter(|(score, _)| *score > 0)
+            .collect();
+        // Stable: equal scores keep spec order
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        let total = matches.len();
+        let results: Vec<_> = matches
+            .into_iter()
+            .take(limit)
+            .map(|(score, e)| {
+                serde_json::json!({
+                    "name": self.tool_name(e),
+                    "method": e.method.as_str(),
+                    "path": e.path,
+                    "description": e.description.lines().next().unwrap_or_default(),
+                    "tags": e.tags,
+                    "score": score,
+                })
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "query": query,
+            "total": total,
+            "results": results,
+        }))
+    }
+
+    /// Run the entries of a `batch_call`, each through `call_tool`
+    async fn batch_call(
+        &self,
+        args: &serde_json::Map<String, serde_json::Value>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<serde_json::Value, ErrorData> {
+        let invalid = |message: String| ErrorData::invalid_params(message, None);
+        let calls = match args.get("calls") {
+            Some(serde_json::Value::Array(calls)) => calls,
+            _ => return Err(invalid("Argument 'calls' must be an array".to_string())),
+        };
+        if calls.is_empty() || calls.len() > self.batch_max {
+            return Err(invalid(format!(
+                "batch_call takes 1 to {} calls (--batch-max), got {}",
+                self.batch_max,
+                calls.len()
+            )));
+        }
+        let stop_on_error = args.get("stop_on_error") == Some(&serde_json::Value::Bool(true));
+
+        let mut requests = Vec::with_capacity(calls.len());
+        for (i, call) in calls.iter().enumerate() {
+            let tool = call
+                .get("tool")
+                .and_then(|t| t.as_str())
+                .ok_or_else(|| invalid(format!("calls[{}] needs a 'tool' name", i)))?;
+            if tool.strip_prefix(self.tool_prefix.as_str()) == Some(BATCH_TOOL) {
+                return Err(invalid(format!(
+                    "calls[{}]: batch_call cannot be nested",
+                    i
+                )));
+            }
+            let arguments = match call.get("arguments") {
+                None | Some(serde_json::Value::Null) => None,
+                Some(serde_json::Value::Object(arguments)) => Some(arguments.clone()),
+                Some(_) => {
+                    return Err(invalid(format!("calls[{}].arguments must be an object", i)))
+                }
+            };
+            requests.push(CallToolRequestParam {
+                name: Cow::Owned(tool.to_string()),
+                arguments,
+            });
+        }
+
+        // Reads have no ordering constraints, so an all-GET batch runs at once
+        // (still within --max-concurrency)
+        let catalog = self.catalog();
+        let all_reads = requests.iter().all(|r| {
+            self.find_endpoint(&catalog, &r.name)
+                .is_some_and(|e| e.method == HttpMethod::Get)
+        });
+        let mut outcomes = Vec::with_capacity(requests.len());
+        if all_reads && !stop_on_error {
+            let mut calls = tokio::task::JoinSet::new();
+            for (i, request) in requests.into_iter().enumerate() {
+                let (service, context) = (self.clone(), context.clone());
+                calls.spawn(async move { (i, service.call_tool_boxed(request, context).await) });
+            }
+            let mut finished = calls.join_all().await;
+            finished.sort_by_key(|(i, _)| *i);
+            outcomes.extend(finished.into_iter().map(|(_, outcome)| Some(outcome)));
+        } else {
+            let mut failed = false;
+            for request in requests {
+                if failed && stop_on_error {
+                    outcomes.push(None);
+                    continue;
+                }
+                let outcome = self.call_tool_boxed(request, context.clone()).await;
+                failed |= !matches!(&outcome, Ok(result) if result.is_error != Some(true));
+                outcomes.push(Some(outcome));
+            }
+        }
+
+        let (mut succeeded, mut failed, mut skipped) = (0, 0, 0);
+        let results: Vec<_> = calls
+            .iter()
+            .zip(outcomes)
+            .enumerate()
+            .map(|(i, (call, outcome))| {
+                let mut entry = serde_json::json!({"index": i, "tool": call["tool"]});
+                match outcome {
+                    None => {
+                        skipped += 1;
+                        entry["status"] = "skipped".into();
+                    }
+                    Some(Ok(result)) if result.is_error != Some(true) => {
+                        succeeded += 1;
+                        entry["status"] = "ok".into();
+                        entry["result"] = result_value(result);
+                    }
+                    Some(Ok(result)) => {
+                        failed += 1;
+                        entry["status"] = "error".into();
+                        entry["error"] = result_value(result);
+                    }
+                    Some(Err(e)) => {
+                        failed += 1;
+                        entry["status"] = "error".into();
+                        entry["error"] = e.message.to_string().into();
+                    }
+                }
+                entry
+            })
+            .collect();
+        Ok(serde_json::json!({
+            "succeeded": succeeded,
+            "failed": failed,
+            "skipped": skipped,
+            "results": results,
+        }))
+    }
+
+    /// Composite tools from `--workflows`; a workflow of GET steps only is
+    /// read-only
+    fn workflow_tools(&self) -> Vec<Tool> {
+        let catalog = self.catalog();
+        self.workflows
+            .iter()
+            .filter(|workflow| self.tool_filter.permits(&workflow.name))
+            .map(|workflow| {
+                let steps: Vec<_> = workflow.steps.iter().map(|s| s.tool.as_str()).collect();
+                let description = format!("{} Steps: {}.", workflow.description, steps.join(", "));
+                let read_only = steps.iter().all(|tool| {
+                    catalog
+                        .endpoints
+                        .iter()
+                        .any(|e| e.name == *tool && e.method == HttpMethod::Get)
+                });
+                Tool {
+                    name: Cow::Owned(format!("{}{}", self.tool_prefix, workflow.name)),
+                    description: Some(Cow::Owned(description.trim_start().to_string())),
+                    input_schema: Arc::new(
+                        workflow
+                            .input_schema
+                            .as_object()
+                            .cloned()
+                            .unwrap_or_default(),
+                    ),
+                    annotations: Some(if read_only {
+                        ToolAnnotations::new().read_only(true).open_world(true)
+                    } else {
+                        ToolAnnotations::new().read_only(false).open_world(true)
+                    }),
+                    icons: None,
+                    meta: None,
+                    output_schema: None,
+                    title: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Run the steps of a workflow in order, each through `call_tool`;
+    /// the first failing step ends the run with a report of the steps done
+    async fn run_workflow(
+        &self,
+        workflow: &Workflow,
+        inputs: serde_json::Map<String, serde_json::Value>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let required = workflow.input_schema["required"].as_array();
+        for name in required.into_iter().flatten().filter_map(|n| n.as_str()) {
+            if !inputs.contains_key(name) {
                 return Err(ErrorData::invalid_params(
-                    format!("Missing required path parameter: {}", param.name),
+                    format!("Missing required argument: {}", name),
                     None,
                 ));
             }
         }
 
-        // Extract query parameters
-        for param in &endpoint.query_params {
-            if let Some(value) = args.get(&param.name) {
-                query_params.insert(param.name.clone(), value_to_string(value));
+        let mut values = serde_json::json!({"inputs": inputs, "steps": {}});
+        let mut completed = Vec::new();
+        for step in &workflow.steps {
+            let outcome = match workflows::render(
+                &serde_json::Value::Object(step.arguments.clone()),
+                &values,
+            ) {
+                Ok(arguments) => {
+                    let request = CallToolRequestParam {
+                        name: Cow::Owned(format!("{}{}", self.tool_prefix, step.tool)),
+                        arguments: arguments.as_object().cloned(),
+                    };
+                    match self.call_tool_boxed(request, context.clone()).await {
+                        Ok(result) if result.is_error != Some(true) => Ok(step_response(result)),
+                        Ok(result) => Err(result_value(result)["message"]
+                            .as_str()
+                            .unwrap_or("the call failed")
+                            .to_string()),
+                        Err(e) => Err(e.message.to_string()),
+                    }
+                }
+                Err(e) => Err(format!("{:#}", e)),
+            };
+            match outcome {
+                Ok(response) => {
+                    values["steps"][&step.id] = serde_json::json!({"response": response});
+                    completed.push(serde_json::json!({
+                        "id": step.id,
+                        "tool": step.tool,
+                        "response": response,
+                    }));
+                }
+                Err(message) => {
+                    warn!(
+                        "Workflow {} stopped at step {}: {}",
+                        workflow.name, step.id, message
+                    );
+                    let message = format!(
+                        "Workflow {} stopped at step {} ({}): {}",
+                        workflow.name, step.id, step.tool, message
+                    );
+                    return Ok(CallToolResult {
+                        content: vec![Content::text(format!("Error: {}", message))],
+                        is_error: Some(true),
+                        structured_content: Some(serde_json::json!({
+                            "workflow": workflow.name,
+                            "failedStep": step.id,
+                            "message": message,
+                            "completed": completed,
+                        })),
+                        meta: None,
+                    });
+                }
             }
         }
+        Ok(json_result(serde_json::json!({
+            "workflow": workflow.name,
+            "steps": completed,
+        })))
+    }
 
-        // Extract body parameters (everything else goes to body)
-        if endpoint.request_body.is_some() {
-            for (key, value) in &args {
-                let is_path_param = endpoint.path_params.iter().any(|p| &p.name == key);
-                let is_query_param = endpoint.query_params.iter().any(|p| &p.name == key);
+    /// Call of the endpoint named by a `call_endpoint` request; it then goes
+    /// through the checks of a direct call
+    fn call_endpoint_request(
+        &self,
+        mut args: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CallToolRequestParam, ErrorData> {
+        let name = required_string_argument(&args, "name")?.to_string();
+        if name
+            .strip_prefix(self.tool_prefix.as_str())
+            .is_some_and(|name| BUILTIN_TOOLS.contains(&name))
+        {
+            return Err(ErrorData::invalid_params(
+                format!("{} only calls API endpoints, not {}", CALL_TOOL, name),
+                None,
+            ));
+        }
+        let arguments = take_object_argument(&mut args, "arguments")?;
+        Ok(CallToolRequestParam {
+            name: Cow::Owned(name),
+            arguments,
+        })
+    }
 
-                if !is_path_param && !is_query_param {
-                    body_params.insert(key.clone(), value.clone());
-                }
+    /// `call_tool` behind a type-erased future, so batch entries can recurse
+    /// into it
+    fn call_tool_boxed(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::pin::Pin<Box<dyn Future<Output = Result<CallToolResult, ErrorData>> + Send + '_>>
+    {
+        Box::pin(self.call_tool(request, context))
+    }
+
+    /// The loaded spec for `pangolin_get_openapi`, optionally cut down to
+    /// one path; operations of tools hidden by the filters are left out
+    fn openapi_spec(
+        &self,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, ErrorData> {
+        let catalog = self.catalog();
+        let mut paths = self.available_paths(&catalog);
+        let Some(path) = string_argument(args, "path")? else {
+            let mut spec = catalog.spec.clone();
+            spec["paths"] = serde_json::Value::Object(paths);
+            return Ok(spec);
+        };
+        let Some(item) = paths.remove(path) else {
+            return Err(ErrorData::invalid_params(
+                format!("Path '{}' is not in the OpenAPI spec", path),
+                None,
+            ));
+        };
+        Ok(serde_json::json!({
+            "openapi": catalog.spec["openapi"],
+            "info": catalog.spec["info"],
+            "paths": { path: item },
+        }))
+    }
+
+    /// The spec's path items keeping only the operations of available tools;
+    /// paths left without any are dropped
+    fn available_paths(&self, catalog: &Catalog) -> serde_json::Map<String, serde_json::Value> {
+        let available: HashSet<(&str, String)> = self
+            .available_endpoints(catalog)
+            .into_iter()
+            .map(|e| (e.path.as_str(), e.method.as_str().to_ascii_lowercase()))
+            .collect();
+        let Some(paths) = catalog.spec["paths"].as_object() else {
+            return serde_json::Map::new();
+        };
+
+        let mut kept = serde_json::Map::new();
+        for (path, item) in paths {
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+            let item: serde_json::Map<_, _> = item
+                .iter()
+                .filter(|(key, _)| {
+                    !SPEC_METHODS.contains(&key.as_str())
+                        || available.contains(&(path.as_str(), key.to_string()))
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            if item.keys().any(|key| SPEC_METHODS.contains(&key.as_str())) {
+                kept.insert(path.clone(), serde_json::Value::Object(item));
             }
         }
+        kept
+    }
 
-        let body = if body_params.is_empty() {
-            None
-        } else {
-            Some(serde_json::Value::Object(body_params))
+    /// Reference of an available tool for `describe_endpoint`
+    fn describe_endpoint(
+        &self,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let name = required_string_argument(args, "name")?;
+        let catalog = self.catalog();
+        let Some(endpoint) = self.find_endpoint(&catalog, name) else {
+            let similar = self.similar_tools(&catalog, name);
+            return Err(if similar.is_empty() {
+                ErrorData::invalid_params(
+                    format!(
+                        "Unknown tool: {} (call {}{} or tools/list for the available ones)",
+                        name, self.tool_prefix, SEARCH_TOOL
+                    ),
+                    None,
+                )
+            } else {
+                ErrorData::invalid_params(
+                    format!(
+                        "Unknown tool: {}. Did you mean {}?",
+                        name,
+                        similar.join(", ")
+                    ),
+                    Some(serde_json::json!({ "candidates": similar })),
+                )
+            });
         };
 
-        // Call the Pangolin API
-        match self
-            .client
-            .call(
-                endpoint.method,
-                &endpoint.path,
-                path_params,
-                query_params,
-                body,
-            )
-            .await
-        {
-            Ok(result) => {
-                let text =
-                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string());
+        Ok(CallToolResult {
+            content: vec![Content::text(resources::describe_endpoint(endpoint, name))],
+            is_error: Some(false),
+            structured_content: Some(serde_json::json!({
+                "name": name,
+                "method": endpoint.method.as_str(),
+                "path": endpoint.path,
+                "inputSchema": self.endpoint_to_mcp(endpoint).input_schema.as_ref(),
+                "example": resources::example_arguments(endpoint),
+            })),
+            meta: None,
+        })
+    }
 
-                Ok(CallToolResult {
-                    content: vec![Content::text(text)],
-                    is_error: Some(false),
-                    meta: None,
-                    structured_content: None,
-                })
+    /// Exposed tool name for an endpoint
+    fn tool_name(&self, endpoint: &PangolinEndpoint) -> String {
+        format!("{}{}", self.tool_prefix, endpoint.name)
+    }
+
+    /// Convert PangolinEndpoint to MCP Tool definition
+    ///
+    /// Properties and the `required` array follow a stable order: path
+    /// parameters first, then query parameters, then body properties, each
+    /// group in spec declaration order.
+    fn endpoint_to_mcp(&self, endpoint: &PangolinEndpoint) -> Tool {
+        let mut properties = serde_json::Map::new();
+        let mut required = Vec::new();
+
+        // Add path parameters (--org-id fills orgId)
+        for param in &endpoint.path_params {
+            if self.org_id.is_some() && param.name == ORG_ID_PARAM {
+                continue;
+            }
+            properties.insert(
+                param.name.clone(),
+                serde_json::Value::Object(parameter_schema(param)),
+            );
+            if param.required {
+                required.push(param.name.clone());
+            }
+        }
+
+        // Add query parameters
+        for param in &endpoint.query_params {
+            properties.insert(
+                param.name.clone(),
+                serde_json::Value::Object(parameter_schema(param)),
+            );
+            if param.required {
+                required.push(param.name.clone());
+            }
+        }
+
+        // Add request body properties
+        if let Some(ref body) = endpoint.request_body {
+            for (name, prop) in &body.properties {
+                properties.insert(
+                    name.clone(),
+                    serde_json::Value::Object(property_schema(prop)),
+                );
             }
-            Err(e) => Ok(CallToolResult {
-                content: vec![Content::text(format!("Error: {}", e))],
-                is_error: Some(true),
-                meta: None,
-                structured_content: None,
+
+            // Add required fields from body, in property declaration order so
+            // they always come after path and query params
+            let declared = body.properties.keys().filter(|k| body.required.contains(k));
+            let undeclared = body
+                .required
+                .iter()
+                .filter(|r| !body.properties.contains_key(*r));
+            for req_field in declared.chain(undeclared) {
+                if !required.contains(req_field) {
+                    required.push(req_field.clone());
+                }
+            }
+        }
+
+        if self.requires_delete_confirm(endpoint) {
+            properties.insert(
+                DELETE_CONFIRM_ARG.to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "description": "Must be true to proceed",
+                }),
+            );
+            required.push(DELETE_CONFIRM_ARG.to_string());
+        }
+
+        properties.insert(
+            FILTER_ARG.to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Return only part of the response: a JSON pointer (/data/niceId) or JSONPath ($.data.sites[*].name)",
+            }),
+        );
+        properties.insert(
+            FIELDS_ARG.to_string(),
+            serde_json::json!({
+                "type": "array",
+                "items": {"type": "string"},
+                "description": "Keep only these keys of the response object, or of every object in an array response (site.name for nested keys)",
             }),
+        );
+        if self.cache.is_some() && endpoint.method == HttpMethod::Get {
+            properties.insert(
+                NO_CACHE_ARG.to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "description": "Fetch a fresh response instead of a cached one",
+                }),
+            );
+        }
+        if self.needs_confirmation(endpoint) {
+            properties.insert(
+                CONFIRM_ARG.to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "description": "Set to true once the user approved this call, for clients that cannot prompt for confirmation",
+                }),
+            );
+        }
+        let content_types = endpoint
+            .request_body
+            .as_ref()
+            .filter(|body| body.content_types.len() > 1);
+        if let Some(body) = content_types {
+            properties.insert(
+                CONTENT_TYPE_ARG.to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "enum": body.content_types,
+                    "description": format!("Request content type (default: {})", body.content_type),
+                }),
+            );
+        }
+        if self.allow_base_url_override {
+            properties.insert(
+                BASE_URL_ARG.to_string(),
+                serde_json::json!({
+                    "type": "string",
+                    "description": "Send this call to another server (http(s) URL replacing the configured base URL)",
+                }),
+            );
+        }
+        // A closed schema must declare every argument the server accepts
+        if self.strict_tool_schemas {
+            properties.insert(
+                DRY_RUN_ARG.to_string(),
+                serde_json::json!({
+                    "type": "boolean",
+                    "description": "Return the HTTP request this call would send without sending it",
+                }),
+            );
         }
-    }
-}
 
-/// Convert a JSON value to a string for URL parameters
-fn value_to_string(value: &serde_json::Value) -> String {
-    match value {
-        serde_json::Value::String(s) => s.clone(),
-        serde_json::Value::Number(n) => n.to_string(),
-        serde_json::Value::Bool(b) => b.to_string(),
-        serde_json::Value::Null => String::new(),
-        other => other.to_string(),
+        let mut schema = serde_json::Map::new();
+        schema.insert(
+            "type".to_string(),
+            serde_json::Value::String("object".to_string()),
+        );
+        schema.insert(
+            "properties".to_string(),
+            serde_json::Value::Object(properties),
+        );
+
+        if !required.is_empty() {
+            let required_arr: Vec<serde_json::Value> = required
+                .iter()
+                .map(|s| serde_json::Value::String(s.clone()))
+                .collect();
+            schema.insert(
+                "required".to_string(),
+                serde_json::Value::Array(required_arr),
+            );
+        }
+        if self.strict_tool_schemas {
+            schema.insert(
+                "additionalProperties".to_string(),
+                serde_json::Value::Bool(false),
+            );
+        }
+
+        // Express body variants as a JSON Schema union instead of merging them
+        let body = endpoint
+            .request_body
+            .as_ref()
+            .filter(|body| !body.variants.is_empty());
+        if let Some(body) = body {
+            let branches: Vec<serde_json::Value> = body
+                .variants
+                .iter()
+                .map(|variant| {
+                    let properties: serde_json::Map<String, serde_json::Value> = variant
+                        .properties
+                        .iter()
+                        .map(|(name, prop)| {
+                            (
+                                name.clone(),
+                                serde_json::Value::Object(property_schema(prop)),
+                            )
+                        })
+                        .collect();
+                    serde_json::json!({
+                        "properties": properties,
+                        "required": variant.required,
+                    })
+                })
+                .collect();
+            let keyword = match body.variant_kind {
+                VariantKind::OneOf => "oneOf",
+                VariantKind::AnyOf => "anyOf",
+            };
+            schema.insert(keyword.to_string(), serde_json::Value::Array(branches));
+        }
+
+        // Build description with method, required arguments and tags
+        let mut desc = format!("[{}] {}", endpoint.method.as_str(), endpoint.description);
+        if !required.is_empty() {
+            if !desc.ends_with(['.', '!', '?']) {
+                desc.push('.');
+            }
+            desc.push_str(&format!(" Requires: {}.", required.join(", ")));
+        }
+        if let Some(body) = body {
+            let discriminator = body.discriminator.as_deref();
+            let summary: Vec<String> = body
+                .variants
+                .iter()
+                .map(|variant| {
+                    let fields: Vec<&str> = variant
+                        .properties
+                        .keys()
+                        .map(String::as_str)
+                        .filter(|name| Some(*name) != discriminator)
+                        .collect();
+                    match discriminator {
+                        Some(discriminator) => format!(
+                            "{}={} ({})",
+                            discriminator,
+                            variant.discriminator_values.join("|"),
+                            fields.join(", ")
+                        ),
+                        None => format!("({})", fields.join(", ")),
+                    }
+                })
+                .collect();
+            match discriminator {
+                Some(discriminator) => desc.push_str(&format!(
+                    " Body depends on '{}': {}.",
+                    discriminator,
+                    summary.join("; ")
+                )),
+                None => desc.push_str(&format!(
+                    " Body must match {} of: {}.",
+                    match body.variant_kind {
+                        VariantKind::OneOf => "exactly one",
+                        VariantKind::AnyOf => "at least one",
+                    },
+                    summary.join("; ")
+                )),
+            }
+        }
+        if !endpoint.tags.is_empty() {
+            desc.push_str(&format!(" (Tags: {})", endpoint.tags.join(", ")));
+        }
+
+        Tool {
+            name: Cow::Owned(self.tool_name(endpoint)),
+            description: Some(Cow::Owned(desc)),
+            input_schema: Arc::new(schema),
+            annotations: Some(tool_annotations(endpoint.method)),
+            icons: None,
+            meta: None,
+            output_schema: None,
+            title: None,
+        }
+    }
+}
+
+impl ServerHandler for PangolinService {
+    fn get_info(&self) -> ServerInfo {
+        let catalog = self.catalog();
+
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_logging()
+                .enable_completions()
+                .enable_prompts()
+                .enable_resources()
+                .enable_tools()
+                .enable_tool_list_changed()
+                .build(),
+            server_info: Implementation {
+                name: "mcp-pangolin".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                icons: None,
+                title: None,
+                website_url: None,
+            },
+            instructions: Some(self.instructions(&catalog)),
+        }
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        info!("Client initialized");
+        self.log_forwarder.set_peer(context.peer.clone());
+        *self.peer.lock().unwrap() = Some(context.peer);
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        debug!("Client set logging level to {:?}", request.level);
+        self.log_forwarder.set_peer(context.peer);
+        self.log_forwarder.set_level(request.level);
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, ErrorData> {
+        let argument = &request.argument;
+        let tool = match &request.r#ref {
+            Reference::Prompt(prompt) => Some(prompt.name.as_str()),
+            Reference::Resource(resource) => {
+                resource.uri.strip_prefix(resources::ENDPOINT_URI_PREFIX)
+            }
+        };
+
+        let catalog = self.catalog();
+        let endpoint = tool.and_then(|name| self.find_endpoint(&catalog, name));
+        let completion = match endpoint.and_then(|e| completions::enum_values(e, &argument.name)) {
+            Some(values) => completions::complete(values, &argument.value),
+            None if argument.name == "orgId" && self.dynamic_completion => {
+                completions::complete(&self.org_ids().await, &argument.value)
+            }
+            None => CompletionInfo::default(),
+        };
+        Ok(CompleteResult { completion })
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let mut tools = if self.generic_mode {
+            Vec::new()
+        } else if self.group_by_tag {
+            self.tag_group_tools()
+        } else {
+            self.tools()
+        };
+        if !self.generic_mode {
+            tools.extend(self.workflow_tools());
+        }
+        tools.extend(self.builtin_tools());
+        let total = tools.len();
+
+        // The cursor is the offset of the next page
+        let start = match request.and_then(|r| r.cursor) {
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .ok()
+                .filter(|offset| *offset <= total)
+                .ok_or_else(|| {
+                    ErrorData::invalid_params(format!("Invalid cursor: {}", cursor), None)
+                })?,
+            None => 0,
+        };
+        let end = self
+            .tools_page_size
+            .map_or(total, |size| (start + size).min(total));
+        debug!("Listing tools {}..{} of {}", start, end, total);
+
+        tools.truncate(end);
+        tools.drain(..start);
+
+        Ok(ListToolsResult {
+            tools,
+            next_cursor: (end < total).then(|| end.to_string()),
+            meta: None,
+        })
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        // Write endpoints stay documented in read-only mode, marked as blocked,
+        // unless --read-only-hide-writes hides them altogether
+        let catalog = self.catalog();
+        let endpoint_docs = catalog
+            .endpoints
+            .iter()
+            .filter(|e| self.is_documented(e))
+            .map(|e| resources::endpoint_to_resource(e, &self.tool_name(e), self.is_blocked(e)));
+        let resources = std::iter::once(resources::status_resource())
+            .chain(catalog.webhooks.iter().map(resources::webhook_to_resource))
+            .chain(endpoint_docs)
+            .collect();
+
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        // Only tools the filters expose, so templates cannot read around them
+        let catalog = self.catalog();
+        let resource_templates = self
+            .available_endpoints(&catalog)
+            .into_iter()
+            .filter(|e| resources::is_templatable(e))
+            .map(|e| resources::endpoint_to_resource_template(e, &self.tool_name(e)))
+            .collect();
+
+        Ok(ListResourceTemplatesResult {
+            resource_templates,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        debug!("Reading resource: {}", request.uri);
+        let catalog = self.catalog();
+
+        if request.uri == resources::STATUS_URI {
+            return Ok(ReadResourceResult {
+                contents: vec![resources::status_contents(&self.status())],
+            });
+        }
+
+        if let Some(name) = request.uri.strip_prefix(resources::WEBHOOK_URI_PREFIX) {
+            if let Some(webhook) = catalog.webhooks.iter().find(|w| w.name == name) {
+                return Ok(ReadResourceResult {
+                    contents: vec![resources::webhook_contents(webhook)],
+                });
+            }
+        }
+
+        if let Some(name) = request.uri.strip_prefix(resources::ENDPOINT_URI_PREFIX) {
+            if let Some(endpoint) = self.find_documented_endpoint(&catalog, name) {
+                return Ok(ReadResourceResult {
+                    contents: vec![resources::endpoint_contents(
+                        endpoint,
+                        name,
+                        self.is_blocked(endpoint),
+                    )],
+                });
+            }
+        }
+
+        // Resource templates: read the GET endpoint whose path matches the URI,
+        // preferring the most specific (most literal segments) match; endpoints
+        // hidden by the filters are not readable
+        let matched = self
+            .available_endpoints(&catalog)
+            .into_iter()
+            .filter(|e| resources::is_templatable(e))
+            .filter_map(|e| Some((e, resources::match_uri_template(&e.path, &request.uri)?)))
+            .min_by_key(|(_, params)| params.len());
+        if let Some((endpoint, path_params)) = matched {
+            if let Some(org_id) = path_params.get(ORG_ID_PARAM) {
+                self.check_org_scope(org_id)?;
+            }
+            let mut result = self
+                .call_api(
+                    endpoint.method,
+                    &endpoint.path,
+                    path_params,
+                    HashMap::new(),
+                    None,
+                    "application/json",
+                )
+                .await
+                .map(|response| response.body)
+                .map_err(|e| {
+                    ErrorData::internal_error(
+                        format!("Failed to read {}: {}", request.uri, e),
+                        None,
+                    )
+                })?;
+
+            // Masked and cut down as tool results are
+            let mut meta = Meta::new();
+            let redacted = self.redactor.redact(&mut result);
+            if redacted > 0 {
+                meta.insert("redacted".to_string(), serde_json::json!(redacted));
+            }
+            if let Some(truncation) = self
+                .max_response_bytes
+                .and_then(|max| truncate::truncate_json(&mut result, max))
+            {
+                meta.insert(
+                    "truncated".to_string(),
+                    serde_json::json!({ "original_bytes": truncation.original_bytes }),
+                );
+            }
+            let text = serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string());
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::TextResourceContents {
+                    uri: request.uri,
+                    mime_type: Some("application/json".to_string()),
+                    text,
+                    meta: (!meta.is_empty()).then_some(meta),
+                }],
+            });
+        }
+
+        Err(ErrorData::resource_not_found(
+            format!("Unknown resource: {}", request.uri),
+            None,
+        ))
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        let prompts = std::iter::once(prompts::getting_started_prompt())
+            .chain(prompts::WORKFLOWS.iter().map(|p| p.to_prompt()))
+            .collect();
+        Ok(ListPromptsResult {
+            prompts,
+            next_cursor: None,
+            meta: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        // Only reference tools callable in the current mode
+        let catalog = self.catalog();
+        let available = self.available_endpoints(&catalog);
+
+        if request.name == prompts::GETTING_STARTED {
+            let text = prompts::getting_started(&available, self.read_only, |e| self.tool_name(e));
+            return Ok(GetPromptResult {
+                description: prompts::getting_started_prompt().description,
+                messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+            });
+        }
+
+        let prompt = prompts::find(&request.name).ok_or_else(|| {
+            ErrorData::invalid_params(format!("Unknown prompt: {}", request.name), None)
+        })?;
+
+        let args = request.arguments.unwrap_or_default();
+        let missing = prompt.missing_arguments(&args);
+        if !missing.is_empty() {
+            return Err(ErrorData::invalid_params(
+                format!("Missing required prompt arguments: {}", missing.join(", ")),
+                None,
+            ));
+        }
+
+        let text = prompt.render(&args, |method, path| {
+            available
+                .iter()
+                .find(|e| e.method.as_str() == method && e.path == path)
+                .map(|e| self.tool_name(e))
+        });
+
+        Ok(GetPromptResult {
+            description: Some(prompt.description.to_string()),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let unprefixed = request.name.strip_prefix(self.tool_prefix.as_str());
+        if let Some(group) = unprefixed.filter(|_| self.group_by_tag) {
+            let catalog = self.catalog();
+            if let Some(operations) = tag_groups(&catalog.endpoints).remove(group) {
+                let args = request.arguments.unwrap_or_default();
+                let request = self.tag_group_request(&request.name, &operations, args)?;
+                return self.call_tool_boxed(request, context).await;
+            }
+        }
+        if let Some(workflow) = self
+            .workflows
+            .iter()
+            .find(|w| unprefixed == Some(w.name.as_str()) && self.tool_filter.permits(&w.name))
+        {
+            let args = request.arguments.unwrap_or_default();
+            return self.run_workflow(workflow, args, context).await;
+        }
+
+        // Built-in tools are answered here; only batch_call reaches the API,
+        // through a call_tool per entry
+        let builtin = request
+            .name
+            .strip_prefix(self.tool_prefix.as_str())
+            .filter(|name| BUILTIN_TOOLS.contains(name) && self.tool_filter.permits(name))
+            .filter(|name| *name != CALL_TOOL || self.generic_mode)
+            .filter(|name| *name != OPENAPI_TOOL || !self.disable_openapi_tool);
+        match builtin {
+            Some(STATS_TOOL) => return Ok(json_result(self.server_stats())),
+            Some(SEARCH_TOOL) => {
+                let args = request.arguments.unwrap_or_default();
+                return self.search_endpoints(&args).map(json_result);
+            }
+            Some(OPENAPI_TOOL) => {
+                let args = request.arguments.unwrap_or_default();
+                return self.openapi_spec(&args).map(json_result);
+            }
+            Some(DESCRIBE_TOOL) => {
+                let args = request.arguments.unwrap_or_default();
+                return self.describe_endpoint(&args);
+            }
+            Some(BATCH_TOOL) => {
+                let args = request.arguments.unwrap_or_default();
+                return self.batch_call(&args, context).await.map(json_result);
+            }
+            Some(CALL_TOOL) => {
+                let args = request.arguments.unwrap_or_default();
+                let request = self.call_endpoint_request(args)?;
+                return self.call_tool_boxed(request, context).await;
+            }
+            _ => {}
+        }
+
+        let started = Instant::now();
+        let mut entry = AuditEntry {
+            tool: request.name.to_string(),
+            ..Default::default()
+        };
+        if self.audit_log.is_some() {
+            entry.timestamp =
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+            entry.arguments =
+                serde_json::Value::Object(request.arguments.clone().unwrap_or_default());
+        }
+        let result = self.run_tool(request, context, &mut entry).await;
+        let duration = started.elapsed();
+        entry.duration_ms = duration.as_millis() as u64;
+        match &result {
+            Ok(result) if result.is_error == Some(true) => {
+                entry.error = result
+                    .structured_content
+                    .as_ref()
+                    .and_then(|c| c["message"].as_str())
+                    .map(str::to_string);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                if let Some(disabled_by) =
+                    e.data.as_ref().and_then(|data| data["disabledBy"].as_str())
+                {
+                    entry.blocked = Some(disabled_by.to_string());
+                }
+                entry.error = Some(e.message.to_string());
+            }
+        }
+
+        // Unknown tools are not tracked
+        if entry.method.is_some() {
+            let outcome = match &result {
+                Ok(result) if result.is_error != Some(true) => CallOutcome::Success,
+                _ if entry.blocked.as_deref() == Some("read_only") => {
+                    CallOutcome::BlockedByReadOnly
+                }
+                _ => CallOutcome::Error,
+            };
+            self.stats.record(&entry.tool, outcome, duration);
+        }
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record(entry);
+        }
+        result
+    }
+}
+
+impl PangolinService {
+    /// Run a tool call, noting in `audit` what was sent and what stopped it
+    async fn run_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+        audit: &mut AuditEntry,
+    ) -> Result<CallToolResult, ErrorData> {
+        let tool_name = request.name.as_ref();
+        debug!(tool = %tool_name, "Calling tool: {}", tool_name);
+
+        // Find the endpoint
+        let catalog = self.catalog();
+        let Some(endpoint) = self.find_endpoint(&catalog, tool_name) else {
+            // --read-only-hide-writes makes blocked tools look nonexistent
+            if let Some(endpoint) = self.find_documented_endpoint(&catalog, tool_name) {
+                audit.method = Some(endpoint.method.as_str());
+                return Err(self.disabled_tool(endpoint));
+            }
+            let similar = self.similar_tools(&catalog, tool_name);
+            return Err(match similar.as_slice() {
+                [] => ErrorData::invalid_params(
+                    format!(
+                        "Unknown tool: {} (the API spec defines no such tool; call tools/list for the available ones)",
+                        tool_name
+                    ),
+                    None,
+                ),
+                [suggestion] => ErrorData::invalid_params(
+                    format!("Unknown tool: {}. Did you mean '{}'?", tool_name, suggestion),
+                    Some(serde_json::json!({ "suggestion": suggestion })),
+                ),
+                candidates => ErrorData::invalid_params(
+                    format!(
+                        "Unknown tool: {}. Several tools have a similar name ({}); call tools/list to pick one",
+                        tool_name,
+                        candidates.join(", ")
+                    ),
+                    Some(serde_json::json!({ "candidates": candidates })),
+                ),
+            });
+        };
+
+        // Extract parameters from arguments
+        let mut args: HashMap<String, serde_json::Value> = match request.arguments {
+            Some(map) => map.into_iter().collect(),
+            None => HashMap::new(),
+        };
+        let mut confirmed_by_argument =
+            args.remove(CONFIRM_ARG) == Some(serde_json::Value::Bool(true));
+        // --require-delete-confirmation: `confirm` is checked here and never
+        // sent upstream; true also answers the `_confirm` fallback
+        if self.requires_delete_confirm(endpoint) {
+            match args.remove(DELETE_CONFIRM_ARG) {
+                Some(serde_json::Value::Bool(true)) => confirmed_by_argument = true,
+                other => {
+                    return Err(ErrorData::invalid_params(
+                        format!(
+                            "{} deletes data and only runs with \"{}\": true (got {}); check with the user first",
+                            tool_name,
+                            DELETE_CONFIRM_ARG,
+                            other.map_or("no value".to_string(), |value| value.to_string())
+                        ),
+                        Some(serde_json::json!({ "confirmationRequired": true })),
+                    ))
+                }
+            }
+        }
+        let dry_run =
+            args.remove(DRY_RUN_ARG) == Some(serde_json::Value::Bool(true)) || self.dry_run;
+        let no_cache = args.remove(NO_CACHE_ARG) == Some(serde_json::Value::Bool(true));
+        let override_client = match args.remove(BASE_URL_ARG) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(_) if !self.allow_base_url_override => {
+                return Err(ErrorData::invalid_params(
+                    format!(
+                        "Argument '{}' is not allowed; start the server with --allow-base-url-override to accept it",
+                        BASE_URL_ARG
+                    ),
+                    None,
+                ))
+            }
+            Some(serde_json::Value::String(base_url)) => {
+                let client = self.client.with_base_url(&base_url).map_err(|e| {
+                    ErrorData::invalid_params(
+                        format!("Argument '{}' is invalid: {:#}", BASE_URL_ARG, e),
+                        None,
+                    )
+                })?;
+                info!(tool = %tool_name, "Sending this call to {}", base_url);
+                Some(client)
+            }
+            Some(other) => {
+                return Err(ErrorData::invalid_params(
+                    format!("Argument '{}' must be a string, got {}", BASE_URL_ARG, other),
+                    None,
+                ))
+            }
+        };
+        let client = override_client.as_ref().unwrap_or(&self.client);
+        let requested_content_type = match args.remove(CONTENT_TYPE_ARG) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(content_type)) => {
+                let accepted = endpoint
+                    .request_body
+                    .as_ref()
+                    .map_or(&[][..], |body| body.content_types.as_slice());
+                if !accepted.contains(&content_type) {
+                    return Err(ErrorData::invalid_params(
+                        format!(
+                            "Argument '{}' must be one of the content types {} accepts: {}",
+                            CONTENT_TYPE_ARG,
+                            tool_name,
+                            if accepted.is_empty() {
+                                "none (it takes no body)".to_string()
+                            } else {
+                                accepted.join(", ")
+                            }
+                        ),
+                        None,
+                    ));
+                }
+                Some(content_type)
+            }
+            Some(other) => {
+                return Err(ErrorData::invalid_params(
+                    format!(
+                        "Argument '{}' must be a string, got {}",
+                        CONTENT_TYPE_ARG, other
+                    ),
+                    None,
+                ))
+            }
+        };
+        // Checked up front so a bad expression never costs an API call
+        let filter = match args.remove(FILTER_ARG) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(expression)) => Some(
+                ResponseFilter::parse(&expression)
+                    .map_err(|message| ErrorData::invalid_params(message, None))?,
+            ),
+            Some(other) => {
+                return Err(ErrorData::invalid_params(
+                    format!("Argument '{}' must be a string, got {}", FILTER_ARG, other),
+                    None,
+                ))
+            }
+        };
+
+        let fields = match args.remove(FIELDS_ARG) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::Array(items)) => Some(
+                items
+                    .into_iter()
+                    .map(|item| match item {
+                        serde_json::Value::String(field) => Ok(field),
+                        other => Err(ErrorData::invalid_params(
+                            format!(
+                                "Argument '{}' must be an array of strings, got item {}",
+                                FIELDS_ARG, other
+                            ),
+                            None,
+                        )),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            Some(other) => {
+                return Err(ErrorData::invalid_params(
+                    format!(
+                        "Argument '{}' must be an array of strings, got {}",
+                        FIELDS_ARG, other
+                    ),
+                    None,
+                ))
+            }
+        };
+
+        // --org-id fills orgId and refuses any other organization
+        if let Some(org_id) = &self.org_id {
+            if endpoint.path_params.iter().any(|p| p.name == ORG_ID_PARAM) {
+                if let Some(value) = args.get(ORG_ID_PARAM).filter(|v| !v.is_null()) {
+                    self.check_org_scope(&value_to_string(value))?;
+                }
+                args.insert(
+                    ORG_ID_PARAM.to_string(),
+                    serde_json::Value::String(org_id.clone()),
+                );
+            }
+        }
+
+        // Report every schema violation at once rather than one per round-trip
+        if !self.skip_arg_validation {
+            let violations = argument_violations(endpoint, &args);
+            if !violations.is_empty() {
+                return Err(invalid_arguments(tool_name, &violations));
+            }
+        }
+
+        // Separate path params, query params, and body params
+        let mut path_params: HashMap<String, String> = HashMap::new();
+        let mut query_params: HashMap<String, String> = HashMap::new();
+        let mut body_params: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+
+        // Extract path parameters (falling back to the documented default)
+        // Null path and query arguments count as omitted
+        let arg = |name: &str| args.get(name).filter(|value| !value.is_null());
+
+        for param in &endpoint.path_params {
+            if let Some(value) = arg(&param.name).or(param.default_value.as_ref()) {
+                // An empty value would collapse its segment and reach another route
+                let value = param_to_string(value, param);
+                if value.is_empty() {
+                    return Err(ErrorData::invalid_params(
+                        format!("Path parameter '{}' must not be empty", param.name),
+                        None,
+                    ));
+                }
+                path_params.insert(param.name.clone(), value);
+            } else if param.required {
+                return Err(ErrorData::invalid_params(
+                    format!("Missing required path parameter: {}", param.name),
+                    None,
+                ));
+            }
+        }
+
+        // Extract query parameters, sending documented defaults for omitted
+        // ones, or else --default-page-size for a GET's page size parameter;
+        // booleans follow --bool-query-style
+        for param in &endpoint.query_params {
+            if let Some(value) = arg(&param.name).or(param.default_value.as_ref()) {
+                let flag = value
+                    .as_bool()
+                    .filter(|_| param.param_type == ParameterType::Boolean);
+                match flag {
+                    Some(flag) => {
+                        if let Some(text) = self.bool_query_style.render(flag) {
+                            query_params.insert(param.name.clone(), text);
+                        }
+                    }
+                    None => {
+                        query_params.insert(param.name.clone(), param_to_string(value, param));
+                    }
+                }
+            } else if let Some(size) = self
+                .default_page_size
+                .filter(|_| endpoint.method == HttpMethod::Get && is_page_size_param(param))
+            {
+                debug!(tool = %tool_name, "Defaulting {} to {}", param.name, size);
+                query_params.insert(param.name.clone(), size.to_string());
+            }
+        }
+
+        // Extract body parameters (everything else goes to body), coercing
+        // string-encoded values to the declared property types and dropping
+        // readOnly fields the API would reject
+        let mut dropped_read_only: Vec<String> = Vec::new();
+        let mut array_body = None;
+        if let Some(ref request_body) = endpoint.request_body {
+            if request_body.free_form {
+                match args.get(FREE_FORM_BODY) {
+                    Some(serde_json::Value::Object(map)) => body_params = map.clone(),
+                    Some(_) => {
+                        return Err(ErrorData::invalid_params(
+                            format!("Argument '{}' must be a JSON object", FREE_FORM_BODY),
+                            None,
+                        ))
+                    }
+                    None => {}
+                }
+            }
+
+            if request_body.array_body {
+                if let Some(value) = args.get(ARRAY_BODY) {
+                    let value = match request_body.properties.get(ARRAY_BODY) {
+                        Some(prop) => coerce_value(value, prop),
+                        None => value.clone(),
+                    };
+                    if !value.is_array() {
+                        return Err(ErrorData::invalid_params(
+                            format!("Argument '{}' must be a JSON array", ARRAY_BODY),
+                            None,
+                        ));
+                    }
+                    array_body = Some(value);
+                }
+            }
+
+            let whole_body = request_body.free_form || request_body.array_body;
+            let closed = request_body.additional_properties == Some(false);
+            let mut unexpected: Vec<&str> = Vec::new();
+            for (key, value) in args.iter().filter(|_| !whole_body) {
+                let is_path_param = endpoint.path_params.iter().any(|p| &p.name == key);
+                let is_query_param = endpoint.query_params.iter().any(|p| &p.name == key);
+
+                if request_body.read_only_properties.contains(key) {
+                    dropped_read_only.push(key.clone());
+                } else if closed
+                    && !is_path_param
+                    && !is_query_param
+                    && !request_body.properties.contains_key(key)
+                {
+                    unexpected.push(key);
+                } else if !is_path_param && !is_query_param {
+                    let value = match request_body.properties.get(key) {
+                        Some(prop) => coerce_value(value, prop),
+                        None => value.clone(),
+                    };
+                    body_params.insert(key.clone(), value);
+                }
+            }
+            if !unexpected.is_empty() {
+                unexpected.sort();
+                return Err(ErrorData::invalid_params(
+                    format!(
+                        "Unexpected arguments for {}: {} (the body accepts no undeclared fields)",
+                        tool_name,
+                        unexpected.join(", ")
+                    ),
+                    None,
+                ));
+            }
+
+            // Required `const` properties have only one possible value
+            for name in &request_body.required {
+                if let Some(value) = request_body
+                    .properties
+                    .get(name)
+                    .and_then(|prop| prop.const_value.as_ref())
+                {
+                    body_params
+                        .entry(name.clone())
+                        .or_insert_with(|| value.clone());
+                }
+            }
+        }
+
+        // Renamed fields go out under their real name
+        if let Some(ref request_body) = endpoint.request_body {
+            for (argument, field) in &request_body.renamed {
+                if let Some(value) = body_params.remove(argument) {
+                    body_params.insert(field.clone(), value);
+                }
+            }
+        }
+
+        // An optional body with nothing set is left out rather than sent as {}
+        let body_required = endpoint
+            .request_body
+            .as_ref()
+            .is_some_and(|request_body| request_body.body_required);
+        let body = if array_body.is_some() {
+            array_body
+        } else if body_params.is_empty() && !body_required {
+            None
+        } else {
+            Some(serde_json::Value::Object(body_params))
+        };
+
+        let mut meta = Meta::new();
+        if !dropped_read_only.is_empty() {
+            dropped_read_only.sort();
+            warn!(
+                "Dropped readOnly fields from {} body: {}",
+                tool_name,
+                dropped_read_only.join(", ")
+            );
+            meta.insert(
+                "dropped_read_only_fields".to_string(),
+                serde_json::json!(dropped_read_only),
+            );
+        }
+
+        let content_type = requested_content_type.as_deref().unwrap_or_else(|| {
+            endpoint
+                .request_body
+                .as_ref()
+                .map_or("application/json", |b| b.content_type.as_str())
+        });
+
+        audit.method = Some(endpoint.method.as_str());
+        audit.path = Some(client.path(&endpoint.path, &path_params));
+
+        if dry_run {
+            audit.blocked = Some("dry_run".to_string());
+            let request = client
+                .preview(
+                    endpoint.method,
+                    &endpoint.path,
+                    path_params,
+                    query_params,
+                    body,
+                    content_type,
+                )
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+            info!(tool = %tool_name, "Dry run of {} {}", endpoint.method.as_str(), request["url"]);
+            let summary = format!(
+                "DRY RUN - NOT EXECUTED: {} {}",
+                endpoint.method.as_str(),
+                request["url"].as_str().unwrap_or_default()
+            );
+            let text = format!(
+                "{}\n{}",
+                summary,
+                serde_json::to_string_pretty(&request).unwrap_or_default()
+            );
+            return Ok(CallToolResult {
+                content: vec![Content::text(text)],
+                is_error: Some(false),
+                structured_content: Some(serde_json::json!({
+                    "dryRun": true,
+                    "executed": false,
+                    "request": request,
+                })),
+                meta: (!meta.is_empty()).then_some(meta),
+            });
+        }
+
+        if self.needs_confirmation(endpoint) {
+            let url = client.url(&endpoint.path, &path_params);
+            self.confirm_call(
+                tool_name,
+                endpoint,
+                &url,
+                &args,
+                confirmed_by_argument,
+                &context.peer,
+            )
+            .await
+            .inspect_err(|_| audit.blocked = Some("confirmation".to_string()))?;
+        }
+
+        // Writes spend a token of --write-rate-limit; none reach the API once
+        // the budget is spent
+        let write_limit = self
+            .write_limit
+            .as_ref()
+            .filter(|_| endpoint.method.is_write_operation());
+        if let Some(limit) = write_limit {
+            if let Err(wait) = limit.try_acquire() {
+                audit.blocked = Some("rate_limit".to_string());
+                let retry_after = wait.as_secs_f64().ceil() as u64;
+                let message = format!(
+                    "Write rate limit reached ({} per minute); capacity returns in {}s",
+                    limit.limit(),
+                    retry_after
+                );
+                warn!(tool = %tool_name, "{}", message);
+                return Ok(CallToolResult {
+                    content: vec![Content::text(format!("Error: {}", message))],
+                    is_error: Some(true),
+                    structured_content: Some(serde_json::json!({
+                        "status": null,
+                        "message": message,
+                        "retry_after_secs": retry_after,
+                    })),
+                    meta: (!meta.is_empty()).then_some(meta),
+                });
+            }
+        }
+
+        // GETs are answered from the cache when possible; `_no_cache` refreshes
+        // and calls to another server bypass it
+        let resolved_path = client.path(&endpoint.path, &path_params);
+        let cache = self.cache.as_ref().filter(|_| override_client.is_none());
+        let cache_key = cache
+            .filter(|_| endpoint.method == HttpMethod::Get)
+            .map(|_| ResponseCache::key(&resolved_path, &query_params));
+        let hit = cache
+            .zip(cache_key.as_ref())
+            .filter(|_| !no_cache)
+            .and_then(|(cache, key)| cache.get(key));
+        if cache_key.is_some() {
+            meta.insert(
+                "cache".to_string(),
+                match &hit {
+                    Some(hit) => serde_json::json!({
+                        "hit": true,
+                        "age_ms": hit.age.as_millis() as u64,
+                    }),
+                    None => serde_json::json!({"hit": false}),
+                },
+            );
+        }
+
+        // Call the Pangolin API
+        let from_cache = hit.is_some();
+        let call_started = Instant::now();
+        let outcome = if let Some(hit) = hit {
+            debug!(tool = %tool_name, "Cached response for {}", resolved_path);
+            Ok(ApiResponse {
+                status: hit.status,
+                method: endpoint.method,
+                path: resolved_path.clone(),
+                body: hit.response,
+                elapsed: Duration::ZERO,
+                attempts: 0,
+            })
+        } else {
+            // `client` is the configured one unless `_base_url` replaced it
+            let call = async {
+                let _permit = self.upstream_permit().await?;
+                client
+                    .call(
+                        endpoint.method,
+                        &endpoint.path,
+                        path_params,
+                        query_params,
+                        body,
+                        content_type,
+                    )
+                    .await
+            };
+            let progress_token = context.meta.get_progress_token();
+            let pending = async {
+                match progress_token {
+                    Some(token) => self.with_progress(call, token, &context.peer).await,
+                    None => call.await,
+                }
+            };
+
+            // Dropping the pending future on cancellation aborts the HTTP request
+            tokio::select! {
+                outcome = pending => outcome,
+                _ = context.ct.cancelled() => {
+                    if endpoint.method.is_write_operation() {
+                        warn!(
+                            "Cancelled {} {} while in flight: the Pangolin API may still have applied the change",
+                            endpoint.method.as_str(),
+                            endpoint.path
+                        );
+                    } else {
+                        info!("Cancelled {} while in flight", tool_name);
+                    }
+                    let message = format!("Tool call '{}' was cancelled", tool_name);
+                    return Ok(CallToolResult {
+                        content: vec![Content::text(format!("Error: {}", message))],
+                        is_error: Some(true),
+                        structured_content: Some(serde_json::json!({
+                            "status": null,
+                            "message": message,
+                        })),
+                        meta: (!meta.is_empty()).then_some(meta),
+                    });
+                }
+            }
+        };
+        match outcome {
+            Ok(response) => {
+                let status = response.status;
+                meta.insert(
+                    "upstream".to_string(),
+                    serde_json::json!({
+                        "duration_ms": response.elapsed.as_millis() as u64,
+                        "http_status": status,
+                        "url": response.path,
+                        "cached": from_cache,
+                        "attempts": response.attempts,
+                    }),
+                );
+                let mut result = response.body;
+                audit.status = Some(status);
+                if let Some(cache) = cache {
+                    match cache_key {
+                        Some(key) if !from_cache => {
+                            cache.insert(key, resolved_path.clone(), status, result.clone())
+                        }
+                        Some(_) => {}
+                        None => {
+                            let dropped = cache.invalidate(&resolved_path);
+                            debug!("{} dropped {} cached response(s)", tool_name, dropped);
+                        }
+                    }
+                }
+                // Before filtering, so `_filter` cannot select a masked value
+                let redacted = self.redactor.redact(&mut result);
+                if redacted > 0 {
+                    meta.insert("redacted".to_string(), serde_json::json!(redacted));
+                }
+                if let Some(filter) = &filter {
+                    let original_bytes = serde_json::to_string(&result).map_or(0, |s| s.len());
+                    result = match filter.apply(&result) {
+                        Ok(selected) => selected,
+                        Err(message) => {
+                            return Ok(CallToolResult {
+                                content: vec![Content::text(format!("Error: {}", message))],
+                                is_error: Some(true),
+                                structured_content: Some(serde_json::json!({
+                                    "status": null,
+                                    "message": message,
+                                })),
+                                meta: (!meta.is_empty()).then_some(meta),
+                            });
+                        }
+                    };
+                    meta.insert(
+                        "filtered".to_string(),
+                        serde_json::json!({
+                            "filter": filter.expression(),
+                            "original_bytes": original_bytes,
+                        }),
+                    );
+                }
+
+                if let Some(fields) = &fields {
+                    let dropped = project_fields(&mut result, fields);
+                    meta.insert(
+                        "projected".to_string(),
+                        serde_json::json!({
+                            "fields": fields,
+                            "dropped_keys": dropped,
+                        }),
+                    );
+                }
+
+                let truncation = self
+                    .max_response_bytes
+                    .and_then(|max| truncate::truncate_json(&mut result, max));
+                let text =
+                    serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string());
+                let mut content = vec![Content::text(text.clone())];
+                if let Some(truncation) = truncation {
+                    let note = truncation_note(endpoint, &truncation);
+                    debug!(tool = %tool_name, "Truncated response: {}", note);
+                    meta.insert(
+                        "truncated".to_string(),
+                        serde_json::json!({
+                            "original_bytes": truncation.original_bytes,
+                            "arrays": truncation
+                                .arrays
+                                .iter()
+                                .map(|a| serde_json::json!({
+                                    "path": a.path,
+                                    "shown": a.shown,
+                                    "total": a.total,
+                                }))
+                                .collect::<Vec<_>>(),
+                            "shortened_strings": truncation.strings,
+                        }),
+                    );
+                    content.push(Content::text(note));
+                }
+
+                // Plain-text responses have no structured form
+                let wrapped = !result.is_object();
+                let structured_content = if result.is_string() {
+                    None
+                } else if text.len() > MAX_STRUCTURED_CONTENT_BYTES {
+                    meta.insert(
+                        "structured_content_omitted".to_string(),
+                        serde_json::json!(format!(
+                            "response exceeds {} bytes",
+                            MAX_STRUCTURED_CONTENT_BYTES
+                        )),
+                    );
+                    None
+                } else {
+                    Some(structured_result(result))
+                };
+
+                // Mismatches against the documented success schema are reported, never fatal
+                if let Some(schema) = success_schema(endpoint) {
+                    let mut mismatches = Vec::new();
+                    if let Some(content) = &structured_content {
+                        // Checked as returned, not as wrapped by `structured_result`
+                        let value = if wrapped { &content["result"] } else { content };
+                        schema_mismatches(value, schema, "$", &mut mismatches);
+                    }
+                    if !mismatches.is_empty() {
+                        debug!(
+                            "{} response does not match its schema: {:?}",
+                            tool_name, mismatches
+                        );
+                        meta.insert(
+                            "output_schema_mismatches".to_string(),
+                            serde_json::json!(mismatches),
+                        );
+                    }
+                }
+
+                Ok(CallToolResult {
+                    content,
+                    is_error: Some(false),
+                    meta: (!meta.is_empty()).then_some(meta),
+                    structured_content,
+                })
+            }
+            Err(e) => {
+                let api_error = e.downcast_ref::<PangolinApiError>();
+                let status = api_error.map(|error| error.status.as_u16());
+                audit.status = status;
+                meta.insert(
+                    "upstream".to_string(),
+                    serde_json::json!({
+                        "duration_ms": api_error
+                            .map_or_else(|| call_started.elapsed(), |error| error.elapsed)
+                            .as_millis() as u64,
+                        "http_status": status,
+                        "url": resolved_path,
+                        "cached": false,
+                        "attempts": api_error.map(|error| error.attempts),
+                    }),
+                );
+                warn!(tool = %tool_name, status, "Tool call {} failed: {}", tool_name, e);
+                if let Some(error) = upstream_error(&e) {
+                    return Err(error);
+                }
+                let Some(api_error) = api_error else {
+                    return Ok(CallToolResult {
+                        content: vec![Content::text(format!("Error: {}", e))],
+                        is_error: Some(true),
+                        structured_content: Some(serde_json::json!({
+                            "status": null,
+                            "message": e.to_string(),
+                        })),
+                        meta: (!meta.is_empty()).then_some(meta),
+                    });
+                };
+                let mut content = api_error.to_json();
+                if let Some(kind) = api_error_kind(api_error.status) {
+                    content["kind"] = kind.into();
+                }
+                Ok(CallToolResult {
+                    content: vec![Content::text(api_error_text(api_error))],
+                    is_error: Some(true),
+                    structured_content: Some(content),
+                    meta: (!meta.is_empty()).then_some(meta),
+                })
+            }
+        }
+    }
+}
+
+/// Remove an optional object argument of a built-in tool
+fn take_object_argument(
+    args: &mut serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Result<Option<serde_json::Map<String, serde_json::Value>>, ErrorData> {
+    match args.remove(name) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::Object(object)) => Ok(Some(object)),
+        Some(_) => Err(ErrorData::invalid_params(
+            format!("Argument '{}' must be an object", name),
+            None,
+        )),
+    }
+}
+
+/// Optional string argument of a built-in tool
+fn string_argument<'a>(
+    args: &'a serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Result<Option<&'a str>, ErrorData> {
+    match args.get(name) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value.as_str())),
+        Some(other) => Err(ErrorData::invalid_params(
+            format!("Argument '{}' must be a string, got {}", name, other),
+            None,
+        )),
+    }
+}
+
+fn required_string_argument<'a>(
+    args: &'a serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Result<&'a str, ErrorData> {
+    string_argument(args, name)?.ok_or_else(|| {
+        ErrorData::invalid_params(format!("Missing required argument: {}", name), None)
+    })
+}
+
+/// A tool result as JSON: its structured content, or else its text
+fn result_value(result: CallToolResult) -> serde_json::Value {
+    if let Some(structured) = result.structured_content {
+        return structured;
+    }
+    let text: Vec<_> = result
+        .content
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+        .collect();
+    text.join("\n").into()
+}
+
+/// Endpoints by `--group-by-tag` tool name: the slug of their first tag, or
+/// `untagged`
+/// Join the instructions, keeping as many tag lines as fit in `max`
+/// characters (0 is unlimited) and counting the dropped ones; text still too
+/// long is cut with an ellipsis
+fn fit_instructions(head: &str, tag_lines: &[String], tail: &str, max: usize) -> String {
+    let omitted = |count: usize| {
+        format!(
+            "- ...and {} more tag(s); call search_endpoints to find their tools\n",
+            count
+        )
+    };
+    let length = |text: &str| text.chars().count();
+    let mut text = head.to_string();
+    let mut used = length(head) + length(tail);
+    for (shown, line) in tag_lines.iter().enumerate() {
+        let rest = tag_lines.len() - shown - 1;
+        let needed = length(line) + 1 + if rest > 0 { length(&omitted(rest)) } else { 0 };
+        if max > 0 && used + needed > max {
+            text.push_str(&omitted(tag_lines.len() - shown));
+            break;
+        }
+        text.push_str(line);
+        text.push('\n');
+        used += length(line) + 1;
+    }
+    if tag_lines.is_empty() {
+        text.push_str("- none\n");
+    }
+    text.push_str(tail);
+
+    if max > 0 && length(&text) > max {
+        text = text.chars().take(max.saturating_sub(1)).collect();
+        text.push('…');
+    }
+    text
+}
+
+/// Endpoints taking an `orgId` path parameter, the only ones --org-id can
+/// confine
+fn is_org_addressed(endpoint: &PangolinEndpoint) -> bool {
+    endpoint.path_params.iter().any(|p| p.name == ORG_ID_PARAM)
+}
+
+fn tag_groups<'a>(
+    endpoints: impl IntoIterator<Item = &'a PangolinEndpoint>,
+) -> BTreeMap<String, Vec<&'a PangolinEndpoint>> {
+    let mut groups: BTreeMap<String, Vec<&PangolinEndpoint>> = BTreeMap::new();
+    for endpoint in endpoints {
+        let group = match endpoint.tags.first() {
+            Some(tag) => tag
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect(),
+            None => "untagged".to_string(),
+        };
+        groups.entry(group).or_default().push(endpoint);
+    }
+    groups
+}
+
+/// Scalar query parameter named like a page size (`limit`, `pageSize`, ...)
+fn is_page_size_param(param: &EndpointParameter) -> bool {
+    matches!(
+        param.param_type,
+        ParameterType::Integer | ParameterType::Number | ParameterType::String
+    ) && PAGE_SIZE_PARAMS.contains(&param.name.to_lowercase().as_str())
+}
+
+/// Response of a successful tool call as JSON: its text parsed back, or
+/// else `result_value`
+fn step_response(result: CallToolResult) -> serde_json::Value {
+    result
+        .content
+        .first()
+        .and_then(|c| c.as_text())
+        .and_then(|t| serde_json::from_str(&t.text).ok())
+        .unwrap_or_else(|| result_value(result))
+}
+
+/// Successful result carrying a JSON document as text and structured content
+fn json_result(value: serde_json::Value) -> CallToolResult {
+    CallToolResult {
+        content: vec![Content::text(
+            serde_json::to_string_pretty(&value).unwrap_or_default(),
+        )],
+        is_error: Some(false),
+        structured_content: Some(value),
+        meta: None,
+    }
+}
+
+/// `_filter` expression selecting part of a response
+enum ResponseFilter {
+    /// JSON pointer (`/data/niceId`)
+    Pointer(String),
+    /// JSONPath (`$.data.sites[*].name`)
+    Path(String, JsonPath),
+}
+
+impl ResponseFilter {
+    fn parse(expression: &str) -> Result<Self, String> {
+        let expression = expression.trim();
+        if expression.starts_with('/') {
+            Ok(Self::Pointer(expression.to_string()))
+        } else if expression.starts_with('$') {
+            JsonPath::parse(expression)
+                .map(|path| Self::Path(expression.to_string(), path))
+                .map_err(|e| format!("Invalid {} '{}': {}", FILTER_ARG, expression, e))
+        } else {
+            Err(format!(
+                "Invalid {} '{}': expected a JSON pointer (/data/niceId) or a JSONPath ($.data.niceId)",
+                FILTER_ARG, expression
+            ))
+        }
+    }
+
+    fn expression(&self) -> &str {
+        match self {
+            Self::Pointer(expression) | Self::Path(expression, _) => expression,
+        }
+    }
+
+    /// The selected fragment: a single value, or an array of every match for
+    /// wildcard paths
+    fn apply(&self, response: &serde_json::Value) -> Result<serde_json::Value, String> {
+        let selected = match self {
+            Self::Pointer(pointer) => response.pointer(pointer).cloned(),
+            Self::Path(_, path) => {
+                let matches = path.select(response);
+                if path.is_singular() {
+                    matches.first().map(|v| (*v).clone())
+                } else if matches.is_empty() {
+                    None
+                } else {
+                    Some(matches.into_iter().cloned().collect())
+                }
+            }
+        };
+        selected.ok_or_else(|| {
+            let keys = response
+                .as_object()
+                .map(|o| o.keys().cloned().collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+            if keys.is_empty() {
+                format!(
+                    "{} '{}' matched nothing in the response",
+                    FILTER_ARG,
+                    self.expression()
+                )
+            } else {
+                format!(
+                    "{} '{}' matched nothing in the response (top-level keys: {})",
+                    FILTER_ARG,
+                    self.expression(),
+                    keys
+                )
+            }
+        })
+    }
+}
+
+/// Keep only `fields` of an object, or of every object in an array, where
+/// `site.name` keeps `name` inside `site`; unknown fields are ignored.
+/// Returns the number of keys dropped
+fn project_fields(value: &mut serde_json::Value, fields: &[String]) -> usize {
+    match value {
+        serde_json::Value::Array(items) => items
+            .iter_mut()
+            .map(|item| project_fields(item, fields))
+            .sum(),
+        serde_json::Value::Object(object) => {
+            let mut dropped = 0;
+            object.retain(|key, _| {
+                let kept = fields.iter().any(|field| {
+                    field == key || field.split_once('.').is_some_and(|(head, _)| head == key)
+                });
+                if !kept {
+                    dropped += 1;
+                }
+                kept
+            });
+            for (key, child) in object.iter_mut() {
+                // A bare field keeps the whole value
+                if fields.iter().any(|field| field == key) {
+                    continue;
+                }
+                let nested: Vec<String> = fields
+                    .iter()
+                    .filter_map(|field| field.split_once('.'))
+                    .filter(|(head, _)| head == key)
+                    .map(|(_, rest)| rest.to_string())
+                    .collect();
+                dropped += project_fields(child, &nested);
+            }
+            dropped
+        }
+        _ => 0,
+    }
+}
+
+/// Tool name reduced for fuzzy matching: lowercase, without separators
+fn normalize_tool_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | '.' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Note telling the agent what a truncated response left out and how to
+/// ask for less
+fn truncation_note(endpoint: &PangolinEndpoint, truncation: &truncate::Truncation) -> String {
+    let mut parts: Vec<String> = truncation
+        .arrays
+        .iter()
+        .map(|a| {
+            let at = if a.path == "$" {
+                String::new()
+            } else {
+                format!(" of {}", a.path)
+            };
+            format!("showing {} of {} items{}", a.shown, a.total, at)
+        })
+        .collect();
+    if truncation.strings > 0 {
+        parts.push(format!("{} long strings shortened", truncation.strings));
+    }
+
+    let paging: Vec<&str> = endpoint
+        .query_params
+        .iter()
+        .map(|p| p.name.as_str())
+        .filter(|name| ["limit", "offset", "page", "pageSize", "perPage", "cursor"].contains(name))
+        .collect();
+    let hint = if paging.is_empty() {
+        "narrow the request to see the rest".to_string()
+    } else {
+        format!("refine with {}", paging.join("/"))
+    };
+    format!(
+        "truncated: {} ({} bytes in full), {}",
+        parts.join(", "),
+        truncation.original_bytes,
+        hint
+    )
+}
+
+/// Behaviour hints clients use to decide when to ask for confirmation
+///
+/// Every tool calls a remote API, hence open-world.
+fn tool_annotations(method: HttpMethod) -> ToolAnnotations {
+    let annotations = ToolAnnotations::new().open_world(true);
+    match method {
+        HttpMethod::Get => annotations.read_only(true),
+        HttpMethod::Delete => annotations
+            .read_only(false)
+            .destructive(true)
+            .idempotent(true),
+        HttpMethod::Put => annotations
+            .read_only(false)
+            .destructive(false)
+            .idempotent(true),
+        HttpMethod::Post | HttpMethod::Patch => annotations.read_only(false).idempotent(false),
+    }
+}
+
+/// Maximum serialized size of an example emitted in a tool schema
+const MAX_EXAMPLE_LEN: usize = 200;
+
+/// Maximum size of an example quoted inline in a property description
+const MAX_INLINE_EXAMPLE_LEN: usize = 60;
+
+/// JSON Schema for a path or query parameter
+fn parameter_schema(param: &EndpointParameter) -> serde_json::Map<String, serde_json::Value> {
+    let mut prop = serde_json::Map::new();
+    prop.insert(
+        "type".to_string(),
+        serde_json::Value::String(param.param_type.to_json_schema_type().to_string()),
+    );
+    if let Some(ref desc) = param.description {
+        prop.insert(
+            "description".to_string(),
+            serde_json::Value::String(desc.clone()),
+        );
+    }
+    if let Some(ref format) = param.format {
+        prop.insert(
+            "format".to_string(),
+            serde_json::Value::String(format.clone()),
+        );
+    }
+    if let Some(ref default) = param.default_value {
+        prop.insert("default".to_string(), default.clone());
+    }
+    if let Some(ref values) = param.enum_values {
+        prop.insert("enum".to_string(), serde_json::json!(values));
+    }
+    if let Some(ref example) = param.example {
+        insert_example(&mut prop, example);
+    }
+    prop
+}
+
+/// JSON Schema for a request body property
+fn property_schema(prop: &PropertySchema) -> serde_json::Map<String, serde_json::Value> {
+    let mut schema_prop = serde_json::Map::new();
+    let json_type = prop.param_type.to_json_schema_type();
+    // Nullable properties accept an explicit null (e.g. to clear a field)
+    let schema_type = if prop.nullable {
+        serde_json::json!([json_type, "null"])
+    } else {
+        serde_json::Value::String(json_type.to_string())
+    };
+    schema_prop.insert("type".to_string(), schema_type);
+    if let Some(ref desc) = prop.description {
+        schema_prop.insert(
+            "description".to_string(),
+            serde_json::Value::String(desc.clone()),
+        );
+    }
+    if let Some(ref format) = prop.format {
+        schema_prop.insert(
+            "format".to_string(),
+            serde_json::Value::String(format.clone()),
+        );
+    }
+    if let Some(ref default) = prop.default_value {
+        schema_prop.insert("default".to_string(), default.clone());
+    }
+    if let Some(ref enum_vals) = prop.enum_values {
+        let mut enum_arr: Vec<serde_json::Value> = enum_vals
+            .iter()
+            .map(|s| serde_json::Value::String(s.clone()))
+            .collect();
+        if prop.nullable {
+            enum_arr.push(serde_json::Value::Null);
+        }
+        schema_prop.insert("enum".to_string(), serde_json::Value::Array(enum_arr));
+    }
+    if let Some(ref const_value) = prop.const_value {
+        schema_prop.insert("const".to_string(), const_value.clone());
+    }
+    if let Some(ref minimum) = prop.minimum {
+        schema_prop.insert("minimum".to_string(), schema_bound(minimum, prop));
+    }
+    if let Some(ref maximum) = prop.maximum {
+        schema_prop.insert("maximum".to_string(), schema_bound(maximum, prop));
+    }
+    if let Some(multiple_of) = prop.multiple_of {
+        schema_prop.insert("multipleOf".to_string(), multiple_of.into());
+    }
+    if let Some(min_items) = prop.min_items {
+        schema_prop.insert("minItems".to_string(), min_items.into());
+    }
+    if let Some(max_items) = prop.max_items {
+        schema_prop.insert("maxItems".to_string(), max_items.into());
+    }
+    if prop.unique_items {
+        schema_prop.insert("uniqueItems".to_string(), true.into());
+    }
+    if let Some(ref example) = prop.example {
+        insert_example(&mut schema_prop, example);
+    }
+    schema_prop
+}
+
+/// A `minimum`/`maximum` bound, written as an integer for integer properties
+/// (`1`, not `1.0`)
+fn schema_bound(bound: &serde_json::Number, prop: &PropertySchema) -> serde_json::Value {
+    match bound.as_f64() {
+        Some(float)
+            if prop.param_type == ParameterType::Integer
+                && !bound.is_i64()
+                && !bound.is_u64()
+                && float.fract() == 0.0
+                && float.abs() < i64::MAX as f64 =>
+        {
+            (float as i64).into()
+        }
+        _ => bound.clone().into(),
+    }
+}
+
+/// Whether `value` is below (`Less`) or above (`Greater`) a bound, comparing
+/// integers exactly
+fn compare_bound(
+    value: &serde_json::Value,
+    bound: &serde_json::Number,
+) -> Option<std::cmp::Ordering> {
+    match (value.as_i64(), bound.as_i64()) {
+        (Some(value), Some(bound)) => Some(value.cmp(&bound)),
+        _ => value.as_f64()?.partial_cmp(&bound.as_f64()?),
+    }
+}
+
+/// Emit an example as the `examples` keyword, plus a short "e.g." hint in
+/// the description for scalar examples
+fn insert_example(
+    schema: &mut serde_json::Map<String, serde_json::Value>,
+    example: &serde_json::Value,
+) {
+    let Some(example) = truncate_example(example) else {
+        return;
+    };
+
+    if !example.is_object() && !example.is_array() {
+        let inline = example.to_string();
+        if inline.len() <= MAX_INLINE_EXAMPLE_LEN {
+            let desc = match schema.get("description").and_then(|d| d.as_str()) {
+                Some(desc) => format!("{} (e.g. {})", desc, inline),
+                None => format!("e.g. {}", inline),
+            };
+            schema.insert("description".to_string(), serde_json::Value::String(desc));
+        }
+    }
+
+    schema.insert(
+        "examples".to_string(),
+        serde_json::Value::Array(vec![example]),
+    );
+}
+
+/// Shrink an example to fit in a tool schema
+///
+/// Long strings are cut, arrays keep as many leading items as fit, and
+/// other oversized values are dropped.
+fn truncate_example(example: &serde_json::Value) -> Option<serde_json::Value> {
+    if example.to_string().len() <= MAX_EXAMPLE_LEN {
+        return Some(example.clone());
+    }
+
+    match example {
+        serde_json::Value::String(s) => {
+            let cut: String = s.chars().take(MAX_EXAMPLE_LEN).collect();
+            Some(serde_json::Value::String(format!("{}...", cut)))
+        }
+        serde_json::Value::Array(items) => {
+            let mut kept = Vec::new();
+            for item in items {
+                kept.push(item.clone());
+                if serde_json::Value::Array(kept.clone()).to_string().len() > MAX_EXAMPLE_LEN {
+                    kept.pop();
+                    break;
+                }
+            }
+            (!kept.is_empty()).then_some(serde_json::Value::Array(kept))
+        }
+        _ => None,
+    }
+}
+
+/// Convert a JSON value to a string for URL parameters
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Path or query value as sent; integer parameters given as whole floats
+/// (`123.0`) are rendered without a decimal point
+fn param_to_string(value: &serde_json::Value, param: &EndpointParameter) -> String {
+    match (param.param_type == ParameterType::Integer)
+        .then(|| as_integer(value))
+        .flatten()
+    {
+        Some(integer) => integer.to_string(),
+        None => value_to_string(value),
+    }
+}
+
+/// Integer value of a JSON number without fractional part (JSON Schema
+/// counts `1.0` as an integer)
+fn as_integer(value: &serde_json::Value) -> Option<i128> {
+    if let Some(integer) = value.as_i64() {
+        return Some(integer.into());
+    }
+    if let Some(integer) = value.as_u64() {
+        return Some(integer.into());
+    }
+    value
+        .as_f64()
+        .filter(|f| f.fract() == 0.0 && f.abs() < 2f64.powi(63))
+        .map(|f| f as i128)
+}
+
+/// A tool argument departing from the advertised input schema
+struct Violation {
+    argument: String,
+    message: String,
+}
+
+impl Violation {
+    fn new(argument: &str, message: String) -> Self {
+        Self {
+            argument: argument.to_string(),
+            message,
+        }
+    }
+}
+
+/// Single `invalid_params` error listing every violation, with the
+/// violations also attached as structured data
+fn invalid_arguments(tool_name: &str, violations: &[Violation]) -> ErrorData {
+    let lines: Vec<String> = violations
+        .iter()
+        .map(|v| format!("- {}", v.message))
+        .collect();
+    let data: Vec<serde_json::Value> = violations
+        .iter()
+        .map(|v| serde_json::json!({"argument": v.argument, "message": v.message}))
+        .collect();
+    ErrorData::invalid_params(
+        format!("Invalid arguments for {}:\n{}", tool_name, lines.join("\n")),
+        Some(serde_json::json!({ "violations": data })),
+    )
+}
+
+/// Check tool arguments against the constraints advertised in the input
+/// schema: required, type, enum, bounds, lengths, pattern and body variants
+fn argument_violations(
+    endpoint: &PangolinEndpoint,
+    args: &HashMap<String, serde_json::Value>,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    let params = endpoint
+        .path_params
+        .iter()
+        .map(|p| (p, "path"))
+        .chain(endpoint.query_params.iter().map(|p| (p, "query")));
+    for (param, location) in params {
+        match args.get(&param.name).filter(|value| !value.is_null()) {
+            Some(value) => parameter_violations(param, value, &mut violations),
+            None if param.required && param.default_value.is_none() => {
+                violations.push(Violation::new(
+                    &param.name,
+                    format!(
+                        "Missing required {} parameter: {} (expected {})",
+                        location,
+                        param.name,
+                        param.param_type.to_json_schema_type()
+                    ),
+                ))
+            }
+            None => {}
+        }
+    }
+
+    let Some(ref body) = endpoint.request_body else {
+        return violations;
+    };
+    let is_param = |name: &str| {
+        endpoint
+            .path_params
+            .iter()
+            .chain(&endpoint.query_params)
+            .any(|p| p.name == name)
+    };
+
+    for name in &body.required {
+        let Some(prop) = body.properties.get(name) else {
+            continue;
+        };
+        if prop.const_value.is_none() && !args.contains_key(name) {
+            violations.push(Violation::new(
+                name,
+                format!(
+                    "Missing required body field: {} (expected {})",
+                    name,
+                    prop.param_type.to_json_schema_type()
+                ),
+            ));
+        }
+    }
+
+    for (name, prop) in &body.properties {
+        if let Some(value) = args.get(name).filter(|_| !is_param(name)) {
+            property_violations(name, &coerce_value(value, prop), prop, &mut violations);
+        }
+    }
+
+    if !body.free_form && !body.array_body {
+        let fields: serde_json::Map<String, serde_json::Value> = args
+            .iter()
+            .filter(|(key, _)| !is_param(key) && !body.read_only_properties.contains(key))
+            .map(|(key, value)| {
+                let value = match body.properties.get(key) {
+                    Some(prop) => coerce_value(value, prop),
+                    None => value.clone(),
+                };
+                (key.clone(), value)
+            })
+            .collect();
+        if let Err(message) = validate_body_variant(body, &fields) {
+            let argument = body.discriminator.as_deref().unwrap_or_default();
+            violations.push(Violation::new(argument, message));
+        }
+    }
+
+    violations
+}
+
+/// Check a path or query argument; string-encoded values of other types
+/// are accepted since they travel as text anyway
+fn parameter_violations(
+    param: &EndpointParameter,
+    value: &serde_json::Value,
+    violations: &mut Vec<Violation>,
+) {
+    let coerced = coerce_scalar(value, &param.param_type);
+    let accepts_scalar =
+        param.param_type == ParameterType::String && !coerced.is_object() && !coerced.is_array();
+    if !accepts_scalar && !type_matches(&coerced, &param.param_type) {
+        violations.push(Violation::new(
+            &param.name,
+            format!(
+                "Parameter '{}' must be {}, got {}",
+                param.name,
+                type_phrase(&param.param_type),
+                value
+            ),
+        ));
+        return;
+    }
+
+    if let Some(ref values) = param.enum_values {
+        if !values.contains(&value_to_string(value)) {
+            violations.push(Violation::new(
+                &param.name,
+                format!(
+                    "Parameter '{}' must be one of: {}; got {}",
+                    param.name,
+                    values.join(", "),
+                    value
+                ),
+            ));
+        }
+    }
+}
+
+/// Check a body value against its declared constraints
+fn property_violations(
+    name: &str,
+    value: &serde_json::Value,
+    prop: &PropertySchema,
+    violations: &mut Vec<Violation>,
+) {
+    let mut violation = |message: String| violations.push(Violation::new(name, message));
+
+    if value.is_null() {
+        if !prop.nullable {
+            violation(format!(
+                "Field '{}' must be {}, got null",
+                name,
+                type_phrase(&prop.param_type)
+            ));
+        }
+        return;
+    }
+
+    if !type_matches(value, &prop.param_type) {
+        violation(format!(
+            "Field '{}' must be {}, got {}",
+            name,
+            type_phrase(&prop.param_type),
+            value
+        ));
+        return;
+    }
+
+    if let Some(ref values) = prop.enum_values {
+        if !values.contains(&value_to_string(value)) {
+            violation(format!(
+                "Field '{}' must be one of: {}; got {}",
+                name,
+                values.join(", "),
+                value
+            ));
+        }
+    }
+
+    if let Some(ref const_value) = prop.const_value {
+        if value != const_value {
+            violation(format!(
+                "Field '{}' must be {} (const), got {}",
+                name, const_value, value
+            ));
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        let below = |bound: &serde_json::Number| {
+            compare_bound(value, bound) == Some(std::cmp::Ordering::Less)
+        };
+        let above = |bound: &serde_json::Number| {
+            compare_bound(value, bound) == Some(std::cmp::Ordering::Greater)
+        };
+        if let Some(minimum) = prop.minimum.as_ref().filter(|min| below(min)) {
+            violation(format!(
+                "Field '{}' must be at least {} (minimum), got {}",
+                name, minimum, value
+            ));
+        }
+        if let Some(maximum) = prop.maximum.as_ref().filter(|max| above(max)) {
+            violation(format!(
+                "Field '{}' must be at most {} (maximum), got {}",
+                name, maximum, value
+            ));
+        }
+        if let Some(multiple_of) = prop.multiple_of {
+            let quotient = number / multiple_of;
+            if multiple_of > 0.0 && (quotient - quotient.round()).abs() > 1e-9 {
+                violation(format!(
+                    "Field '{}' must be a multiple of {} (multipleOf), got {}",
+                    name, multiple_of, value
+                ));
+            }
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        let length = text.chars().count() as i64;
+        if let Some(min_length) = prop.min_length.filter(|min| length < *min) {
+            violation(format!(
+                "Field '{}' must be at least {} character(s) long (minLength), got {}",
+                name, min_length, length
+            ));
+        }
+        if let Some(max_length) = prop.max_length.filter(|max| length > *max) {
+            violation(format!(
+                "Field '{}' must be at most {} character(s) long (maxLength), got {}",
+                name, max_length, length
+            ));
+        }
+        if let Some(ref pattern) = prop.pattern {
+            // Patterns the regex crate cannot compile are left to the API
+            if Regex::new(pattern).is_ok_and(|re| !re.is_match(text)) {
+                violation(format!(
+                    "Field '{}' must match {} (pattern), got {}",
+                    name, pattern, value
+                ));
+            }
+        }
+    }
+
+    let Some(items) = value.as_array() else {
+        return;
+    };
+
+    if let Some(min_items) = prop.min_items {
+        if (items.len() as u64) < min_items {
+            violation(format!(
+                "Field '{}' must contain at least {} item(s) (minItems), got {}",
+                name,
+                min_items,
+                items.len()
+            ));
+        }
+    }
+    if let Some(max_items) = prop.max_items {
+        if items.len() as u64 > max_items {
+            violation(format!(
+                "Field '{}' must contain at most {} item(s) (maxItems), got {}",
+                name,
+                max_items,
+                items.len()
+            ));
+        }
+    }
+    if prop.unique_items {
+        if let Some((i, item)) = items
+            .iter()
+            .enumerate()
+            .find(|(i, item)| items[..*i].contains(item))
+        {
+            violation(format!(
+                "Field '{}' must not contain duplicate items (uniqueItems): {} is repeated at index {}",
+                name, item, i
+            ));
+        }
+    }
+    if let Some(ref item_schema) = prop.items {
+        for (i, item) in items.iter().enumerate() {
+            property_violations(&format!("{}[{}]", name, i), item, item_schema, violations);
+        }
+    }
+}
+
+/// Expected type as used in violation messages
+fn type_phrase(param_type: &ParameterType) -> &'static str {
+    match param_type {
+        ParameterType::String => "a string",
+        ParameterType::Integer => "an integer",
+        ParameterType::Number => "a number",
+        ParameterType::Boolean => "a boolean",
+        ParameterType::Array => "a JSON array",
+        ParameterType::Object => "a JSON object",
+    }
+}
+
+/// Whether a JSON value has the given schema type
+fn type_matches(value: &serde_json::Value, param_type: &ParameterType) -> bool {
+    match param_type {
+        ParameterType::String => value.is_string(),
+        ParameterType::Integer => as_integer(value).is_some(),
+        ParameterType::Number => value.is_number(),
+        ParameterType::Boolean => value.is_boolean(),
+        ParameterType::Array => value.is_array(),
+        ParameterType::Object => value.is_object(),
+    }
+}
+
+/// Check a body against the `oneOf` branch its discriminator selects
+///
+/// Rejects bodies with a missing or unknown discriminator, fields that only
+/// belong to other branches, and missing fields the branch requires.
+fn validate_body_variant(
+    request_body: &RequestBodySchema,
+    body: &serde_json::Map<String, serde_json::Value>,
+) -> Result<(), String> {
+    let Some(ref discriminator) = request_body.discriminator else {
+        return Ok(());
+    };
+    let known: Vec<&str> = request_body
+        .variants
+        .iter()
+        .flat_map(|v| v.discriminator_values.iter().map(String::as_str))
+        .collect();
+
+    let value = body
+        .get(discriminator)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| {
+            format!(
+                "Missing body field '{}' (one of: {})",
+                discriminator,
+                known.join(", ")
+            )
+        })?;
+    let variant = request_body
+        .variants
+        .iter()
+        .find(|v| v.discriminator_values.iter().any(|d| d == value))
+        .ok_or_else(|| {
+            format!(
+                "Invalid {} '{}' (expected one of: {})",
+                discriminator,
+                value,
+                known.join(", ")
+            )
+        })?;
+
+    for field in body.keys() {
+        let other_branch_only = !variant.properties.contains_key(field)
+            && request_body
+                .variants
+                .iter()
+                .any(|v| v.properties.contains_key(field));
+        if other_branch_only {
+            return Err(format!(
+                "Field '{}' is not allowed when {} is '{}'",
+                field, discriminator, value
+            ));
+        }
+    }
+
+    if let Some(missing) = variant.required.iter().find(|r| !body.contains_key(*r)) {
+        return Err(format!(
+            "Missing required field '{}' when {} is '{}'",
+            missing, discriminator, value
+        ));
+    }
+
+    Ok(())
+}
+
+/// Coerce a string-encoded scalar into the declared property type
+///
+/// LLMs often pass `"123"` or `"true"` for numeric and boolean fields; the API
+/// expects real JSON numbers and booleans. Values that cannot be parsed are
+/// left untouched for validation (or the API) to report.
+fn coerce_value(value: &serde_json::Value, prop: &PropertySchema) -> serde_json::Value {
+    match (value, &prop.param_type) {
+        (serde_json::Value::Array(items), ParameterType::Array) => match prop.items {
+            Some(ref item_schema) => serde_json::Value::Array(
+                items.iter().map(|i| coerce_value(i, item_schema)).collect(),
+            ),
+            None => value.clone(),
+        },
+        _ => coerce_scalar(value, &prop.param_type),
+    }
+}
+
+/// Parse a string-encoded integer, number or boolean; other values are
+/// returned unchanged
+fn coerce_scalar(value: &serde_json::Value, param_type: &ParameterType) -> serde_json::Value {
+    match (value, param_type) {
+        (serde_json::Value::String(s), ParameterType::Integer) => s
+            .trim()
+            .parse::<i64>()
+            .map(serde_json::Value::from)
+            .unwrap_or_else(|_| value.clone()),
+        (serde_json::Value::Number(_), ParameterType::Integer) => as_integer(value)
+            .and_then(|integer| i64::try_from(integer).ok())
+            .map_or_else(|| value.clone(), serde_json::Value::from),
+        (serde_json::Value::String(s), ParameterType::Number) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .map(serde_json::Value::from)
+                .ok()
+                .or_else(|| {
+                    s.parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(serde_json::Value::Number)
+                })
+                .unwrap_or_else(|| value.clone())
+        }
+        (serde_json::Value::String(s), ParameterType::Boolean) => {
+            match s.trim().to_ascii_lowercase().as_str() {
+                "true" => serde_json::Value::Bool(true),
+                "false" => serde_json::Value::Bool(false),
+                _ => value.clone(),
+            }
+        }
+        _ => value.clone(),
+    }
+}
+
+/// Maximum serialized size of a response also returned as `structured_content`
+const MAX_STRUCTURED_CONTENT_BYTES: usize = 256 * 1024;
+
+/// Maximum number of schema mismatches reported in a result's meta
+const MAX_SCHEMA_MISMATCHES: usize = 10;
+
+/// Structured form of a JSON response; non-object values are wrapped as
+/// `{"result": ...}` since structured content must be an object
+fn structured_result(value: serde_json::Value) -> serde_json::Value {
+    if value.is_object() {
+        value
+    } else {
+        serde_json::json!({ "result": value })
+    }
+}
+
+/// Failures answered with an MCP error instead of an error tool result
+///
+/// Every answer of the API, 4xx included, stays a structured error result
+/// the model can act on; only an unreachable, timed out or overloaded
+/// upstream (network failure, open circuit breaker, 502/503/504) is a
+/// retriable internal error, since the call itself could not be made.
+fn upstream_error(error: &anyhow::Error) -> Option<ErrorData> {
+    if let Some(api_error) = error.downcast_ref::<PangolinApiError>() {
+        if !matches!(api_error.status.as_u16(), 502..=504) {
+            return None;
+        }
+        let mut data = api_error.to_json();
+        data["kind"] = "unavailable".into();
+        data["retriable"] = true.into();
+        return Some(ErrorData::internal_error(
+            format!(
+                "{}; the Pangolin API is unavailable, retry later",
+                api_error
+            ),
+            Some(data),
+        ));
+    }
+
+    let kind = if error.downcast_ref::<BreakerOpen>().is_some() {
+        "unavailable"
+    } else {
+        let transport = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<reqwest::Error>())?;
+        if transport.is_timeout() {
+            "timeout"
+        } else if transport.is_connect() || transport.is_request() || transport.is_body() {
+            "unreachable"
+        } else {
+            return None;
+        }
+    };
+    let message = format!("{:#}", error);
+    Some(ErrorData::internal_error(
+        format!("{}; retry later", message),
+        Some(serde_json::json!({
+            "status": null,
+            "message": message,
+            "kind": kind,
+            "retriable": true,
+        })),
+    ))
+}
+
+/// Machine-readable kind of the answers clients handle apart from other
+/// errors: a rejected key, a missing permission or a missing object
+fn api_error_kind(status: reqwest::StatusCode) -> Option<&'static str> {
+    match status.as_u16() {
+        401 => Some("unauthorized"),
+        403 => Some("forbidden"),
+        404 => Some("not_found"),
+        _ => None,
+    }
+}
+
+/// Readable rendering of an upstream error: the message, the request and
+/// any JSON error body
+fn api_error_text(error: &PangolinApiError) -> String {
+    // Rejected keys are for the operator to fix, not the model
+    let hint = match error.status.as_u16() {
+        401 => " (check PANGOLIN_API_KEY)",
+        403 => " (the key in PANGOLIN_API_KEY lacks permission for this operation)",
+        _ => "",
+    };
+    let mut text = format!(
+        "Error: {}{}\nRequest: {} {}",
+        error,
+        hint,
+        error.method.as_str(),
+        error.path
+    );
+    if let Some(request_id) = &error.request_id {
+        text.push_str(&format!(" (request ID {})", request_id));
+    }
+    if let Some(details) = &error.details {
+        text.push_str(&format!(
+            "\nDetails: {}",
+            serde_json::to_string_pretty(details).unwrap_or_default()
+        ));
+    }
+    text
+}
+
+/// Schema of the first documented 2xx JSON response
+fn success_schema(endpoint: &PangolinEndpoint) -> Option<&serde_json::Value> {
+    endpoint
+        .responses
+        .iter()
+        .filter(|r| r.status.starts_with('2'))
+        .find_map(|r| r.schema.as_ref())
+}
+
+/// Collect where a value departs from a response schema
+///
+/// Only `type`, `required`, `properties` and `items` are checked; `$ref`
+/// and composed schemas are not followed.
+fn schema_mismatches(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    at: &str,
+    mismatches: &mut Vec<String>,
+) {
+    if mismatches.len() >= MAX_SCHEMA_MISMATCHES {
+        return;
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(serde_json::Value::String(t)) => vec![t.as_str()],
+        Some(serde_json::Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    let nullable = schema.get("nullable").and_then(|n| n.as_bool()) == Some(true);
+    let type_matches = |t: &str| match t {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    };
+    if value.is_null() && nullable {
+        return;
+    }
+    if !types.is_empty() && !types.iter().any(|t| type_matches(t)) {
+        mismatches.push(format!("{}: expected {}", at, types.join(" or ")));
+        return;
+    }
+
+    match value {
+        serde_json::Value::Object(object) => {
+            let required = schema.get("required").and_then(|r| r.as_array());
+            for name in required.into_iter().flatten().filter_map(|n| n.as_str()) {
+                if !object.contains_key(name) {
+                    mismatches.push(format!("{}: missing required property '{}'", at, name));
+                }
+            }
+            if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+                for (name, property) in properties {
+                    if let Some(child) = object.get(name) {
+                        schema_mismatches(child, property, &format!("{}.{}", at, name), mismatches);
+                    }
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    schema_mismatches(item, item_schema, &format!("{}[{}]", at, i), mismatches);
+                }
+            }
+        }
+        _ => {}
     }
 }
@@ -0,0 +1,49 @@
+//! Graceful shutdown on SIGINT/SIGTERM
+
+use std::future::Future;
+use tracing::info;
+
+/// Resolve with the name of the first termination signal received
+pub async fn termination_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                tracing::warn!("Failed to listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+/// Drive `service` until it ends on its own or `shutdown` resolves; on
+/// shutdown `stop` is called and the service is awaited while it winds down
+///
+/// Returns the service's output and the signal that stopped it, if any.
+pub async fn run_until_shutdown<T>(
+    service: impl Future<Output = T>,
+    shutdown: impl Future<Output = &'static str>,
+    stop: impl FnOnce(),
+) -> (T, Option<&'static str>) {
+    tokio::pin!(service);
+    tokio::select! {
+        output = &mut service => (output, None),
+        signal = shutdown => {
+            info!("{} received, shutting down", signal);
+            stop();
+            (service.await, Some(signal))
+        }
+    }
+}
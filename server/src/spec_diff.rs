@@ -0,0 +1,330 @@
+//! Endpoint-level comparison between two versions of the OpenAPI spec
+//!
+//! Endpoints are matched by method and path; for endpoints present in both
+//! versions, path/query parameters and body properties are compared by name.
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use std::fmt;
+
+use crate::types::PangolinEndpoint;
+
+/// Differences between two extracted endpoint sets
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct SpecDiff {
+    pub added: Vec<EndpointRef>,
+    pub removed: Vec<EndpointRef>,
+    pub changed: Vec<EndpointChange>,
+}
+
+/// An endpoint identified by method and path
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EndpointRef {
+    pub method: String,
+    pub path: String,
+    pub tool: String,
+}
+
+/// Input changes of an endpoint present in both versions
+#[derive(Debug, Serialize, PartialEq)]
+pub struct EndpointChange {
+    #[serde(flatten)]
+    pub endpoint: EndpointRef,
+    pub added_inputs: Vec<InputRef>,
+    pub removed_inputs: Vec<InputRef>,
+    pub required_changes: Vec<RequiredChange>,
+}
+
+/// A tool input: a path or query parameter, or a body property
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct InputRef {
+    pub location: &'static str,
+    pub name: String,
+    pub required: bool,
+}
+
+/// An input whose required-ness differs between versions
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RequiredChange {
+    pub location: &'static str,
+    pub name: String,
+    pub required: bool,
+}
+
+impl SpecDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two endpoint sets
+pub fn diff_endpoints(old: &[PangolinEndpoint], new: &[PangolinEndpoint]) -> SpecDiff {
+    let key = |e: &PangolinEndpoint| (e.method.as_str().to_string(), e.path.clone());
+    let old_by_key: IndexMap<_, _> = old.iter().map(|e| (key(e), e)).collect();
+    let new_by_key: IndexMap<_, _> = new.iter().map(|e| (key(e), e)).collect();
+
+    let mut diff = SpecDiff::default();
+
+    for (k, endpoint) in &old_by_key {
+        if !new_by_key.contains_key(k) {
+            diff.removed.push(endpoint_ref(endpoint));
+        }
+    }
+
+    for (k, endpoint) in &new_by_key {
+        let Some(old_endpoint) = old_by_key.get(k) else {
+            diff.added.push(endpoint_ref(endpoint));
+            continue;
+        };
+
+        let old_inputs = inputs(old_endpoint);
+        let new_inputs = inputs(endpoint);
+
+        let removed_inputs: Vec<InputRef> = old_inputs
+            .iter()
+            .filter(|i| !new_inputs.iter().any(|n| same_input(n, i)))
+            .cloned()
+            .collect();
+        let added_inputs: Vec<InputRef> = new_inputs
+            .iter()
+            .filter(|i| !old_inputs.iter().any(|o| same_input(o, i)))
+            .cloned()
+            .collect();
+        let required_changes: Vec<RequiredChange> = new_inputs
+            .iter()
+            .filter_map(|n| {
+                let o = old_inputs.iter().find(|o| same_input(o, n))?;
+                (o.required != n.required).then(|| RequiredChange {
+                    location: n.location,
+                    name: n.name.clone(),
+                    required: n.required,
+                })
+            })
+            .collect();
+
+        if !added_inputs.is_empty() || !removed_inputs.is_empty() || !required_changes.is_empty() {
+            diff.changed.push(EndpointChange {
+                endpoint: endpoint_ref(endpoint),
+                added_inputs,
+                removed_inputs,
+                required_changes,
+            });
+        }
+    }
+
+    diff
+}
+
+fn endpoint_ref(endpoint: &PangolinEndpoint) -> EndpointRef {
+    EndpointRef {
+        method: endpoint.method.as_str().to_string(),
+        path: endpoint.path.clone(),
+        tool: endpoint.name.clone(),
+    }
+}
+
+fn same_input(a: &InputRef, b: &InputRef) -> bool {
+    a.location == b.location && a.name == b.name
+}
+
+/// All inputs of an endpoint, in tool schema order
+fn inputs(endpoint: &PangolinEndpoint) -> Vec<InputRef> {
+    let params = endpoint
+        .path_params
+        .iter()
+        .map(|p| ("path", p))
+        .chain(endpoint.query_params.iter().map(|p| ("query", p)))
+        .map(|(location, p)| InputRef {
+            location,
+            name: p.name.clone(),
+            required: p.required,
+        });
+
+    let body = endpoint.request_body.iter().flat_map(|body| {
+        body.properties.keys().map(|name| InputRef {
+            location: "body",
+            name: name.clone(),
+            required: body.required.contains(name),
+        })
+    });
+
+    params.chain(body).collect()
+}
+
+impl fmt::Display for SpecDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No endpoint changes");
+        }
+
+        if !self.added.is_empty() {
+            writeln!(f, "Added endpoints:")?;
+            for e in &self.added {
+                writeln!(f, "  + {} {} ({})", e.method, e.path, e.tool)?;
+            }
+        }
+        if !self.removed.is_empty() {
+            writeln!(f, "Removed endpoints:")?;
+            for e in &self.removed {
+                writeln!(f, "  - {} {} ({})", e.method, e.path, e.tool)?;
+            }
+        }
+        if !self.changed.is_empty() {
+            writeln!(f, "Changed endpoints:")?;
+            for change in &self.changed {
+                let e = &change.endpoint;
+                writeln!(f, "  ~ {} {} ({})", e.method, e.path, e.tool)?;
+                for i in &change.added_inputs {
+                    let required = if i.required { ", required" } else { "" };
+                    writeln!(f, "      + {} ({}{})", i.name, i.location, required)?;
+                }
+                for i in &change.removed_inputs {
+                    writeln!(f, "      - {} ({})", i.name, i.location)?;
+                }
+                for c in &change.required_changes {
+                    let now = if c.required { "required" } else { "optional" };
+                    writeln!(f, "      * {} ({}) is now {}", c.name, c.location, now)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swagger::SwaggerSpec;
+    use serde_json::json;
+
+    const OLD_SPEC: &str = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Pangolin", "version": "1.0.0"},
+        "paths": {
+            "/orgs": {
+                "get": {"parameters": [{"name": "limit", "in": "query"}]}
+            },
+            "/org/{orgId}": {
+                "get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "post": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "subnet": {"type": "string"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "/site/{siteId}": {
+                "delete": {"parameters": [{"name": "siteId", "in": "path", "required": true}]}
+            }
+        }
+    }"#;
+
+    const NEW_SPEC: &str = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Pangolin", "version": "1.1.0"},
+        "paths": {
+            "/orgs": {
+                "get": {"parameters": [{"name": "limit", "in": "query"}]}
+            },
+            "/org/{orgId}": {
+                "get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "post": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "billingEmail": {"type": "string"}
+                                    },
+                                    "required": ["name"]
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "/org/{orgId}/domains": {
+                "get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}
+            }
+        }
+    }"#;
+
+    fn diff_fixtures() -> SpecDiff {
+        let old = SwaggerSpec::from_json(OLD_SPEC)
+            .unwrap()
+            .extract_endpoints();
+        let new = SwaggerSpec::from_json(NEW_SPEC)
+            .unwrap()
+            .extract_endpoints();
+        diff_endpoints(&old, &new)
+    }
+
+    #[test]
+    fn test_diff_endpoints() {
+        let diff = serde_json::to_value(diff_fixtures()).unwrap();
+
+        assert_eq!(
+            diff,
+            json!({
+                "added": [
+                    {"method": "GET", "path": "/org/{orgId}/domains", "tool": "org_by_orgId_domains"}
+                ],
+                "removed": [
+                    {"method": "DELETE", "path": "/site/{siteId}", "tool": "delete_site_by_siteId"}
+                ],
+                "changed": [
+                    {
+                        "method": "POST",
+                        "path": "/org/{orgId}",
+                        "tool": "update_org_by_orgId",
+                        "added_inputs": [
+                            {"location": "body", "name": "billingEmail", "required": false}
+                        ],
+                        "removed_inputs": [
+                            {"location": "body", "name": "subnet", "required": false}
+                        ],
+                        "required_changes": [
+                            {"location": "body", "name": "name", "required": true}
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_diff_text_output() {
+        let text = diff_fixtures().to_string();
+
+        assert!(text.contains("  + GET /org/{orgId}/domains (org_by_orgId_domains)"));
+        assert!(text.contains("  - DELETE /site/{siteId} (delete_site_by_siteId)"));
+        assert!(text.contains("  ~ POST /org/{orgId} (update_org_by_orgId)"));
+        assert!(text.contains("      + billingEmail (body)"));
+        assert!(text.contains("      - subnet (body)"));
+        assert!(text.contains("      * name (body) is now required"));
+    }
+
+    #[test]
+    fn test_identical_specs_have_no_diff() {
+        let endpoints = SwaggerSpec::from_json(OLD_SPEC)
+            .unwrap()
+            .extract_endpoints();
+        let diff = diff_endpoints(&endpoints, &endpoints);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_string(), "No endpoint changes\n");
+    }
+}
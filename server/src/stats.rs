@@ -0,0 +1,103 @@
+//! Per-tool usage counters behind the built-in `get_server_stats` tool
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds (inclusive, in milliseconds) of the latency histogram
+/// buckets; slower calls land in a final unbounded bucket
+const LATENCY_BUCKETS_MS: [u64; 8] = [50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// How a tool call ended
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallOutcome {
+    Success,
+    Error,
+    /// Refused because the tool is disabled in read-only mode
+    BlockedByReadOnly,
+}
+
+#[derive(Debug, Default)]
+struct ToolStats {
+    calls: u64,
+    errors: u64,
+    blocked_by_read_only: u64,
+    total_ms: u64,
+    max_ms: u64,
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+/// Counters shared by every clone of the service
+#[derive(Debug)]
+pub struct ServerStats {
+    started: Instant,
+    tools: Mutex<BTreeMap<String, ToolStats>>,
+}
+
+impl Default for ServerStats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            tools: Mutex::default(),
+        }
+    }
+}
+
+impl ServerStats {
+    pub fn record(&self, tool: &str, outcome: CallOutcome, duration: Duration) {
+        let ms = duration.as_millis() as u64;
+        let mut tools = self.tools.lock().unwrap();
+        let stats = tools.entry(tool.to_string()).or_default();
+        stats.calls += 1;
+        if outcome != CallOutcome::Success {
+            stats.errors += 1;
+        }
+        if outcome == CallOutcome::BlockedByReadOnly {
+            stats.blocked_by_read_only += 1;
+        }
+        stats.total_ms += ms;
+        stats.max_ms = stats.max_ms.max(ms);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|bound| ms <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        stats.buckets[bucket] += 1;
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Per-tool counters and latency histograms, by tool name
+    pub fn tools_summary(&self) -> serde_json::Value {
+        let tools = self.tools.lock().unwrap();
+        tools
+            .iter()
+            .map(|(name, stats)| {
+                let histogram: Vec<_> = stats
+                    .buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, count)| {
+                        serde_json::json!({
+                            "le": LATENCY_BUCKETS_MS.get(i),
+                            "count": count,
+                        })
+                    })
+                    .collect();
+                let summary = serde_json::json!({
+                    "calls": stats.calls,
+                    "errors": stats.errors,
+                    "blockedByReadOnly": stats.blocked_by_read_only,
+                    "latencyMs": {
+                        "mean": stats.total_ms / stats.calls.max(1),
+                        "max": stats.max_ms,
+                        "histogram": histogram,
+                    },
+                });
+                (name.clone(), summary)
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    }
+}
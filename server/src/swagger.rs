@@ -8,10 +8,14 @@ use indexmap::IndexMap;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
+use crate::json_patch;
+use crate::overlay;
 use crate::types::{
-    EndpointParameter, HttpMethod, PangolinEndpoint, ParameterType, PropertySchema,
-    RequestBodySchema,
+    BodyVariant, EndpointParameter, EndpointResponse, HttpMethod, PangolinEndpoint, ParameterType,
+    PropertySchema, RequestBodySchema, VariantKind, WebhookDoc, ARRAY_BODY, FREE_FORM_BODY,
 };
 
 /// Root OpenAPI specification structure
@@ -24,6 +28,16 @@ pub struct SwaggerSpec {
     pub paths: IndexMap<String, PathItem>,
     #[serde(default)]
     pub components: Option<Components>,
+    /// OpenAPI 3.1 outbound webhooks (kept raw so payload schemas survive intact)
+    #[serde(default)]
+    pub webhooks: IndexMap<String, serde_json::Value>,
+    /// Raw document as loaded, before `$ref` resolution (used to re-derive
+    /// the spec after overlays are applied)
+    #[serde(skip)]
+    raw: serde_json::Value,
+    /// Directory external `$ref`s are resolved against
+    #[serde(skip)]
+    base_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +57,9 @@ pub struct SwaggerServer {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathItem {
+    /// Reference to a shared path item (resolved at load time when possible)
+    #[serde(default, rename = "$ref")]
+    pub reference: Option<String>,
     #[serde(default)]
     pub get: Option<Operation>,
     #[serde(default)]
@@ -55,9 +72,26 @@ pub struct PathItem {
     pub patch: Option<Operation>,
 }
 
+impl PathItem {
+    /// Declared operations, in extraction order
+    fn operations(&self) -> impl Iterator<Item = (HttpMethod, &Operation)> {
+        [
+            (HttpMethod::Get, &self.get),
+            (HttpMethod::Post, &self.post),
+            (HttpMethod::Put, &self.put),
+            (HttpMethod::Delete, &self.delete),
+            (HttpMethod::Patch, &self.patch),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| Some((method, operation.as_ref()?)))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Operation {
+    #[serde(default)]
+    pub operation_id: Option<String>,
     #[serde(default)]
     pub description: Option<String>,
     #[serde(default)]
@@ -76,8 +110,13 @@ pub struct Operation {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Parameter {
+    /// Reference to a shared parameter (e.g. `#/components/parameters/OrgId`),
+    /// resolved when endpoints are extracted
+    #[serde(default, rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(default)]
     pub name: String,
-    #[serde(rename = "in")]
+    #[serde(default, rename = "in")]
     pub location: String,
     #[serde(default)]
     pub required: bool,
@@ -85,6 +124,11 @@ pub struct Parameter {
     pub description: Option<String>,
     #[serde(default)]
     pub schema: Option<ParameterSchema>,
+    #[serde(default)]
+    pub example: Option<serde_json::Value>,
+    /// Named Example Objects (`{"summary": ..., "value": ...}`)
+    #[serde(default)]
+    pub examples: Option<IndexMap<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +141,10 @@ pub struct ParameterSchema {
     pub default: Option<serde_json::Value>,
     #[serde(default, rename = "enum")]
     pub enum_values: Option<Vec<String>>,
+    #[serde(default)]
+    pub example: Option<serde_json::Value>,
+    #[serde(default)]
+    pub examples: Option<Vec<serde_json::Value>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,7 +152,7 @@ pub struct RequestBody {
     #[serde(default)]
     pub required: bool,
     #[serde(default)]
-    pub content: HashMap<String, MediaType>,
+    pub content: IndexMap<String, MediaType>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -116,10 +164,12 @@ pub struct MediaType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Schema {
+    #[serde(default, rename = "$ref")]
+    pub reference: Option<String>,
     #[serde(rename = "type")]
     pub schema_type: Option<String>,
     #[serde(default)]
-    pub properties: Option<HashMap<String, SchemaProperty>>,
+    pub properties: Option<IndexMap<String, SchemaProperty>>,
     #[serde(default)]
     pub required: Option<Vec<String>>,
     #[serde(default)]
@@ -132,10 +182,21 @@ pub struct Schema {
     pub any_of: Option<Vec<Schema>>,
     #[serde(default, rename = "oneOf")]
     pub one_of: Option<Vec<Schema>>,
+    #[serde(default)]
+    pub discriminator: Option<Discriminator>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct Discriminator {
+    pub property_name: String,
+    /// Discriminator value to schema `$ref` (or bare schema name)
+    #[serde(default)]
+    pub mapping: IndexMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub struct SchemaProperty {
     #[serde(rename = "type")]
     pub schema_type: Option<String>,
@@ -154,17 +215,35 @@ pub struct SchemaProperty {
     #[serde(default)]
     pub max_length: Option<i64>,
     #[serde(default)]
-    pub minimum: Option<f64>,
+    pub minimum: Option<serde_json::Number>,
     #[serde(default)]
-    pub maximum: Option<f64>,
+    pub maximum: Option<serde_json::Number>,
     #[serde(default)]
     pub exclusive_minimum: Option<bool>,
     #[serde(default)]
     pub pattern: Option<String>,
     #[serde(default)]
+    pub multiple_of: Option<f64>,
+    #[serde(default, rename = "const")]
+    pub const_value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub min_items: Option<u64>,
+    #[serde(default)]
+    pub max_items: Option<u64>,
+    #[serde(default)]
+    pub unique_items: Option<bool>,
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub write_only: Option<bool>,
+    #[serde(default)]
+    pub example: Option<serde_json::Value>,
+    #[serde(default)]
+    pub examples: Option<Vec<serde_json::Value>>,
+    #[serde(default)]
     pub items: Option<Box<SchemaProperty>>,
     #[serde(default)]
-    pub properties: Option<HashMap<String, SchemaProperty>>,
+    pub properties: Option<IndexMap<String, SchemaProperty>>,
     #[serde(default)]
     pub required: Option<Vec<String>>,
     #[serde(default, rename = "anyOf")]
@@ -179,63 +258,207 @@ pub struct Components {
     pub security_schemes: Option<HashMap<String, serde_json::Value>>,
     #[serde(default)]
     pub parameters: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default, rename = "pathItems")]
+    pub path_items: Option<HashMap<String, serde_json::Value>>,
 }
 
-/// Wrapper for the full Swagger document with swaggerDoc field
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct SwaggerDocument {
-    pub swagger_doc: SwaggerSpec,
-    #[serde(default)]
-    pub custom_options: Option<serde_json::Value>,
+/// Options controlling how endpoints are extracted from the spec
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Request content type to use per endpoint (tool name -> content type),
+    /// overriding the default preference order
+    pub content_type_overrides: HashMap<String, String>,
 }
 
+/// Request content types in order of preference when an operation offers several
+const CONTENT_TYPE_PREFERENCE: &[&str] = &[
+    "application/json",
+    "application/x-www-form-urlencoded",
+    "text/plain",
+];
+
+/// Maximum number of `$ref` hops followed before giving up (guards against cycles)
+const MAX_REF_DEPTH: usize = 16;
+
 impl SwaggerSpec {
     /// Load from file
+    ///
+    /// External `$ref`s are resolved relative to the file's directory.
     #[allow(dead_code)]
     pub fn from_file(path: &str) -> Result<Self> {
         let content = std::fs::read_to_string(path).context("Failed to read swagger file")?;
-        Self::from_json(&content)
+        Self::parse(&content, Path::new(path).parent())
     }
 
     /// Parse from JSON string
     pub fn from_json(json: &str) -> Result<Self> {
-        // First try to parse as SwaggerDocument (with swaggerDoc wrapper)
-        if let Ok(doc) = serde_json::from_str::<SwaggerDocument>(json) {
-            return Ok(doc.swagger_doc);
+        Self::parse(json, None)
+    }
+
+    /// Fetch over HTTP with `client` and parse
+    ///
+    /// The API key, when given, is sent as a Bearer token like API calls are.
+    #[allow(dead_code)]
+    pub async fn from_url(
+        client: &reqwest::Client,
+        url: &str,
+        api_key: Option<&str>,
+    ) -> Result<Self> {
+        let mut request = client.get(url);
+        if let Some(api_key) = api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("Failed to fetch swagger spec")?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch swagger spec from {}: HTTP {}", url, status);
+        }
+
+        let content = response
+            .text()
+            .await
+            .context("Failed to read swagger spec response")?;
+        Self::parse(&content, None)
+    }
+
+    fn parse(json: &str, base_dir: Option<&Path>) -> Result<Self> {
+        let doc: serde_json::Value =
+            serde_json::from_str(json).context("Failed to parse swagger JSON")?;
+        Self::from_value(doc, base_dir.map(Path::to_path_buf))
+    }
+
+    fn from_value(mut doc: serde_json::Value, base_dir: Option<PathBuf>) -> Result<Self> {
+        // Unwrap the document if it comes with a swaggerDoc wrapper
+        if let Some(inner) = doc.get_mut("swaggerDoc").map(serde_json::Value::take) {
+            doc = inner;
+        }
+
+        let raw = doc.clone();
+        resolve_path_item_refs(&mut doc, base_dir.as_deref());
+
+        let mut spec: Self = serde_json::from_value(doc).context("Failed to parse swagger JSON")?;
+        spec.raw = raw;
+        spec.base_dir = base_dir;
+        Ok(spec)
+    }
+
+    /// Apply an OpenAPI Overlay document on top of the loaded spec
+    ///
+    /// Returns the number of nodes touched by the overlay's actions.
+    pub fn apply_overlay(&mut self, overlay: &serde_json::Value) -> Result<usize> {
+        let mut doc = self.raw.clone();
+        let touched = overlay::apply_overlay(&mut doc, overlay)?;
+        *self = Self::from_value(doc, self.base_dir.take())
+            .context("Overlay produced an invalid specification")?;
+        Ok(touched)
+    }
+
+    /// Apply a JSON Patch (array of operations) or JSON Merge Patch (object)
+    /// on top of the loaded spec
+    pub fn apply_patch(&mut self, patch: &serde_json::Value) -> Result<()> {
+        let mut doc = self.raw.clone();
+        json_patch::apply_patch(&mut doc, patch)?;
+        *self = Self::from_value(doc, self.base_dir.take())
+            .context("Patch produced an invalid specification")?;
+        Ok(())
+    }
+
+    /// Add the paths, components and webhooks of another spec
+    ///
+    /// Paths may be split across specs, but one path+method defined in both,
+    /// or a component or webhook defined differently, is an error.
+    pub fn merge(&mut self, other: SwaggerSpec) -> Result<()> {
+        let mut other_raw = other.raw;
+        // The other spec's external path items resolve against its own directory
+        resolve_path_item_refs(&mut other_raw, other.base_dir.as_deref());
+
+        let mut doc = self.raw.clone();
+        resolve_path_item_refs(&mut doc, self.base_dir.as_deref());
+        let paths = object_entry(&mut doc, "paths");
+        for (path, item) in object_members(&other_raw, "paths") {
+            let Some(item) = item.as_object() else {
+                continue;
+            };
+            let target = paths
+                .entry(path.clone())
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .with_context(|| format!("Path item {} is not an object", path))?;
+            for (key, value) in item {
+                match target.get(key) {
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                    Some(existing)
+                        if existing == value && !SPEC_METHODS.contains(&key.as_str()) => {}
+                    Some(_) if SPEC_METHODS.contains(&key.as_str()) => {
+                        anyhow::bail!("Conflicting definitions of {} {}", key.to_uppercase(), path)
+                    }
+                    Some(_) => anyhow::bail!("Conflicting '{}' of path {}", key, path),
+                }
+            }
         }
-        // Otherwise try direct parsing
-        serde_json::from_str(json).context("Failed to parse swagger JSON")
+
+        for (section, definitions) in object_members(&other_raw, "components") {
+            let Some(definitions) = definitions.as_object() else {
+                continue;
+            };
+            let components = object_entry(&mut doc, "components");
+            let target = components
+                .entry(section.clone())
+                .or_insert_with(|| serde_json::json!({}))
+                .as_object_mut()
+                .with_context(|| format!("components.{} is not an object", section))?;
+            merge_definitions(target, definitions, &format!("components.{}", section))?;
+        }
+        if let Some(webhooks) = other_raw.get("webhooks").and_then(|w| w.as_object()) {
+            merge_definitions(object_entry(&mut doc, "webhooks"), webhooks, "webhooks")?;
+        }
+
+        *self = Self::from_value(doc, self.base_dir.take())
+            .context("Merging produced an invalid specification")?;
+        Ok(())
     }
 
     /// Extract all endpoints from the specification
+    #[allow(dead_code)]
     pub fn extract_endpoints(&self) -> Vec<PangolinEndpoint> {
+        self.extract_endpoints_with(&ExtractOptions::default())
+    }
+
+    /// Extract all endpoints from the specification using custom options
+    pub fn extract_endpoints_with(&self, options: &ExtractOptions) -> Vec<PangolinEndpoint> {
         let mut endpoints = Vec::new();
 
         for (path, path_item) in &self.paths {
             // Process each HTTP method
             if let Some(op) = &path_item.get {
-                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Get, op) {
+                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Get, op, options) {
                     endpoints.push(endpoint);
                 }
             }
             if let Some(op) = &path_item.post {
-                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Post, op) {
+                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Post, op, options) {
                     endpoints.push(endpoint);
                 }
             }
             if let Some(op) = &path_item.put {
-                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Put, op) {
+                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Put, op, options) {
                     endpoints.push(endpoint);
                 }
             }
             if let Some(op) = &path_item.delete {
-                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Delete, op) {
+                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Delete, op, options)
+                {
                     endpoints.push(endpoint);
                 }
             }
             if let Some(op) = &path_item.patch {
-                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Patch, op) {
+                if let Some(endpoint) = self.create_endpoint(path, HttpMethod::Patch, op, options) {
                     endpoints.push(endpoint);
                 }
             }
@@ -244,11 +467,170 @@ impl SwaggerSpec {
         endpoints
     }
 
+    /// Problems leaving tools partially working, one message per problem
+    /// (`--strict` refuses to start on any): unresolved `$ref`s, duplicate
+    /// operationIds, parameters missing required fields and path parameters
+    /// not matching their path
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        self.collect_unresolved_refs(&self.raw, "", &mut problems);
+
+        let mut operation_ids: HashMap<&str, String> = HashMap::new();
+        for (path, item) in &self.paths {
+            for (method, operation) in item.operations() {
+                let location = format!("{} {}", method.as_str(), path);
+                if let Some(id) = operation.operation_id.as_deref() {
+                    match operation_ids.get(id) {
+                        Some(first) => problems.push(format!(
+                            "{}: operationId '{}' is already used by {}",
+                            location, id, first
+                        )),
+                        None => {
+                            operation_ids.insert(id, location.clone());
+                        }
+                    }
+                }
+                for (index, param) in operation.parameters.iter().enumerate() {
+                    // Unresolvable references were reported above
+                    let resolved;
+                    let param = match &param.reference {
+                        Some(reference) => {
+                            match resolve_ref(&self.raw, reference, self.base_dir.as_deref())
+                                .and_then(|value| Ok(serde_json::from_value::<Parameter>(value)?))
+                            {
+                                Ok(param) => {
+                                    resolved = param;
+                                    &resolved
+                                }
+                                Err(_) => continue,
+                            }
+                        }
+                        None => param,
+                    };
+                    if param.name.is_empty() {
+                        problems.push(format!("{}: parameter #{} has no name", location, index));
+                    } else if param.location.is_empty() {
+                        problems.push(format!(
+                            "{}: parameter '{}' has no 'in' location",
+                            location, param.name
+                        ));
+                    } else if param.location == "path" && !param.required {
+                        problems.push(format!(
+                            "{}: path parameter '{}' is not marked required",
+                            location, param.name
+                        ));
+                    }
+                }
+            }
+        }
+
+        for endpoint in self.extract_endpoints() {
+            for problem in path_param_mismatches(&endpoint.path, &endpoint.path_params) {
+                problems.push(format!(
+                    "{} {}: {}",
+                    endpoint.method.as_str(),
+                    endpoint.path,
+                    problem
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Report every `$ref` below `value` that does not resolve; `pointer` is
+    /// the JSON pointer of `value` in the document
+    fn collect_unresolved_refs(
+        &self,
+        value: &serde_json::Value,
+        pointer: &str,
+        problems: &mut Vec<String>,
+    ) {
+        match value {
+            serde_json::Value::Object(object) => {
+                if let Some(serde_json::Value::String(reference)) = object.get("$ref") {
+                    if let Err(e) = resolve_ref(&self.raw, reference, self.base_dir.as_deref()) {
+                        problems.push(format!(
+                            "{}: unresolved $ref '{}' ({:#})",
+                            if pointer.is_empty() { "/" } else { pointer },
+                            reference,
+                            e
+                        ));
+                    }
+                }
+                for (key, child) in object {
+                    let key = key.replace('~', "~0").replace('/', "~1");
+                    self.collect_unresolved_refs(child, &format!("{}/{}", pointer, key), problems);
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.collect_unresolved_refs(item, &format!("{}/{}", pointer, index), problems);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Extract documentation for the outbound webhooks declared in the spec
+    pub fn extract_webhooks(&self) -> Vec<WebhookDoc> {
+        let methods = [
+            ("post", HttpMethod::Post),
+            ("put", HttpMethod::Put),
+            ("patch", HttpMethod::Patch),
+            ("get", HttpMethod::Get),
+            ("delete", HttpMethod::Delete),
+        ];
+
+        let mut webhooks = Vec::new();
+
+        for (name, item) in &self.webhooks {
+            // A webhook is delivered with a single method; take the first one declared
+            let Some((raw_op, method)) = methods
+                .iter()
+                .find_map(|(key, method)| item.get(*key).map(|op| (op, *method)))
+            else {
+                continue;
+            };
+
+            let Ok(operation) = serde_json::from_value::<Operation>(raw_op.clone()) else {
+                continue;
+            };
+
+            let description = operation
+                .description
+                .or(operation.summary)
+                .unwrap_or_else(|| format!("{} webhook", name));
+
+            // Prefer the JSON payload, otherwise the first declared content type
+            let content = raw_op.pointer("/requestBody/content");
+            let payload_schema = content
+                .and_then(|c| c.get("application/json"))
+                .or_else(|| {
+                    content
+                        .and_then(|c| c.as_object())
+                        .and_then(|c| c.values().next())
+                })
+                .and_then(|media| media.get("schema"))
+                .cloned();
+
+            webhooks.push(WebhookDoc {
+                name: name.clone(),
+                method,
+                description,
+                tags: operation.tags,
+                payload_schema,
+            });
+        }
+
+        webhooks
+    }
+
     fn create_endpoint(
         &self,
         path: &str,
         method: HttpMethod,
         operation: &Operation,
+        options: &ExtractOptions,
     ) -> Option<PangolinEndpoint> {
         // Generate tool name from path and method
         let name = generate_tool_name(path, method);
@@ -265,6 +647,17 @@ impl SwaggerSpec {
         let mut query_params = Vec::new();
 
         for param in &operation.parameters {
+            let resolved;
+            let param = match &param.reference {
+                Some(reference) => match self.resolve_parameter(reference) {
+                    Some(param) => {
+                        resolved = param;
+                        &resolved
+                    }
+                    None => continue,
+                },
+                None => param,
+            };
             let endpoint_param = convert_parameter(param);
             match param.location.as_str() {
                 "path" => path_params.push(endpoint_param),
@@ -273,11 +666,28 @@ impl SwaggerSpec {
             }
         }
 
+        // build_url would drop these values or send the raw placeholder
+        for problem in path_param_mismatches(path, &path_params) {
+            warn!("{} {}: {}", method.as_str(), path, problem);
+        }
+
         // Extract request body schema
-        let request_body = operation
-            .request_body
-            .as_ref()
-            .and_then(extract_request_body_schema);
+        let preferred_content_type = options.content_type_overrides.get(&name);
+        let mut request_body = operation.request_body.as_ref().and_then(|body| {
+            extract_request_body_schema(
+                body,
+                preferred_content_type.map(String::as_str),
+                &|reference| self.resolve_schema(reference),
+            )
+        });
+        if let Some(body) = request_body.as_mut() {
+            let params: Vec<&str> = path_params
+                .iter()
+                .chain(&query_params)
+                .map(|p| p.name.as_str())
+                .collect();
+            disambiguate_body_fields(body, &params);
+        }
 
         Some(PangolinEndpoint {
             name,
@@ -288,10 +698,44 @@ impl SwaggerSpec {
             path_params,
             query_params,
             request_body,
+            responses: extract_responses(&operation.responses),
         })
     }
 }
 
+impl SwaggerSpec {
+    /// Resolve a parameter `$ref` (usually into `components.parameters`)
+    /// against the raw document
+    fn resolve_parameter(&self, reference: &str) -> Option<Parameter> {
+        let resolved = resolve_ref(&self.raw, reference, self.base_dir.as_deref())
+            .and_then(|value| Ok(serde_json::from_value::<Parameter>(value)?));
+        match resolved {
+            Ok(param) if !param.name.is_empty() => Some(param),
+            Ok(_) => {
+                warn!("Skipping parameter {}: no name", reference);
+                None
+            }
+            Err(e) => {
+                warn!("Skipping parameter {}: {:#}", reference, e);
+                None
+            }
+        }
+    }
+
+    /// Resolve a schema `$ref` against the raw document
+    fn resolve_schema(&self, reference: &str) -> Option<Schema> {
+        let resolved = resolve_ref(&self.raw, reference, self.base_dir.as_deref())
+            .and_then(|value| Ok(serde_json::from_value(value)?));
+        match resolved {
+            Ok(schema) => Some(schema),
+            Err(e) => {
+                warn!("Skipping schema {}: {:#}", reference, e);
+                None
+            }
+        }
+    }
+}
+
 /// Generate a tool name from path and method
 fn generate_tool_name(path: &str, method: HttpMethod) -> String {
     // Remove leading slash and replace special chars
@@ -329,80 +773,392 @@ fn convert_parameter(param: &Parameter) -> EndpointParameter {
 
     let default_value = schema.and_then(|s| s.default.clone());
 
+    // Parameter-level examples win over the schema's; OpenAPI 3.0 schemas
+    // use `example`, JSON Schema (OpenAPI 3.1) uses `examples`
+    let example = param
+        .example
+        .clone()
+        .or_else(|| {
+            param
+                .examples
+                .as_ref()
+                .and_then(|e| e.values().find_map(|ex| ex.get("value").cloned()))
+        })
+        .or_else(|| {
+            schema.and_then(|s| {
+                s.example
+                    .clone()
+                    .or_else(|| s.examples.as_ref().and_then(|e| e.first().cloned()))
+            })
+        });
+
     EndpointParameter {
         name: param.name.clone(),
         param_type,
         required: param.required,
         description: param.description.clone(),
+        format: schema.and_then(|s| s.format.clone()),
         default_value,
+        enum_values: schema.and_then(|s| s.enum_values.clone()),
+        example,
     }
 }
 
+/// Convert documented responses, keeping the JSON body schema when present
+fn extract_responses(responses: &IndexMap<String, serde_json::Value>) -> Vec<EndpointResponse> {
+    responses
+        .iter()
+        .map(|(status, response)| EndpointResponse {
+            status: status.clone(),
+            description: response
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(String::from),
+            schema: response
+                .pointer("/content/application~1json/schema")
+                .cloned(),
+        })
+        .collect()
+}
+
+/// Pick the request content type to use for an operation
+///
+/// An explicit preference wins when the operation declares it; otherwise
+/// JSON is preferred over form encoding over plain text, falling back to the
+/// first declared content type.
+fn select_content_type<'a>(
+    content: &'a IndexMap<String, MediaType>,
+    preferred: Option<&str>,
+) -> Option<(&'a String, &'a MediaType)> {
+    preferred
+        .into_iter()
+        .chain(CONTENT_TYPE_PREFERENCE.iter().copied())
+        .find_map(|content_type| content.get_key_value(content_type))
+        .or_else(|| content.first())
+}
+
 /// Extract request body schema from OpenAPI request body
-fn extract_request_body_schema(request_body: &RequestBody) -> Option<RequestBodySchema> {
-    // Get JSON content type
-    let media_type = request_body
-        .content
-        .get("application/json")
-        .or_else(|| request_body.content.values().next())?;
+fn extract_request_body_schema(
+    request_body: &RequestBody,
+    preferred_content_type: Option<&str>,
+    resolve: &dyn Fn(&str) -> Option<Schema>,
+) -> Option<RequestBodySchema> {
+    let (content_type, media_type) =
+        select_content_type(&request_body.content, preferred_content_type)?;
 
     let schema = media_type.schema.as_ref()?;
 
-    // Handle allOf, anyOf, oneOf by merging properties
-    let mut all_properties = HashMap::new();
+    // Merge direct properties and (nested, referenced) allOf members
+    let mut all_properties = IndexMap::new();
     let mut all_required = Vec::new();
+    let mut read_only_properties = Vec::new();
+    collect_composed_properties(
+        schema,
+        resolve,
+        &mut all_properties,
+        &mut all_required,
+        &mut read_only_properties,
+        0,
+    );
+
+    // Process oneOf/anyOf, keeping each branch separate
+    let (branches, variant_kind) = match (&schema.one_of, &schema.any_of) {
+        (Some(one_of), _) => (one_of.as_slice(), VariantKind::OneOf),
+        (None, Some(any_of)) => (any_of.as_slice(), VariantKind::AnyOf),
+        (None, None) => (&[][..], VariantKind::OneOf),
+    };
+    let discriminator = schema.discriminator.as_ref();
+    let mut variants = Vec::new();
+    for branch in branches {
+        let resolved = match branch.reference {
+            Some(ref reference) => match resolve(reference) {
+                Some(schema) => schema,
+                None => continue,
+            },
+            None => branch.clone(),
+        };
+
+        let mut properties = IndexMap::new();
+        let mut required = Vec::new();
+        collect_composed_properties(
+            &resolved,
+            resolve,
+            &mut properties,
+            &mut required,
+            &mut read_only_properties,
+            0,
+        );
+        required.retain(|name| !read_only_properties.contains(name));
 
-    // Process direct properties
-    if let Some(props) = &schema.properties {
-        for (name, prop) in props {
-            all_properties.insert(name.clone(), convert_schema_property(name, prop));
+        // Each branch only accepts its own discriminator values
+        let discriminator_values = discriminator
+            .map(|d| discriminator_values(&resolved, branch.reference.as_deref(), d))
+            .unwrap_or_default();
+        if let Some(prop) = discriminator.and_then(|d| properties.get_mut(&d.property_name)) {
+            if !discriminator_values.is_empty() {
+                prop.enum_values = Some(discriminator_values.clone());
+            }
         }
-    }
-    if let Some(req) = &schema.required {
-        all_required.extend(req.clone());
+
+        for (name, prop) in &properties {
+            all_properties
+                .entry(name.clone())
+                .or_insert_with(|| prop.clone());
+        }
+        variants.push(BodyVariant {
+            discriminator_values,
+            properties,
+            required,
+        });
     }
 
-    // Process allOf
-    if let Some(all_of) = &schema.all_of {
-        for sub_schema in all_of {
-            if let Some(props) = &sub_schema.properties {
-                for (name, prop) in props {
-                    // props is HashMap<String, SchemaProperty>, so prop is already SchemaProperty
-                    all_properties.insert(name.clone(), convert_schema_property(name, prop));
-                }
-            }
-            if let Some(req) = &sub_schema.required {
-                all_required.extend(req.clone());
+    // Fields every branch requires are required overall
+    if let Some((first, rest)) = variants.split_first() {
+        for name in &first.required {
+            if rest.iter().all(|v| v.required.contains(name)) && !all_required.contains(name) {
+                all_required.push(name.clone());
             }
         }
     }
 
-    // Process anyOf (take first one as example)
-    if let Some(any_of) = &schema.any_of {
-        if let Some(first) = any_of.first() {
-            if let Some(props) = &first.properties {
-                for (name, prop) in props {
-                    // props is HashMap<String, SchemaProperty>, so prop is already SchemaProperty
-                    all_properties.insert(name.clone(), convert_schema_property(name, prop));
-                }
-            }
-            if let Some(req) = &first.required {
-                all_required.extend(req.clone());
-            }
+    // The merged discriminator property accepts every branch's value
+    if let Some(prop) = discriminator.and_then(|d| all_properties.get_mut(&d.property_name)) {
+        let values: Vec<String> = variants
+            .iter()
+            .flat_map(|v| v.discriminator_values.iter().cloned())
+            .collect();
+        if !values.is_empty() {
+            prop.enum_values = Some(values);
         }
     }
 
-    if all_properties.is_empty() {
+    all_required.retain(|name| !read_only_properties.contains(name));
+
+    // Top-level arrays are taken whole through a single argument
+    let array_body = all_properties.is_empty() && schema.schema_type.as_deref() == Some("array");
+    if array_body {
+        let items = SchemaProperty {
+            schema_type: Some("array".to_string()),
+            description: Some("Request body items (sent as a JSON array)".to_string()),
+            items: schema.items.clone(),
+            ..Default::default()
+        };
+        all_properties.insert(
+            ARRAY_BODY.to_string(),
+            convert_schema_property(ARRAY_BODY, &items),
+        );
+        all_required.push(ARRAY_BODY.to_string());
+    }
+
+    // A body whose every property is readOnly takes no input at all; it
+    // stays a closed, empty object rather than turning free-form
+    let all_read_only = all_properties.is_empty() && !read_only_properties.is_empty();
+
+    // Arbitrary JSON objects are taken whole through a single argument
+    let free_form = all_properties.is_empty()
+        && !all_read_only
+        && (schema.additional_properties == Some(true)
+            || schema.schema_type.as_deref() == Some("object"));
+    if free_form {
+        let body = SchemaProperty {
+            schema_type: Some("object".to_string()),
+            description: Some("Request body (free-form JSON object)".to_string()),
+            ..Default::default()
+        };
+        all_properties.insert(
+            FREE_FORM_BODY.to_string(),
+            convert_schema_property(FREE_FORM_BODY, &body),
+        );
+    }
+
+    if all_properties.is_empty() && !all_read_only {
         return None;
     }
 
     Some(RequestBodySchema {
-        content_type: "application/json".to_string(),
+        content_type: content_type.clone(),
+        content_types: request_body.content.keys().cloned().collect(),
         properties: all_properties,
         required: all_required,
+        body_required: request_body.required,
+        read_only_properties,
+        free_form,
+        array_body,
+        additional_properties: if all_read_only {
+            Some(false)
+        } else {
+            schema.additional_properties
+        },
+        discriminator: discriminator
+            .filter(|_| !variants.is_empty())
+            .map(|d| d.property_name.clone()),
+        variant_kind,
+        variants,
+        renamed: IndexMap::new(),
     })
 }
 
+/// Prefix given to body fields whose name a path or query parameter already uses
+const BODY_ALIAS_PREFIX: &str = "body_";
+
+/// Rename body fields colliding with a path or query parameter (`id` ->
+/// `body_id`) so both can be passed as tool arguments
+fn disambiguate_body_fields(body: &mut RequestBodySchema, params: &[&str]) {
+    let colliding: Vec<String> = body
+        .properties
+        .keys()
+        .chain(&body.read_only_properties)
+        .filter(|name| params.contains(&name.as_str()))
+        .cloned()
+        .collect();
+
+    for field in colliding {
+        let mut alias = format!("{}{}", BODY_ALIAS_PREFIX, field);
+        while params.contains(&alias.as_str()) || body.properties.contains_key(&alias) {
+            alias = format!("{}{}", BODY_ALIAS_PREFIX, alias);
+        }
+
+        let rename = |name: &mut String| {
+            if *name == field {
+                *name = alias.clone();
+            }
+        };
+        if let Some(index) = body.properties.get_index_of(&field) {
+            let (_, mut prop) = body.properties.shift_remove_index(index).unwrap();
+            prop.description = Some(match prop.description.take() {
+                Some(desc) => format!("{} (body field '{}')", desc, field),
+                None => format!("Body field '{}'", field),
+            });
+            body.properties.shift_insert(index, alias.clone(), prop);
+        }
+        body.required.iter_mut().for_each(rename);
+        body.read_only_properties.iter_mut().for_each(rename);
+        body.discriminator.iter_mut().for_each(rename);
+        for variant in &mut body.variants {
+            variant.required.iter_mut().for_each(rename);
+            if let Some(index) = variant.properties.get_index_of(&field) {
+                let (_, prop) = variant.properties.shift_remove_index(index).unwrap();
+                variant.properties.shift_insert(index, alias.clone(), prop);
+            }
+        }
+        body.renamed.insert(alias, field);
+    }
+}
+
+/// Maximum nesting of `allOf`/`$ref` followed when merging a body schema
+const MAX_ALL_OF_DEPTH: usize = 16;
+
+/// Add a schema's properties and required fields, following `$ref`s and
+/// recursing into `allOf` members
+///
+/// Members are merged in order, so a later definition of a property
+/// overrides an earlier one (keeping the first position).
+fn collect_composed_properties(
+    schema: &Schema,
+    resolve: &dyn Fn(&str) -> Option<Schema>,
+    properties: &mut IndexMap<String, PropertySchema>,
+    required: &mut Vec<String>,
+    read_only_properties: &mut Vec<String>,
+    depth: usize,
+) {
+    if depth > MAX_ALL_OF_DEPTH {
+        warn!(
+            "allOf nesting deeper than {} levels, ignoring the rest",
+            MAX_ALL_OF_DEPTH
+        );
+        return;
+    }
+
+    if let Some(ref reference) = schema.reference {
+        if let Some(resolved) = resolve(reference) {
+            collect_composed_properties(
+                &resolved,
+                resolve,
+                properties,
+                required,
+                read_only_properties,
+                depth + 1,
+            );
+        }
+        return;
+    }
+
+    collect_properties(schema, properties, required, read_only_properties);
+    for sub_schema in schema.all_of.iter().flatten() {
+        collect_composed_properties(
+            sub_schema,
+            resolve,
+            properties,
+            required,
+            read_only_properties,
+            depth + 1,
+        );
+    }
+}
+
+/// Add a schema's direct properties and required fields
+///
+/// Server-assigned (readOnly) properties are never accepted as input and are
+/// recorded separately instead.
+fn collect_properties(
+    schema: &Schema,
+    properties: &mut IndexMap<String, PropertySchema>,
+    required: &mut Vec<String>,
+    read_only_properties: &mut Vec<String>,
+) {
+    for (name, prop) in schema.properties.iter().flatten() {
+        if prop.read_only == Some(true) {
+            if !read_only_properties.contains(name) {
+                read_only_properties.push(name.clone());
+            }
+        } else {
+            properties.insert(name.clone(), convert_schema_property(name, prop));
+        }
+    }
+    for name in schema.required.iter().flatten() {
+        if !required.contains(name) {
+            required.push(name.clone());
+        }
+    }
+}
+
+/// Discriminator values selecting a `oneOf` branch
+///
+/// Explicit `mapping` entries win, then an `enum` on the branch's
+/// discriminator property, then the implicit mapping to the schema name.
+fn discriminator_values(
+    branch: &Schema,
+    reference: Option<&str>,
+    discriminator: &Discriminator,
+) -> Vec<String> {
+    if let Some(reference) = reference {
+        let schema_name = reference.rsplit('/').next().unwrap_or(reference);
+        let mapped: Vec<String> = discriminator
+            .mapping
+            .iter()
+            .filter(|(_, target)| *target == reference || *target == schema_name)
+            .map(|(value, _)| value.clone())
+            .collect();
+        if !mapped.is_empty() {
+            return mapped;
+        }
+    }
+
+    if let Some(values) = branch
+        .properties
+        .as_ref()
+        .and_then(|props| props.get(&discriminator.property_name))
+        .and_then(|prop| prop.enum_values.clone())
+    {
+        return values;
+    }
+
+    reference
+        .and_then(|r| r.rsplit('/').next())
+        .map(|name| vec![name.to_string()])
+        .unwrap_or_default()
+}
+
 /// Convert OpenAPI SchemaProperty to our PropertySchema type
 fn convert_schema_property(name: &str, prop: &SchemaProperty) -> PropertySchema {
     let param_type = prop
@@ -420,20 +1176,163 @@ fn convert_schema_property(name: &str, prop: &SchemaProperty) -> PropertySchema
         name: name.to_string(),
         param_type,
         description: prop.description.clone(),
+        format: prop.format.clone(),
         default_value: prop.default.clone(),
         enum_values: prop.enum_values.clone(),
         nullable: prop.nullable.unwrap_or(false),
         min_length: prop.min_length,
         max_length: prop.max_length,
-        minimum: prop.minimum,
-        maximum: prop.maximum,
+        minimum: prop.minimum.clone(),
+        maximum: prop.maximum.clone(),
         pattern: prop.pattern.clone(),
+        multiple_of: prop.multiple_of,
+        min_items: prop.min_items,
+        max_items: prop.max_items,
+        unique_items: prop.unique_items.unwrap_or(false),
+        const_value: prop.const_value.clone(),
+        example: prop
+            .example
+            .clone()
+            .or_else(|| prop.examples.as_ref().and_then(|e| e.first().cloned())),
         items,
     }
 }
 
+/// Resolve a `$ref` against the document root or an external file
+///
+/// Supports local references (`#/components/pathItems/Org`) and references
+/// into other files (`paths/org.json#/Org`), which are resolved relative to
+/// `base_dir`. Chained references are followed up to [`MAX_REF_DEPTH`] hops.
+pub fn resolve_ref(
+    root: &serde_json::Value,
+    reference: &str,
+    base_dir: Option<&Path>,
+) -> Result<serde_json::Value> {
+    let mut reference = reference.to_string();
+    let mut external: Option<(serde_json::Value, Option<PathBuf>)> = None;
+
+    for _ in 0..MAX_REF_DEPTH {
+        let (file, fragment) = reference.split_once('#').unwrap_or((&reference, ""));
+
+        if !file.is_empty() {
+            let dir = external
+                .as_ref()
+                .and_then(|(_, dir)| dir.clone())
+                .or_else(|| base_dir.map(Path::to_path_buf));
+            let file_path = match dir {
+                Some(dir) => dir.join(file),
+                None if Path::new(file).is_absolute() => PathBuf::from(file),
+                None => anyhow::bail!(
+                    "Cannot resolve external $ref '{}' without a base directory",
+                    reference
+                ),
+            };
+            let content = std::fs::read_to_string(&file_path)
+                .with_context(|| format!("Failed to read $ref file {:?}", file_path))?;
+            let doc = serde_json::from_str(&content)
+                .with_context(|| format!("Failed to parse $ref file {:?}", file_path))?;
+            external = Some((doc, file_path.parent().map(Path::to_path_buf)));
+        }
+
+        let doc = external.as_ref().map(|(doc, _)| doc).unwrap_or(root);
+        let target = doc
+            .pointer(fragment)
+            .with_context(|| format!("Unresolved $ref '{}'", reference))?;
+
+        match target.get("$ref").and_then(|r| r.as_str()) {
+            Some(next) => reference = next.to_string(),
+            None => return Ok(target.clone()),
+        }
+    }
+
+    anyhow::bail!("$ref '{}' exceeds maximum depth (cycle?)", reference)
+}
+
+/// Path item keys holding operations
+pub const SPEC_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Object member `key` of `doc`, created when missing
+fn object_entry<'a>(
+    doc: &'a mut serde_json::Value,
+    key: &str,
+) -> &'a mut serde_json::Map<String, serde_json::Value> {
+    let object = doc.as_object_mut().expect("spec documents are objects");
+    let entry = object
+        .entry(key.to_string())
+        .or_insert_with(|| serde_json::json!({}));
+    if !entry.is_object() {
+        *entry = serde_json::json!({});
+    }
+    entry.as_object_mut().unwrap()
+}
+
+/// Members of the object at `key` of `doc` (none when missing)
+fn object_members<'a>(
+    doc: &'a serde_json::Value,
+    key: &str,
+) -> impl Iterator<Item = (&'a String, &'a serde_json::Value)> {
+    doc.get(key)
+        .and_then(|value| value.as_object())
+        .into_iter()
+        .flatten()
+}
+
+/// Add named definitions, allowing duplicates only when identical
+fn merge_definitions(
+    target: &mut serde_json::Map<String, serde_json::Value>,
+    definitions: &serde_json::Map<String, serde_json::Value>,
+    section: &str,
+) -> Result<()> {
+    for (name, definition) in definitions {
+        match target.get(name) {
+            None => {
+                target.insert(name.clone(), definition.clone());
+            }
+            Some(existing) if existing == definition => {}
+            Some(_) => anyhow::bail!("Conflicting definitions of {}.{}", section, name),
+        }
+    }
+    Ok(())
+}
+
+/// Replace `$ref` path items in the raw document with their targets
+///
+/// Sibling keys next to the `$ref` take precedence over the referenced item.
+/// Unresolvable references are left in place and produce no endpoints.
+fn resolve_path_item_refs(doc: &mut serde_json::Value, base_dir: Option<&Path>) {
+    let root = doc.clone();
+    let Some(paths) = doc.get_mut("paths").and_then(|p| p.as_object_mut()) else {
+        return;
+    };
+
+    for (path, item) in paths.iter_mut() {
+        let Some(reference) = item.get("$ref").and_then(|r| r.as_str()).map(String::from) else {
+            continue;
+        };
+
+        match resolve_ref(&root, &reference, base_dir) {
+            Ok(serde_json::Value::Object(mut resolved)) => {
+                if let Some(siblings) = item.as_object() {
+                    for (key, value) in siblings {
+                        if key != "$ref" {
+                            resolved.insert(key.clone(), value.clone());
+                        }
+                    }
+                }
+                *item = serde_json::Value::Object(resolved);
+            }
+            Ok(_) => warn!(
+                "Path item $ref '{}' for {} is not an object",
+                reference, path
+            ),
+            Err(e) => warn!("Failed to resolve path item for {}: {:#}", path, e),
+        }
+    }
+}
+
 /// Extract path parameters from a path template
-#[allow(dead_code)]
 fn extract_path_params(path: &str) -> Vec<String> {
     let re = Regex::new(r"\{([^}]+)\}").unwrap();
     re.captures_iter(path)
@@ -441,14 +1340,66 @@ fn extract_path_params(path: &str) -> Vec<String> {
         .collect()
 }
 
+/// Prepend a base path to an endpoint path, with exactly one slash between
+/// segments
+pub fn join_base_path(base_path: &str, path: &str) -> String {
+    let base_path = base_path.trim_matches('/');
+    if base_path.is_empty() {
+        return path.to_string();
+    }
+    format!("/{}/{}", base_path, path.trim_start_matches('/'))
+}
+
+/// Declared path parameters without a `{name}` placeholder in `path`, and
+/// placeholders without a declared path parameter
+fn path_param_mismatches(path: &str, params: &[EndpointParameter]) -> Vec<String> {
+    let placeholders = extract_path_params(path);
+    let unplaced = params
+        .iter()
+        .filter(|p| !placeholders.contains(&p.name))
+        .map(|p| {
+            format!(
+                "path parameter '{}' has no {{{}}} placeholder",
+                p.name, p.name
+            )
+        });
+    let undeclared = placeholders
+        .iter()
+        .filter(|name| !params.iter().any(|p| &p.name == *name))
+        .map(|name| format!("placeholder {{{}}} has no declared path parameter", name));
+    unplaced.chain(undeclared).collect()
+}
+
 /// Build the actual URL by substituting path parameters
+///
+/// Accidental double slashes (from a trailing slash on the base URL or an
+/// empty path segment) are collapsed, except for the one after the scheme.
 pub fn build_url(base_url: &str, path: &str, path_params: &HashMap<String, String>) -> String {
-    let mut url = format!("{}{}", base_url.trim_end_matches('/'), path);
-
+    let mut path = path.to_string();
     for (key, value) in path_params {
-        url = url.replace(&format!("{{{}}}", key), value);
+        path = path.replace(&format!("{{{}}}", key), value);
+    }
+
+    let (scheme, rest) = match base_url.split_once("://") {
+        Some((scheme, rest)) => (format!("{}://", scheme), rest),
+        None => (String::new(), base_url),
+    };
+
+    let mut joined = rest.trim_end_matches('/').to_string();
+    if !path.is_empty() {
+        joined.push('/');
+        joined.push_str(&path);
     }
 
+    let mut url = scheme;
+    let mut previous_slash = false;
+    for c in joined.chars() {
+        if c == '/' && previous_slash {
+            continue;
+        }
+        previous_slash = c == '/';
+        url.push(c);
+    }
     url
 }
 
@@ -473,12 +1424,556 @@ mod tests {
         assert_eq!(generate_tool_name("/", HttpMethod::Get), "health_check");
     }
 
+    #[test]
+    fn test_extract_webhooks() {
+        let spec = SwaggerSpec::from_json(
+            r#"{
+                "openapi": "3.1.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {},
+                "webhooks": {
+                    "siteOnline": {
+                        "post": {
+                            "description": "Sent when a site comes online",
+                            "tags": ["Site"],
+                            "requestBody": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "object",
+                                            "properties": {"siteId": {"type": "integer"}}
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    "orgDeleted": {
+                        "post": {"summary": "Sent when an org is deleted"}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let webhooks = spec.extract_webhooks();
+        assert_eq!(webhooks.len(), 2);
+
+        assert_eq!(webhooks[0].name, "siteOnline");
+        assert_eq!(webhooks[0].method, HttpMethod::Post);
+        assert_eq!(webhooks[0].description, "Sent when a site comes online");
+        assert_eq!(
+            webhooks[0].payload_schema.as_ref().unwrap()["properties"]["siteId"]["type"],
+            "integer"
+        );
+
+        assert_eq!(webhooks[1].name, "orgDeleted");
+        assert_eq!(webhooks[1].description, "Sent when an org is deleted");
+        assert!(webhooks[1].payload_schema.is_none());
+    }
+
+    #[test]
+    fn test_path_item_ref_to_components() {
+        let spec = SwaggerSpec::from_json(
+            r##"{
+                "openapi": "3.1.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {
+                    "/org/{orgId}": {"$ref": "#/components/pathItems/Org"}
+                },
+                "components": {
+                    "pathItems": {
+                        "Org": {
+                            "get": {
+                                "description": "Get an organization",
+                                "parameters": [
+                                    {"name": "orgId", "in": "path", "required": true}
+                                ]
+                            },
+                            "delete": {"description": "Delete an organization"}
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let endpoints = spec.extract_endpoints();
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].name, "org_by_orgId");
+        assert_eq!(endpoints[0].description, "Get an organization");
+        assert_eq!(endpoints[0].path_params[0].name, "orgId");
+        assert_eq!(endpoints[1].name, "delete_org_by_orgId");
+    }
+
+    #[test]
+    fn test_parameter_refs_to_components() {
+        let spec = SwaggerSpec::from_json(
+            r##"{
+                "openapi": "3.0.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {
+                    "/org/{orgId}/sites": {
+                        "get": {
+                            "parameters": [
+                                {"$ref": "#/components/parameters/OrgId"},
+                                {"$ref": "#/components/parameters/Limit"},
+                                {"$ref": "#/components/parameters/Missing"}
+                            ]
+                        }
+                    }
+                },
+                "components": {
+                    "parameters": {
+                        "OrgId": {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "description": "Organization ID",
+                            "schema": {"type": "string"}
+                        },
+                        "Limit": {"$ref": "#/components/parameters/PageSize"},
+                        "PageSize": {
+                            "name": "limit",
+                            "in": "query",
+                            "schema": {"type": "integer", "default": 50}
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let endpoints = spec.extract_endpoints();
+        assert_eq!(endpoints.len(), 1);
+        let endpoint = &endpoints[0];
+        assert_eq!(endpoint.path_params.len(), 1);
+        assert_eq!(endpoint.path_params[0].name, "orgId");
+        assert!(endpoint.path_params[0].required);
+        assert_eq!(
+            endpoint.path_params[0].description.as_deref(),
+            Some("Organization ID")
+        );
+        assert_eq!(endpoint.query_params.len(), 1);
+        assert_eq!(endpoint.query_params[0].name, "limit");
+        assert_eq!(endpoint.query_params[0].param_type, ParameterType::Integer);
+        assert_eq!(
+            endpoint.query_params[0].default_value,
+            Some(serde_json::json!(50))
+        );
+    }
+
+    #[test]
+    fn test_nested_all_of_refs_are_merged() {
+        let spec = SwaggerSpec::from_json(
+            r##"{
+                "openapi": "3.0.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {
+                    "/org/{orgId}/resource": {
+                        "put": {
+                            "requestBody": {
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "allOf": [
+                                                {"$ref": "#/components/schemas/HttpResource"},
+                                                {
+                                                    "type": "object",
+                                                    "properties": {"ssl": {"type": "boolean"}}
+                                                }
+                                            ]
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "components": {
+                    "schemas": {
+                        "Named": {
+                            "type": "object",
+                            "properties": {
+                                "name": {"type": "string"},
+                                "ssl": {"type": "string"}
+                            },
+                            "required": ["name"]
+                        },
+                        "BaseResource": {
+                            "allOf": [
+                                {"$ref": "#/components/schemas/Named"},
+                                {"type": "object", "properties": {"siteId": {"type": "integer"}}}
+                            ]
+                        },
+                        "HttpResource": {
+                            "allOf": [
+                                {"$ref": "#/components/schemas/BaseResource"},
+                                {
+                                    "type": "object",
+                                    "properties": {"subdomain": {"type": "string"}},
+                                    "required": ["subdomain", "name"]
+                                }
+                            ]
+                        }
+                    }
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let endpoints = spec.extract_endpoints();
+        let body = endpoints[0].request_body.as_ref().unwrap();
+        let names: Vec<_> = body.properties.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["name", "ssl", "siteId", "subdomain"]);
+        // The later member's definition wins
+        assert!(matches!(
+            body.properties["ssl"].param_type,
+            ParameterType::Boolean
+        ));
+        assert_eq!(body.required, vec!["name", "subdomain"]);
+    }
+
+    #[test]
+    fn test_path_item_ref_to_external_file() {
+        let dir = std::env::temp_dir().join(format!("mcp-pangolin-ref-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("sites.json"),
+            r#"{"SiteItem": {"get": {"description": "Get a site"}}}"#,
+        )
+        .unwrap();
+        let spec_path = dir.join("openapi.json");
+        std::fs::write(
+            &spec_path,
+            r##"{
+                "openapi": "3.1.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {
+                    "/site/{siteId}": {"$ref": "sites.json#/SiteItem"},
+                    "/missing": {"$ref": "#/components/pathItems/Missing"}
+                }
+            }"##,
+        )
+        .unwrap();
+
+        let spec = SwaggerSpec::from_file(spec_path.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // The unresolved reference is tolerated and yields no endpoints
+        let endpoints = spec.extract_endpoints();
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].name, "site_by_siteId");
+        assert_eq!(endpoints[0].description, "Get a site");
+    }
+
+    const MULTI_CONTENT_TYPE_SPEC: &str = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "text/plain": {
+                                "schema": {"type": "object", "properties": {"raw": {"type": "string"}}}
+                            },
+                            "application/x-www-form-urlencoded": {
+                                "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                            },
+                            "application/json": {
+                                "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_content_type_selection_prefers_json() {
+        for _ in 0..10 {
+            let spec = SwaggerSpec::from_json(MULTI_CONTENT_TYPE_SPEC).unwrap();
+            let endpoints = spec.extract_endpoints();
+            let body = endpoints[0].request_body.as_ref().unwrap();
+            assert_eq!(body.content_type, "application/json");
+        }
+    }
+
+    #[test]
+    fn test_content_type_override() {
+        let spec = SwaggerSpec::from_json(MULTI_CONTENT_TYPE_SPEC).unwrap();
+        let mut options = ExtractOptions::default();
+        options.content_type_overrides.insert(
+            "update_org_by_orgId".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        );
+
+        let endpoints = spec.extract_endpoints_with(&options);
+        let body = endpoints[0].request_body.as_ref().unwrap();
+        assert_eq!(body.content_type, "application/x-www-form-urlencoded");
+
+        // Overrides naming an undeclared content type fall back to the preference order
+        options.content_type_overrides.insert(
+            "update_org_by_orgId".to_string(),
+            "application/xml".to_string(),
+        );
+        let endpoints = spec.extract_endpoints_with(&options);
+        let body = endpoints[0].request_body.as_ref().unwrap();
+        assert_eq!(body.content_type, "application/json");
+    }
+
+    #[test]
+    fn test_apply_overlay_hides_and_updates_operations() {
+        let mut spec = SwaggerSpec::from_json(
+            r#"{
+                "openapi": "3.0.0",
+                "info": {"title": "Test", "version": "v1"},
+                "paths": {
+                    "/org/{orgId}": {
+                        "get": {"description": "Get an organization"},
+                        "delete": {"description": "Delete an organization"}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let overlay = serde_json::json!({
+            "overlay": "1.0.0",
+            "info": {"title": "Hide deletes", "version": "1.0.0"},
+            "actions": [
+                {"target": "$.paths['/org/{orgId}'].delete", "remove": true},
+                {
+                    "target": "$.paths['/org/{orgId}'].get",
+                    "update": {"description": "Fetch one organization by its ID"}
+                }
+            ]
+        });
+        assert_eq!(spec.apply_overlay(&overlay).unwrap(), 2);
+
+        let endpoints = spec.extract_endpoints();
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].name, "org_by_orgId");
+        assert_eq!(endpoints[0].description, "Fetch one organization by its ID");
+    }
+
+    #[test]
+    fn test_merge_combines_paths_and_rejects_conflicts() {
+        let core = r##"{
+            "openapi": "3.0.0",
+            "info": {"title": "Core", "version": "v1"},
+            "paths": {
+                "/org/{orgId}": {
+                    "get": {"description": "Get an organization", "responses": {"200": {"description": "ok", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Org"}}}}}}
+                }
+            },
+            "components": {"schemas": {"Org": {"type": "object"}}}
+        }"##;
+        let admin = r##"{
+            "openapi": "3.0.0",
+            "info": {"title": "Admin", "version": "v1"},
+            "paths": {
+                "/org/{orgId}": {
+                    "delete": {"description": "Delete an organization"}
+                },
+                "/users": {
+                    "get": {"description": "List users", "responses": {"200": {"description": "ok", "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}}}}
+                }
+            },
+            "components": {"schemas": {"Org": {"type": "object"}, "User": {"type": "object"}}}
+        }"##;
+
+        let mut spec = SwaggerSpec::from_json(core).unwrap();
+        spec.merge(SwaggerSpec::from_json(admin).unwrap()).unwrap();
+        assert_eq!(spec.info.title, "Core");
+        let endpoints: Vec<_> = spec
+            .extract_endpoints()
+            .into_iter()
+            .map(|e| (e.method, e.path))
+            .collect();
+        assert_eq!(
+            endpoints,
+            vec![
+                (HttpMethod::Get, "/org/{orgId}".to_string()),
+                (HttpMethod::Delete, "/org/{orgId}".to_string()),
+                (HttpMethod::Get, "/users".to_string()),
+            ]
+        );
+        let schemas = spec.components.as_ref().unwrap().schemas.as_ref().unwrap();
+        assert!(schemas.contains_key("User"));
+
+        let billing = r#"{
+            "openapi": "3.0.0",
+            "info": {"title": "Billing", "version": "v1"},
+            "paths": {"/users": {"get": {"description": "List billed users"}}}
+        }"#;
+        let err = spec
+            .merge(SwaggerSpec::from_json(billing).unwrap())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Conflicting definitions of GET /users");
+    }
+
+    #[test]
+    fn test_apply_patch_adds_parameters() {
+        let spec_json = r#"{
+            "openapi": "3.0.0",
+            "info": {"title": "Test", "version": "v1"},
+            "paths": {
+                "/org/{orgId}/sites": {
+                    "get": {
+                        "description": "List sites",
+                        "parameters": [
+                            {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                            {"name": "limit", "in": "query", "schema": {"type": "integer"}}
+                        ]
+                    }
+                }
+            }
+        }"#;
+
+        // JSON Patch: append a query parameter and make another one required
+        let mut spec = SwaggerSpec::from_json(spec_json).unwrap();
+        let patch = serde_json::json!([
+            {
+                "op": "add",
+                "path": "/paths/~1org~1{orgId}~1sites/get/parameters/-",
+                "value": {"name": "offset", "in": "query", "schema": {"type": "integer"}}
+            },
+            {"op": "add", "path": "/paths/~1org~1{orgId}~1sites/get/parameters/1/required", "value": true}
+        ]);
+        spec.apply_patch(&patch).unwrap();
+        let endpoints = spec.extract_endpoints();
+        let query: Vec<_> = endpoints[0]
+            .query_params
+            .iter()
+            .map(|p| (p.name.as_str(), p.required))
+            .collect();
+        assert_eq!(query, vec![("limit", true), ("offset", false)]);
+
+        // Merge patch: replace the description, drop nothing else
+        let mut spec = SwaggerSpec::from_json(spec_json).unwrap();
+        let patch = serde_json::json!({
+            "paths": {"/org/{orgId}/sites": {"get": {"description": "List the sites of an organization"}}}
+        });
+        spec.apply_patch(&patch).unwrap();
+        let endpoints = spec.extract_endpoints();
+        assert_eq!(
+            endpoints[0].description,
+            "List the sites of an organization"
+        );
+        assert_eq!(endpoints[0].query_params.len(), 1);
+
+        // Failed operations name the offending step
+        let patch = serde_json::json!([{"op": "remove", "path": "/paths/~1missing"}]);
+        let err = spec.apply_patch(&patch).unwrap_err();
+        assert_eq!(
+            format!("{:#}", err),
+            "JSON Patch operation #0 failed: no value at '/paths/~1missing'"
+        );
+    }
+
     #[test]
     fn test_extract_path_params() {
         let params = extract_path_params("/org/{orgId}/site/{siteId}/resource/{resourceId}");
         assert_eq!(params, vec!["orgId", "siteId", "resourceId"]);
     }
 
+    #[test]
+    fn test_validate_collects_every_problem() {
+        let spec_json = r##"{
+            "openapi": "3.0.0",
+            "info": {"title": "Test", "version": "1.0.0"},
+            "paths": {
+                "/org/{orgId}": {
+                    "get": {
+                        "operationId": "getOrg",
+                        "parameters": [
+                            {"name": "orgId", "in": "path", "schema": {"type": "string"}},
+                            {"$ref": "#/components/parameters/Missing"},
+                            {"in": "query", "schema": {"type": "string"}}
+                        ],
+                        "responses": {}
+                    },
+                    "post": {
+                        "operationId": "getOrg",
+                        "parameters": [
+                            {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                            {"name": "verbose"}
+                        ],
+                        "requestBody": {
+                            "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Nope"}}}
+                        },
+                        "responses": {}
+                    }
+                }
+            }
+        }"##;
+        let spec = SwaggerSpec::from_json(spec_json).unwrap();
+        let problems = spec.validate();
+        assert_eq!(problems.len(), 6, "{:#?}", problems);
+        assert!(problems[0].starts_with(
+            "/paths/~1org~1{orgId}/get/parameters/1: unresolved $ref '#/components/parameters/Missing'"
+        ));
+        assert!(problems[1].starts_with(
+            "/paths/~1org~1{orgId}/post/requestBody/content/application~1json/schema: unresolved $ref '#/components/schemas/Nope'"
+        ));
+        assert_eq!(
+            problems[2..],
+            [
+                "GET /org/{orgId}: path parameter 'orgId' is not marked required",
+                "GET /org/{orgId}: parameter #2 has no name",
+                "POST /org/{orgId}: operationId 'getOrg' is already used by GET /org/{orgId}",
+                "POST /org/{orgId}: parameter 'verbose' has no 'in' location",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_param_mismatches_are_reported_both_ways() {
+        let spec_json = r#"{
+            "openapi": "3.0.0",
+            "info": {"title": "Test", "version": "1.0.0"},
+            "paths": {
+                "/org/{orgId}": {
+                    "get": {
+                        "parameters": [
+                            {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                            {"name": "siteId", "in": "path", "required": true, "schema": {"type": "integer"}}
+                        ],
+                        "responses": {}
+                    }
+                },
+                "/site/{siteId}/resource/{resourceId}": {
+                    "get": {
+                        "parameters": [
+                            {"name": "siteId", "in": "path", "required": true, "schema": {"type": "integer"}}
+                        ],
+                        "responses": {}
+                    }
+                }
+            }
+        }"#;
+        let spec = SwaggerSpec::from_json(spec_json).unwrap();
+        assert_eq!(
+            spec.validate(),
+            vec![
+                "GET /org/{orgId}: path parameter 'siteId' has no {siteId} placeholder",
+                "GET /site/{siteId}/resource/{resourceId}: placeholder {resourceId} has no declared path parameter",
+            ]
+        );
+        // The mismatched endpoints are still extracted
+        assert_eq!(spec.extract_endpoints().len(), 2);
+
+        assert!(SwaggerSpec::from_json(
+            r#"{"openapi": "3.0.0", "info": {"title": "T", "version": "1"}, "paths": {}}"#
+        )
+        .unwrap()
+        .validate()
+        .is_empty());
+    }
+
     #[test]
     fn test_build_url() {
         let mut params = HashMap::new();
@@ -495,4 +1990,52 @@ mod tests {
             "https://api.pangolin.example.com/v1/org/org123/site/site456"
         );
     }
+
+    #[test]
+    fn test_build_url_normalizes_slashes() {
+        let params = HashMap::new();
+
+        // Empty path yields the base URL without a trailing slash
+        assert_eq!(
+            build_url("https://pangolin.example.com/v1/", "", &params),
+            "https://pangolin.example.com/v1"
+        );
+        // Leading double slash in the path
+        assert_eq!(
+            build_url("https://pangolin.example.com/v1", "//orgs", &params),
+            "https://pangolin.example.com/v1/orgs"
+        );
+        // Multiple trailing slashes on the base URL
+        assert_eq!(
+            build_url("https://pangolin.example.com/v1///", "/orgs", &params),
+            "https://pangolin.example.com/v1/orgs"
+        );
+
+        // Empty templated segments don't leave a double slash
+        let mut params = HashMap::new();
+        params.insert("orgId".to_string(), String::new());
+        assert_eq!(
+            build_url("http://localhost:3000", "/org/{orgId}/sites", &params),
+            "http://localhost:3000/org/sites"
+        );
+    }
+
+    #[test]
+    fn test_build_url_with_base_path() {
+        let mut params = HashMap::new();
+        params.insert("orgId".to_string(), "org123".to_string());
+
+        for base_path in ["/integration", "integration/", "/integration/"] {
+            let path = join_base_path(base_path, "/org/{orgId}");
+            assert_eq!(path, "/integration/org/{orgId}");
+            assert_eq!(
+                build_url("https://pangolin.example.com/", &path, &params),
+                "https://pangolin.example.com/integration/org/org123"
+            );
+        }
+
+        // No base path leaves the endpoint path untouched
+        assert_eq!(join_base_path("", "/org/{orgId}"), "/org/{orgId}");
+        assert_eq!(join_base_path("/", "/org/{orgId}"), "/org/{orgId}");
+    }
 }
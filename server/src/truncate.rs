@@ -0,0 +1,169 @@
+//! Shrinking oversized API responses to fit a byte budget
+//!
+//! Responses are cut at JSON boundaries so the result stays valid JSON:
+//! trailing array elements go first (largest array first), and only once
+//! every array is empty are long strings shortened.
+
+use serde_json::Value;
+
+/// Marker appended to shortened strings
+const ELLIPSIS: &str = "…";
+
+/// An array that lost trailing elements
+#[derive(Debug, Clone, PartialEq)]
+pub struct TruncatedArray {
+    /// Location in the response, e.g. `$.data.resources`
+    pub path: String,
+    pub shown: usize,
+    pub total: usize,
+}
+
+/// What was removed to fit the budget
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Truncation {
+    pub arrays: Vec<TruncatedArray>,
+    /// Number of strings shortened
+    pub strings: usize,
+    /// Size of the pretty-printed response before truncation
+    pub original_bytes: usize,
+}
+
+/// Shrink `value` until its pretty-printed form fits in `max_bytes`; returns
+/// what was removed, or None when it already fit
+pub fn truncate_json(value: &mut Value, max_bytes: usize) -> Option<Truncation> {
+    let original_bytes = pretty_len(value);
+    if original_bytes <= max_bytes {
+        return None;
+    }
+
+    let mut truncation = Truncation {
+        original_bytes,
+        ..Default::default()
+    };
+
+    // Largest arrays first, keeping as many leading elements as fit
+    let mut arrays = Vec::new();
+    collect_arrays(value, "$", &mut arrays);
+    arrays.sort_by_key(|(_, size)| std::cmp::Reverse(*size));
+    for (path, _) in arrays {
+        let total = array_at(value, &path).map_or(0, |a| a.len());
+        if total == 0 {
+            continue;
+        }
+        let shown = largest_fitting(value, &path, total, max_bytes);
+        if shown < total {
+            truncation
+                .arrays
+                .push(TruncatedArray { path, shown, total });
+        }
+        if pretty_len(value) <= max_bytes {
+            return Some(truncation);
+        }
+    }
+
+    // Then the longest strings, cutting each just enough to fit
+    while pretty_len(value) > max_bytes {
+        let excess = pretty_len(value) - max_bytes;
+        let Some(longest) = longest_string(value) else {
+            break;
+        };
+        let shortened_before = longest.ends_with(ELLIPSIS);
+        let text = longest.strip_suffix(ELLIPSIS).unwrap_or(longest);
+        let chars = text.chars().count();
+        if chars == 0 {
+            break;
+        }
+        // Every dropped character saves at least one byte
+        let keep = chars.saturating_sub(excess + ELLIPSIS.len());
+        *longest = text.chars().take(keep).collect::<String>() + ELLIPSIS;
+        if !shortened_before {
+            truncation.strings += 1;
+        }
+    }
+    Some(truncation)
+}
+
+fn pretty_len(value: &Value) -> usize {
+    serde_json::to_string_pretty(value).map_or(0, |s| s.len())
+}
+
+/// Paths of every array, with their serialized size
+fn collect_arrays(value: &Value, path: &str, out: &mut Vec<(String, usize)>) {
+    match value {
+        Value::Array(items) => {
+            out.push((path.to_string(), pretty_len(value)));
+            for (i, item) in items.iter().enumerate() {
+                collect_arrays(item, &format!("{}[{}]", path, i), out);
+            }
+        }
+        Value::Object(object) => {
+            for (key, child) in object {
+                collect_arrays(child, &format!("{}.{}", path, key), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Array at a path produced by `collect_arrays`
+fn array_at<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Vec<Value>> {
+    let mut current = value;
+    let mut rest = path.strip_prefix('$')?;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']')?;
+            let index: usize = after[..end].parse().ok()?;
+            current = current.as_array_mut()?.get_mut(index)?;
+            rest = &after[end + 1..];
+        } else {
+            let after = rest.strip_prefix('.')?;
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            current = current.as_object_mut()?.get_mut(&after[..end])?;
+            rest = &after[end..];
+        }
+    }
+    current.as_array_mut()
+}
+
+/// Truncate the array at `path` to the most leading elements that fit
+/// (binary search), returning how many were kept
+fn largest_fitting(value: &mut Value, path: &str, total: usize, max_bytes: usize) -> usize {
+    let Some(items) = array_at(value, path) else {
+        return total;
+    };
+    let all = std::mem::take(items);
+
+    let fits = |value: &mut Value, keep: usize| {
+        if let Some(items) = array_at(value, path) {
+            *items = all[..keep].to_vec();
+        }
+        pretty_len(value) <= max_bytes
+    };
+
+    let (mut low, mut high) = (0, total);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if fits(value, mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    fits(value, low);
+    low
+}
+
+fn longest_string(value: &mut Value) -> Option<&mut String> {
+    match value {
+        Value::String(s) => Some(s),
+        Value::Array(items) => items
+            .iter_mut()
+            .filter_map(longest_string)
+            .max_by_key(|s| s.len()),
+        Value::Object(object) => object
+            .values_mut()
+            .filter_map(longest_string)
+            .max_by_key(|s| s.len()),
+        _ => None,
+    }
+}
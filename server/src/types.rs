@@ -1,5 +1,5 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 /// HTTP method type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -13,6 +13,23 @@ pub enum HttpMethod {
 }
 
 impl HttpMethod {
+    /// Every supported method, in listing order
+    pub const ALL: [HttpMethod; 5] = [
+        HttpMethod::Get,
+        HttpMethod::Post,
+        HttpMethod::Put,
+        HttpMethod::Delete,
+        HttpMethod::Patch,
+    ];
+
+    /// Parse a method name, case-insensitively
+    #[allow(dead_code)]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|m| m.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
     /// Returns true if this method is considered a write operation
     pub fn is_write_operation(&self) -> bool {
         matches!(
@@ -51,6 +68,33 @@ pub struct PangolinEndpoint {
     pub query_params: Vec<EndpointParameter>,
     /// Request body schema (if any)
     pub request_body: Option<RequestBodySchema>,
+    /// Documented responses, in spec order
+    pub responses: Vec<EndpointResponse>,
+}
+
+/// A documented response of an endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointResponse {
+    /// Status code (e.g. "200") or "default"
+    pub status: String,
+    pub description: Option<String>,
+    /// Schema of the JSON response body, as declared in the spec
+    pub schema: Option<serde_json::Value>,
+}
+
+/// Documentation for an outbound webhook declared in the spec's `webhooks` section
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDoc {
+    /// Webhook name (key in the `webhooks` map)
+    pub name: String,
+    /// HTTP method Pangolin uses to deliver the webhook
+    pub method: HttpMethod,
+    /// Description of the webhook
+    pub description: String,
+    /// Tags for categorization
+    pub tags: Vec<String>,
+    /// Raw JSON schema of the delivered payload (if any)
+    pub payload_schema: Option<serde_json::Value>,
 }
 
 /// Represents a parameter for an endpoint
@@ -60,7 +104,12 @@ pub struct EndpointParameter {
     pub param_type: ParameterType,
     pub required: bool,
     pub description: Option<String>,
+    /// Format hint (e.g. "date-time", "uuid")
+    pub format: Option<String>,
     pub default_value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub enum_values: Option<Vec<String>>,
+    pub example: Option<serde_json::Value>,
 }
 
 /// Possible parameter types
@@ -98,11 +147,70 @@ impl ParameterType {
     }
 }
 
+/// Argument carrying the whole body of endpoints accepting an arbitrary object
+pub const FREE_FORM_BODY: &str = "body";
+
+/// Argument carrying the elements of endpoints whose body is a JSON array
+pub const ARRAY_BODY: &str = "items";
+
 /// Request body schema
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestBodySchema {
+    /// Content type sent unless the call picks another
     pub content_type: String,
-    pub properties: HashMap<String, PropertySchema>,
+    /// Every content type the operation accepts, in declaration order
+    #[serde(default)]
+    pub content_types: Vec<String>,
+    /// Body properties, in spec declaration order
+    pub properties: IndexMap<String, PropertySchema>,
+    pub required: Vec<String>,
+    /// The spec marks the body itself required (`requestBody.required`), so
+    /// it is sent even when no field is set
+    #[serde(default)]
+    pub body_required: bool,
+    /// readOnly properties left out of the input schema; stripped if sent anyway
+    #[serde(default)]
+    pub read_only_properties: Vec<String>,
+    /// Body is an arbitrary JSON object passed as the [`FREE_FORM_BODY`] argument
+    #[serde(default)]
+    pub free_form: bool,
+    /// Body is a JSON array passed as the [`ARRAY_BODY`] argument
+    #[serde(default)]
+    pub array_body: bool,
+    /// `additionalProperties` of the body schema; `Some(false)` rejects undeclared fields
+    #[serde(default)]
+    pub additional_properties: Option<bool>,
+    /// Property whose value selects one of `variants`
+    #[serde(default)]
+    pub discriminator: Option<String>,
+    /// Whether the body must match exactly one or at least one variant
+    #[serde(default)]
+    pub variant_kind: VariantKind,
+    /// `oneOf`/`anyOf` branches, kept apart rather than merged so a body can
+    /// be checked against the branch its discriminator selects
+    #[serde(default)]
+    pub variants: Vec<BodyVariant>,
+    /// Body fields exposed under another argument name because a path or
+    /// query parameter already uses theirs (argument -> body field)
+    #[serde(default)]
+    pub renamed: IndexMap<String, String>,
+}
+
+/// How the variants of a request body combine
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum VariantKind {
+    #[default]
+    OneOf,
+    AnyOf,
+}
+
+/// One branch of a `oneOf`/`anyOf` request body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BodyVariant {
+    /// Discriminator values selecting this branch (empty without a discriminator)
+    pub discriminator_values: Vec<String>,
+    /// Branch properties, in spec declaration order
+    pub properties: IndexMap<String, PropertySchema>,
     pub required: Vec<String>,
 }
 
@@ -112,13 +220,23 @@ pub struct PropertySchema {
     pub name: String,
     pub param_type: ParameterType,
     pub description: Option<String>,
+    /// Format hint (e.g. "date-time", "uuid", "email")
+    pub format: Option<String>,
     pub default_value: Option<serde_json::Value>,
     pub enum_values: Option<Vec<String>>,
     pub nullable: bool,
     pub min_length: Option<i64>,
     pub max_length: Option<i64>,
-    pub minimum: Option<f64>,
-    pub maximum: Option<f64>,
+    /// Bounds kept as written in the spec, so integer bounds stay exact
+    pub minimum: Option<serde_json::Number>,
+    pub maximum: Option<serde_json::Number>,
     pub pattern: Option<String>,
+    pub multiple_of: Option<f64>,
+    pub min_items: Option<u64>,
+    pub max_items: Option<u64>,
+    pub unique_items: bool,
+    /// Single allowed value (`const`)
+    pub const_value: Option<serde_json::Value>,
+    pub example: Option<serde_json::Value>,
     pub items: Option<Box<PropertySchema>>,
 }
@@ -0,0 +1,177 @@
+//! Composite tools defined in a `--workflows` file
+//!
+//! A workflow runs an ordered list of generated tools as one MCP tool. Step
+//! arguments are templates: any string may embed `{{inputs.<name>}}` or
+//! `{{steps.<id>.response.<field>...}}`, resolved against the workflow
+//! arguments and the responses of earlier steps. A string made of a single
+//! placeholder takes the referenced value as is (numbers stay numbers).
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Contents of a `--workflows` file
+#[derive(Debug, Deserialize)]
+struct WorkflowFile {
+    workflows: Vec<Workflow>,
+}
+
+/// A named composite tool
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// JSON Schema of the workflow arguments
+    #[serde(default = "empty_object_schema")]
+    pub input_schema: Value,
+    pub steps: Vec<WorkflowStep>,
+}
+
+/// One call of a workflow
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowStep {
+    /// Name under which later steps reference this step's response
+    pub id: String,
+    /// Generated tool name, without --tool-prefix
+    pub tool: String,
+    /// Argument templates
+    #[serde(default)]
+    pub arguments: serde_json::Map<String, Value>,
+}
+
+fn empty_object_schema() -> Value {
+    serde_json::json!({"type": "object", "properties": {}})
+}
+
+/// Read and check the workflows of a JSON file
+#[allow(dead_code)]
+pub fn load(path: &Path) -> Result<Vec<Workflow>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read workflows file {}", path.display()))?;
+    parse(&content).with_context(|| format!("Invalid workflows file {}", path.display()))
+}
+
+/// Parse and check workflow definitions
+pub fn parse(content: &str) -> Result<Vec<Workflow>> {
+    let file: WorkflowFile = serde_json::from_str(content)?;
+    let mut names = HashSet::new();
+    for workflow in &file.workflows {
+        if !names.insert(workflow.name.as_str()) {
+            bail!("duplicate workflow '{}'", workflow.name);
+        }
+        check(workflow).with_context(|| format!("workflow '{}'", workflow.name))?;
+    }
+    Ok(file.workflows)
+}
+
+/// Steps exist, have unique ids and only reference earlier steps
+fn check(workflow: &Workflow) -> Result<()> {
+    if !workflow.input_schema.is_object() {
+        bail!("inputSchema must be an object");
+    }
+    if workflow.steps.is_empty() {
+        bail!("no steps");
+    }
+    let mut earlier = HashSet::new();
+    for step in &workflow.steps {
+        for reference in placeholders(&Value::Object(step.arguments.clone())) {
+            let mut parts = reference.split('.');
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some("inputs"), Some(_), _) => {}
+                (Some("steps"), Some(id), Some("response")) if earlier.contains(id) => {}
+                (Some("steps"), Some(id), Some("response")) => {
+                    bail!("step '{}' references '{}' before it has run", step.id, id)
+                }
+                _ => bail!(
+                    "step '{}': '{{{{{}}}}}' must start with inputs.<name> or steps.<id>.response",
+                    step.id,
+                    reference
+                ),
+            }
+        }
+        if !earlier.insert(step.id.as_str()) {
+            bail!("duplicate step id '{}'", step.id);
+        }
+    }
+    Ok(())
+}
+
+/// Every `{{...}}` reference in a template, trimmed
+fn placeholders(template: &Value) -> Vec<String> {
+    match template {
+        Value::String(text) => {
+            let mut found = Vec::new();
+            let mut rest = text.as_str();
+            while let Some((_, after)) = rest.split_once("{{") {
+                let Some((reference, tail)) = after.split_once("}}") else {
+                    break;
+                };
+                found.push(reference.trim().to_string());
+                rest = tail;
+            }
+            found
+        }
+        Value::Array(items) => items.iter().flat_map(placeholders).collect(),
+        Value::Object(object) => object.values().flat_map(placeholders).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Value at a dotted path of `context` (numeric segments index arrays)
+fn lookup<'a>(context: &'a Value, reference: &str) -> Option<&'a Value> {
+    reference
+        .split('.')
+        .try_fold(context, |value, segment| match value {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => value.get(segment),
+        })
+}
+
+/// Fill the placeholders of `template` from `context`
+/// (`{"inputs": ..., "steps": {"<id>": {"response": ...}}}`)
+pub fn render(template: &Value, context: &Value) -> Result<Value> {
+    match template {
+        Value::String(text) => {
+            let resolve = |reference: &str| {
+                lookup(context, reference)
+                    .filter(|value| !value.is_null())
+                    .with_context(|| format!("'{{{{{}}}}}' has no value", reference))
+            };
+            let trimmed = text.trim();
+            if let Some(reference) = trimmed
+                .strip_prefix("{{")
+                .and_then(|t| t.strip_suffix("}}"))
+                .filter(|reference| !reference.contains("{{"))
+            {
+                return resolve(reference.trim()).cloned();
+            }
+
+            let mut rendered = String::new();
+            let mut rest = text.as_str();
+            while let Some((before, after)) = rest.split_once("{{") {
+                let Some((reference, tail)) = after.split_once("}}") else {
+                    break;
+                };
+                rendered.push_str(before);
+                match resolve(reference.trim())? {
+                    Value::String(s) => rendered.push_str(s),
+                    other => rendered.push_str(&other.to_string()),
+                }
+                rest = tail;
+            }
+            rendered.push_str(rest);
+            Ok(Value::String(rendered))
+        }
+        Value::Array(items) => items.iter().map(|item| render(item, context)).collect(),
+        Value::Object(object) => object
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), render(value, context)?)))
+            .collect::<Result<serde_json::Map<_, _>>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
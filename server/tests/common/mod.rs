@@ -0,0 +1,169 @@
+//! Shared helpers for end-to-end MCP handler tests
+//!
+//! Test crates using these helpers must declare the server modules
+//! (`service`, `swagger`, `types`, ...) at their crate root via `#[path]`.
+
+#![allow(dead_code)]
+
+use rmcp::service::{RoleClient, RunningService};
+use rmcp::ClientHandler;
+use rmcp::ServiceExt;
+
+use crate::service::{PangolinService, ServiceConfig};
+use crate::swagger::SwaggerSpec;
+
+/// Sample OpenAPI spec used by the handler tests
+pub fn get_test_swagger_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {
+            "title": "Test Pangolin API",
+            "version": "v1"
+        },
+        "paths": {
+            "/orgs": {
+                "get": {
+                    "description": "List all organizations",
+                    "tags": ["Organization"],
+                    "parameters": [
+                        {
+                            "name": "limit",
+                            "in": "query",
+                            "schema": {"type": "integer"}
+                        }
+                    ],
+                    "responses": {}
+                }
+            },
+            "/org/{orgId}": {
+                "get": {
+                    "description": "Get an organization",
+                    "tags": ["Organization"],
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "schema": {"type": "string"}
+                        }
+                    ],
+                    "responses": {}
+                },
+                "post": {
+                    "description": "Update an organization",
+                    "tags": ["Organization"],
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "schema": {"type": "string"}
+                        }
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"}
+                                    },
+                                    "required": ["name"]
+                                }
+                            }
+                        }
+                    },
+                    "responses": {}
+                },
+                "delete": {
+                    "description": "Delete an organization",
+                    "tags": ["Organization"],
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "schema": {"type": "string"}
+                        }
+                    ],
+                    "responses": {}
+                }
+            },
+            "/site/{siteId}": {
+                "get": {
+                    "description": "Get a site",
+                    "tags": ["Site"],
+                    "parameters": [
+                        {
+                            "name": "siteId",
+                            "in": "path",
+                            "required": true,
+                            "schema": {"type": "integer"}
+                        }
+                    ],
+                    "responses": {}
+                }
+            }
+        }
+    }"#
+}
+
+/// Build a service from a spec, pointed at the given upstream base URL
+pub fn build_service(spec_json: &str, base_url: &str, config: ServiceConfig) -> PangolinService {
+    let spec = SwaggerSpec::from_json(spec_json).expect("Failed to parse test swagger spec");
+    PangolinService::new(
+        spec,
+        "test-api-key".to_string(),
+        base_url.to_string(),
+        config,
+    )
+    .expect("Failed to create service")
+}
+
+/// Serve the service over an in-memory transport and connect a client to it
+pub async fn connect(service: PangolinService) -> RunningService<RoleClient, ()> {
+    connect_with(service, ()).await
+}
+
+/// Like `connect`, with a custom client handler (e.g. to observe notifications)
+pub async fn connect_with<C: ClientHandler>(
+    service: PangolinService,
+    handler: C,
+) -> RunningService<RoleClient, C> {
+    let (server_transport, client_transport) = tokio::io::duplex(64 * 1024);
+
+    tokio::spawn(async move {
+        let server = service
+            .serve(server_transport)
+            .await
+            .expect("Failed to start MCP server");
+        let _ = server.waiting().await;
+    });
+
+    handler
+        .serve(client_transport)
+        .await
+        .expect("Failed to connect MCP client")
+}
+
+/// List the tools exposed for a spec and return the one with the given name
+pub async fn get_tool(spec_json: &str, config: ServiceConfig, name: &str) -> rmcp::model::Tool {
+    let client = connect(build_service(spec_json, "http://localhost:1", config)).await;
+    let tools = client.list_all_tools().await.expect("Failed to list tools");
+    client.cancel().await.expect("Failed to stop client");
+
+    tools
+        .into_iter()
+        .find(|t| t.name == name)
+        .unwrap_or_else(|| panic!("Tool {} should be listed", name))
+}
+
+/// Concatenate the text contents of a tool result
+pub fn result_text(result: &rmcp::model::CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
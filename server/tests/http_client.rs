@@ -0,0 +1,984 @@
+//! Tests for `PangolinClient` against a mock Pangolin API
+//!
+//! Run with: cargo test --test http_client
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+// Supporting modules, only partially exercised here
+#[allow(dead_code)]
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[allow(dead_code)]
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[allow(dead_code)]
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[allow(dead_code)]
+#[path = "../src/types.rs"]
+mod types;
+
+use pangolin_client::{ClientConfig, PangolinApiError, PangolinClient};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use types::HttpMethod;
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Shared in-memory sink for captured log output
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl LogBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl std::io::Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for LogBuffer {
+    type Writer = LogBuffer;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+#[tokio::test]
+async fn test_trace_http_logs_bodies_and_redacts_api_key() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "my-org"})))
+        .mount(&upstream)
+        .await;
+
+    let logs = LogBuffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(logs.clone())
+        .with_ansi(false)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "super-secret-key".to_string(),
+        ClientConfig {
+            trace_http: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let mut path_params = HashMap::new();
+    path_params.insert("orgId".to_string(), "my-org".to_string());
+    client
+        .call(
+            HttpMethod::Post,
+            "/org/{orgId}",
+            path_params,
+            HashMap::new(),
+            Some(json!({"name": "Renamed"})),
+            "application/json",
+        )
+        .await
+        .unwrap();
+
+    let output = logs.contents();
+    assert!(output.contains("HTTP request: POST"), "{}", output);
+    assert!(output.contains("/org/my-org"));
+    assert!(
+        output.contains(r#"{"name":"Renamed"}"#),
+        "request body should be logged"
+    );
+    assert!(
+        output.contains(r#"{"orgId":"my-org"}"#),
+        "response body should be logged"
+    );
+    assert!(output.contains("authorization: [REDACTED]"), "{}", output);
+    assert!(
+        !output.contains("super-secret-key"),
+        "API key must never be logged: {}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn test_user_agent_defaults_to_crate_version() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .and(header(
+            "User-Agent",
+            concat!("mcp-pangolin/", env!("CARGO_PKG_VERSION")),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        ClientConfig::default(),
+    )
+    .unwrap();
+    client
+        .call(
+            HttpMethod::Get,
+            "/orgs",
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_user_agent_can_be_overridden() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .and(header("User-Agent", "acme-agents/2.1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        ClientConfig {
+            user_agent: "acme-agents/2.1".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    client
+        .call(
+            HttpMethod::Get,
+            "/orgs",
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap();
+}
+
+async fn get_orgs(client: &PangolinClient) -> anyhow::Result<serde_json::Value> {
+    client
+        .call(
+            HttpMethod::Get,
+            "/orgs",
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .map(|response| response.body)
+}
+
+fn breaker_config() -> ClientConfig {
+    ClientConfig {
+        breaker_threshold: 2,
+        breaker_cooldown: Duration::from_millis(200),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_fails_fast_then_recovers() {
+    let upstream = MockServer::start().await;
+    let outage = Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(2)
+        .mount_as_scoped(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        breaker_config(),
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        let err = get_orgs(&client).await.unwrap_err();
+        assert!(err.to_string().contains("503"), "{}", err);
+    }
+
+    // Open: the upstream is not contacted (the mock expects exactly two calls)
+    let err = get_orgs(&client).await.unwrap_err();
+    assert!(
+        err.to_string().contains("Pangolin API unavailable"),
+        "{}",
+        err
+    );
+    drop(outage);
+
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&upstream)
+        .await;
+
+    // Half-open after the cooldown: the trial call succeeds and closes the breaker
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(get_orgs(&client).await.unwrap(), json!([]));
+    assert_eq!(get_orgs(&client).await.unwrap(), json!([]));
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_reopens_when_trial_fails() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        breaker_config(),
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        get_orgs(&client).await.unwrap_err();
+    }
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let err = get_orgs(&client).await.unwrap_err();
+    assert!(err.to_string().contains("500"), "{}", err);
+
+    let err = get_orgs(&client).await.unwrap_err();
+    assert!(
+        err.to_string().contains("Pangolin API unavailable"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_cancelled_trial_does_not_keep_the_breaker_open() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(2)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([]))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .up_to_n_times(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        breaker_config(),
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        get_orgs(&client).await.unwrap_err();
+    }
+
+    // The half-open trial is dropped before it completes...
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let cancelled = tokio::time::timeout(Duration::from_millis(100), get_orgs(&client)).await;
+    assert!(cancelled.is_err());
+
+    // ...so the next call becomes the trial instead of failing fast forever
+    assert_eq!(get_orgs(&client).await.unwrap(), json!([]));
+    assert_eq!(get_orgs(&client).await.unwrap(), json!([]));
+}
+
+#[tokio::test]
+async fn test_failing_key_command_does_not_keep_the_breaker_open() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(2)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&upstream)
+        .await;
+
+    // Fails on its third run only, which is the half-open trial
+    let counter =
+        std::env::temp_dir().join(format!("mcp-pangolin-breaker-key-{}", std::process::id()));
+    let _ = std::fs::remove_file(&counter);
+    let command = format!(
+        "echo x >> '{0}'; if [ $(wc -l < '{0}') -eq 3 ]; then exit 1; fi; echo key",
+        counter.display()
+    );
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        String::new(),
+        ClientConfig {
+            api_key_command: Some(command),
+            api_key_ttl: Duration::ZERO,
+            ..breaker_config()
+        },
+    )
+    .unwrap();
+
+    for _ in 0..2 {
+        get_orgs(&client).await.unwrap_err();
+    }
+
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let err = get_orgs(&client).await.unwrap_err();
+    assert!(
+        err.to_string().contains("--api-key-command failed"),
+        "{}",
+        err
+    );
+
+    // The trial never reached the API, so another call may try
+    assert_eq!(get_orgs(&client).await.unwrap(), json!([]));
+
+    let _ = std::fs::remove_file(&counter);
+}
+
+#[tokio::test]
+async fn test_client_errors_do_not_trip_the_breaker() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(4)
+        .mount(&upstream)
+        .await;
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        "test-api-key".to_string(),
+        breaker_config(),
+    )
+    .unwrap();
+
+    for _ in 0..4 {
+        let err = get_orgs(&client).await.unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+}
+
+/// Self-signed certificate generated for these tests
+fn test_ca_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/test-ca.pem")
+}
+
+#[test]
+fn test_client_trusts_custom_ca_certificate() {
+    let config = ClientConfig {
+        ca_cert: Some(test_ca_path()),
+        ..Default::default()
+    };
+    assert!(PangolinClient::new("https://pangolin.internal", "key".to_string(), config).is_ok());
+}
+
+#[test]
+fn test_unreadable_ca_certificate_is_rejected() {
+    let config = ClientConfig {
+        ca_cert: Some(test_ca_path().with_file_name("missing.pem")),
+        ..Default::default()
+    };
+    let err =
+        PangolinClient::new("https://pangolin.internal", "key".to_string(), config).unwrap_err();
+    assert!(
+        err.to_string().contains("Failed to read CA certificate"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_client_builds_with_verification_disabled() {
+    let config = ClientConfig {
+        accept_invalid_certs: true,
+        ..Default::default()
+    };
+    assert!(PangolinClient::new("https://pangolin.internal", "key".to_string(), config).is_ok());
+}
+
+#[tokio::test]
+async fn test_spec_fetch_uses_the_client_options() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/openapi.json"))
+        .and(header("User-Agent", "acme-agents/2.1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Pangolin Integration API", "version": "v1"},
+            "paths": {}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = ClientConfig {
+        user_agent: "acme-agents/2.1".to_string(),
+        ca_cert: Some(test_ca_path()),
+        ..Default::default()
+    };
+    let url = format!("{}/openapi.json", server.uri());
+    let client = pangolin_client::build_http_client(&config, &url).unwrap();
+    let spec = swagger::SwaggerSpec::from_url(&client, &url, None)
+        .await
+        .unwrap();
+    assert_eq!(spec.info.title, "Pangolin Integration API");
+
+    let config = ClientConfig {
+        ca_cert: Some(test_ca_path().with_file_name("missing.pem")),
+        ..Default::default()
+    };
+    let err = pangolin_client::build_http_client(&config, &url).unwrap_err();
+    assert!(
+        err.to_string().contains("Failed to read CA certificate"),
+        "{}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_requests_go_through_configured_proxy() {
+    // The mock server plays the proxy: it receives the request for the
+    // unresolvable upstream host
+    let proxy = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .and(header("host", "pangolin.invalid"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgs": []})))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let config = ClientConfig {
+        proxy: Some(proxy.uri()),
+        ..Default::default()
+    };
+    let client = PangolinClient::new(
+        "http://pangolin.invalid",
+        "test-api-key".to_string(),
+        config,
+    )
+    .unwrap();
+
+    let result = get_orgs(&client).await.unwrap();
+    assert_eq!(result, json!({"orgs": []}));
+}
+
+#[tokio::test]
+async fn test_spec_fetch_goes_through_configured_proxy() {
+    let proxy = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/openapi.json"))
+        .and(header("host", "pangolin.invalid"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "openapi": "3.0.0",
+            "info": {"title": "Pangolin Integration API", "version": "v1"},
+            "paths": {}
+        })))
+        .expect(1)
+        .mount(&proxy)
+        .await;
+
+    let config = ClientConfig {
+        proxy: Some(proxy.uri()),
+        ..Default::default()
+    };
+    let url = "http://pangolin.invalid/openapi.json";
+    let client = pangolin_client::build_http_client(&config, url).unwrap();
+    let spec = swagger::SwaggerSpec::from_url(&client, url, None)
+        .await
+        .unwrap();
+    assert_eq!(spec.info.title, "Pangolin Integration API");
+}
+
+#[test]
+fn test_invalid_proxy_url_is_rejected() {
+    let config = ClientConfig {
+        proxy: Some("not a url".to_string()),
+        ..Default::default()
+    };
+    let err =
+        PangolinClient::new("https://pangolin.internal", "key".to_string(), config).unwrap_err();
+    assert!(err.to_string().contains("Invalid proxy URL"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_rejected_key_is_refreshed_with_command_and_retried() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .and(header("Authorization", "Bearer old-key"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({"message": "expired"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .and(header("Authorization", "Bearer new-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgs": []})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    // Prints old-key on the first run and new-key afterwards
+    let marker = std::env::temp_dir().join(format!("mcp-pangolin-key-{}", std::process::id()));
+    let _ = std::fs::remove_file(&marker);
+    let command = format!(
+        "if [ -f '{0}' ]; then echo new-key; else touch '{0}'; echo old-key; fi",
+        marker.display()
+    );
+
+    let client = PangolinClient::new(
+        &upstream.uri(),
+        String::new(),
+        ClientConfig {
+            api_key_command: Some(command),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let result = client
+        .call(
+            HttpMethod::Get,
+            "/orgs",
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap();
+    assert_eq!(result.body, json!({"orgs": []}));
+    assert_eq!(result.attempts, 2);
+
+    let _ = std::fs::remove_file(&marker);
+}
+
+#[tokio::test]
+async fn test_failing_api_key_command_is_reported() {
+    let client = PangolinClient::new(
+        "http://localhost:1",
+        String::new(),
+        ClientConfig {
+            api_key_command: Some("echo vault sealed >&2; exit 3".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let err = client
+        .call(
+            HttpMethod::Get,
+            "/orgs",
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--api-key-command failed (exit status: 3): vault sealed"
+    );
+}
+
+#[tokio::test]
+async fn test_key_command_can_run_without_a_client() {
+    // --openapi-url fetches the spec with this key before any client exists
+    assert_eq!(
+        pangolin_client::run_key_command("echo '  spec-key  '")
+            .await
+            .unwrap(),
+        "spec-key"
+    );
+    let err = pangolin_client::run_key_command("true").await.unwrap_err();
+    assert_eq!(err.to_string(), "--api-key-command printed no API key");
+}
+
+async fn respond_with(response: ResponseTemplate, include_status: bool) -> serde_json::Value {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(response)
+        .mount(&server)
+        .await;
+    let client = PangolinClient::new(
+        &server.uri(),
+        "key".to_string(),
+        ClientConfig {
+            include_status,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    get_orgs(&client).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_status_is_attached_when_requested() {
+    let accepted = ResponseTemplate::new(202).set_body_json(json!({"taskId": "t-1"}));
+    assert_eq!(
+        respond_with(accepted.clone(), true).await,
+        json!({"_status": 202, "data": {"taskId": "t-1"}})
+    );
+    let no_content = json!({
+        "status": "success",
+        "httpStatus": 204,
+        "method": "GET",
+        "path": "/orgs",
+        "message": "Fetched orgs (HTTP 204)",
+    });
+    assert_eq!(
+        respond_with(ResponseTemplate::new(204), true).await,
+        json!({"_status": 204, "data": no_content})
+    );
+
+    // Off by default: the body is returned as is
+    assert_eq!(
+        respond_with(accepted, false).await,
+        json!({"taskId": "t-1"})
+    );
+    assert_eq!(
+        respond_with(ResponseTemplate::new(204), false).await,
+        no_content
+    );
+}
+
+#[tokio::test]
+async fn test_empty_success_says_what_happened() {
+    let server = MockServer::start().await;
+    for (verb, status) in [("POST", 200), ("PUT", 201), ("DELETE", 204)] {
+        Mock::given(method(verb))
+            .respond_with(ResponseTemplate::new(status))
+            .mount(&server)
+            .await;
+    }
+
+    let client = test_client(&server);
+    for (verb, path, status, message) in [
+        (
+            HttpMethod::Post,
+            "/site/{siteId}",
+            200,
+            "Updated site 42 (HTTP 200)",
+        ),
+        (
+            HttpMethod::Put,
+            "/org/{orgId}/site",
+            201,
+            "Created site (HTTP 201)",
+        ),
+        (
+            HttpMethod::Delete,
+            "/site/{siteId}",
+            204,
+            "Deleted site 42 (HTTP 204)",
+        ),
+    ] {
+        let response = client
+            .call(
+                verb,
+                path,
+                HashMap::from([
+                    ("siteId".to_string(), "42".to_string()),
+                    ("orgId".to_string(), "acme".to_string()),
+                ]),
+                HashMap::new(),
+                None,
+                "application/json",
+            )
+            .await
+            .unwrap();
+        let resolved = path.replace("{siteId}", "42").replace("{orgId}", "acme");
+        assert_eq!(response.status, status);
+        assert_eq!(response.method, verb);
+        assert_eq!(response.path, resolved);
+        assert_eq!(
+            response.body,
+            json!({
+                "status": "success",
+                "httpStatus": status,
+                "method": verb.as_str(),
+                "path": resolved,
+                "message": message,
+            })
+        );
+    }
+}
+
+fn test_client(server: &MockServer) -> PangolinClient {
+    PangolinClient::new(
+        &server.uri(),
+        "test-api-key".to_string(),
+        ClientConfig::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_get_sends_auth_header_and_query() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme/sites"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .and(query_param("limit", "10"))
+        .and(query_param("name", "main site"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"sites": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = test_client(&server)
+        .call(
+            HttpMethod::Get,
+            "/org/{orgId}/sites",
+            HashMap::from([("orgId".to_string(), "acme".to_string())]),
+            HashMap::from([
+                ("limit".to_string(), "10".to_string()),
+                ("name".to_string(), "main site".to_string()),
+            ]),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.body, json!({"sites": []}));
+}
+
+#[tokio::test]
+async fn test_post_sends_json_body() {
+    let server = MockServer::start().await;
+    let body = json!({"name": "Acme", "subnet": "100.90.128.0/24", "tags": ["a", "b"]});
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .and(header("Content-Type", "application/json"))
+        .and(body_json(&body))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"orgId": "acme"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = test_client(&server)
+        .call(
+            HttpMethod::Post,
+            "/org/{orgId}",
+            HashMap::from([("orgId".to_string(), "acme".to_string())]),
+            HashMap::new(),
+            Some(body),
+            "application/json",
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.body, json!({"orgId": "acme"}));
+}
+
+#[tokio::test]
+async fn test_each_method_reaches_its_route() {
+    let server = MockServer::start().await;
+    for verb in HttpMethod::ALL {
+        Mock::given(method(verb.as_str()))
+            .and(path("/org/acme"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!(verb.as_str())))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = test_client(&server);
+    for verb in HttpMethod::ALL {
+        let response = client
+            .call(
+                verb,
+                "/org/acme",
+                HashMap::new(),
+                HashMap::new(),
+                None,
+                "application/json",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.body, json!(verb.as_str()));
+    }
+}
+
+#[tokio::test]
+async fn test_error_responses_carry_status_and_message() {
+    let server = MockServer::start().await;
+    for (org, response) in [
+        (
+            "message",
+            ResponseTemplate::new(400)
+                .insert_header("x-correlation-id", "corr-1")
+                .set_body_json(json!({"message": "Invalid subnet"})),
+        ),
+        (
+            "error",
+            ResponseTemplate::new(403).set_body_json(json!({"error": "Forbidden key"})),
+        ),
+        (
+            "text",
+            ResponseTemplate::new(502).set_body_string("Bad gateway"),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(format!("/org/{}", org)))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+    }
+
+    let client = test_client(&server);
+    for (org, expected) in [
+        (
+            "message",
+            "Pangolin API error (400 Bad Request): Invalid subnet",
+        ),
+        ("error", "Pangolin API error (403 Forbidden): Forbidden key"),
+        ("text", "Pangolin API error (502 Bad Gateway): Bad gateway"),
+    ] {
+        let err = client
+            .call(
+                HttpMethod::Get,
+                "/org/{orgId}",
+                HashMap::from([("orgId".to_string(), org.to_string())]),
+                HashMap::new(),
+                None,
+                "application/json",
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected);
+
+        let api_error = err.downcast_ref::<PangolinApiError>().unwrap();
+        assert_eq!(api_error.path, format!("/org/{}", org));
+        assert_eq!(api_error.details.is_some(), org != "text");
+    }
+
+    let err = client
+        .call(
+            HttpMethod::Get,
+            "/org/{orgId}",
+            HashMap::from([("orgId".to_string(), "message".to_string())]),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+        .await
+        .unwrap_err();
+    let api_error = err.downcast_ref::<PangolinApiError>().unwrap();
+    assert_eq!(api_error.to_json()["request_id"], "corr-1");
+    assert_eq!(api_error.to_json()["message"], "Invalid subnet");
+}
+
+#[tokio::test]
+async fn test_non_json_success_is_returned_as_text() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("plain ok"))
+        .mount(&server)
+        .await;
+
+    assert_eq!(
+        get_orgs(&test_client(&server)).await.unwrap(),
+        json!("plain ok")
+    );
+}
+
+#[tokio::test]
+async fn test_response_content_type_selects_decoding() {
+    let server = MockServer::start().await;
+    let csv = "siteId,name\n1,\"42\"\n";
+    for (route, response) in [
+        (
+            "/export.csv",
+            ResponseTemplate::new(200).set_body_raw(csv, "text/csv; charset=utf-8"),
+        ),
+        (
+            "/orgs.json",
+            ResponseTemplate::new(200).set_body_raw(r#"{"orgs": [1]}"#, "application/json"),
+        ),
+        (
+            "/logo.png",
+            ResponseTemplate::new(200)
+                .set_body_raw(vec![0x89, b'P', b'N', b'G', 0xff], "image/png"),
+        ),
+    ] {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+    }
+
+    let client = test_client(&server);
+    let get = |route: &'static str| {
+        client.call(
+            HttpMethod::Get,
+            route,
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            "application/json",
+        )
+    };
+
+    // CSV is returned verbatim rather than parsed
+    assert_eq!(get("/export.csv").await.unwrap().body, json!(csv));
+    assert_eq!(get("/orgs.json").await.unwrap().body, json!({"orgs": [1]}));
+    assert_eq!(
+        get("/logo.png").await.unwrap().body,
+        json!({
+            "contentType": "image/png",
+            "encoding": "base64",
+            "data": "iVBOR/8=",
+            "note": "Binary image/png response (5 bytes), base64-encoded",
+        })
+    );
+}
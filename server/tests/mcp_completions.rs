@@ -0,0 +1,215 @@
+//! End-to-end tests for `completion/complete`
+//!
+//! Run with: cargo test --test mcp_completions
+
+#[path = "../src/audit.rs"]
+mod audit;
+
+#[path = "../src/cache.rs"]
+mod cache;
+
+#[path = "../src/completions.rs"]
+mod completions;
+
+#[path = "../src/mcp_logging.rs"]
+mod mcp_logging;
+
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+#[path = "../src/prompts.rs"]
+mod prompts;
+
+#[path = "../src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../src/redact.rs"]
+mod redact;
+
+#[path = "../src/resources.rs"]
+mod resources;
+
+#[path = "../src/search.rs"]
+mod search;
+
+#[path = "../src/service.rs"]
+mod service;
+
+#[path = "../src/stats.rs"]
+mod stats;
+
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[path = "../src/truncate.rs"]
+mod truncate;
+
+#[path = "../src/types.rs"]
+mod types;
+
+#[path = "../src/workflows.rs"]
+mod workflows;
+
+mod common;
+
+use rmcp::model::{ArgumentInfo, CompleteRequestParam, CompletionInfo, Reference};
+use serde_json::json;
+use service::ServiceConfig;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Spec with enum-valued query parameters and body properties
+fn get_enum_swagger_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test Pangolin API", "version": "v1"},
+        "paths": {
+            "/resources": {
+                "get": {
+                    "parameters": [
+                        {"name": "protocol", "in": "query", "schema": {"type": "string", "enum": ["http", "https", "tcp", "udp"]}}
+                    ]
+                }
+            },
+            "/org/{orgId}/resource": {
+                "put": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "type": {"type": "string", "enum": ["Http", "Raw"]}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+async fn complete(
+    base_url: &str,
+    config: ServiceConfig,
+    reference: Reference,
+    argument: &str,
+    value: &str,
+) -> CompletionInfo {
+    let service = common::build_service(get_enum_swagger_spec(), base_url, config);
+    let client = common::connect(service).await;
+    let result = client
+        .complete(CompleteRequestParam {
+            r#ref: reference,
+            argument: ArgumentInfo {
+                name: argument.to_string(),
+                value: value.to_string(),
+            },
+            context: None,
+        })
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+    result.completion
+}
+
+#[tokio::test]
+async fn test_enum_parameters_complete_by_prefix() {
+    let completion = complete(
+        "http://localhost:1",
+        ServiceConfig::default(),
+        Reference::for_prompt("resources"),
+        "protocol",
+        "ht",
+    )
+    .await;
+    assert_eq!(completion.values, vec!["http", "https"]);
+    assert_eq!(completion.total, Some(2));
+    assert_eq!(completion.has_more, Some(false));
+
+    // Body properties complete too, also when addressed by endpoint resource URI
+    let completion = complete(
+        "http://localhost:1",
+        ServiceConfig::default(),
+        Reference::for_resource("pangolin://endpoint/create_org_by_orgId_resource"),
+        "type",
+        "",
+    )
+    .await;
+    assert_eq!(completion.values, vec!["Http", "Raw"]);
+}
+
+#[tokio::test]
+async fn test_completion_without_match_is_empty() {
+    for (tool, argument, value) in [
+        ("resources", "protocol", "ftp"),
+        ("resources", "unknown", ""),
+        ("no_such_tool", "protocol", ""),
+        ("create_org_by_orgId_resource", "orgId", ""),
+    ] {
+        let completion = complete(
+            "http://localhost:1",
+            ServiceConfig::default(),
+            Reference::for_prompt(tool),
+            argument,
+            value,
+        )
+        .await;
+        assert!(
+            completion.values.is_empty(),
+            "{} {}: {:?}",
+            tool,
+            argument,
+            completion
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_dynamic_org_completion_uses_orgs_listing() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {"orgs": [{"orgId": "acme", "name": "Acme"}, {"orgId": "globex"}]},
+            "success": true
+        })))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        get_enum_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            dynamic_completion: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    for (value, expected) in [("", vec!["acme", "globex"]), ("g", vec!["globex"])] {
+        let result = client
+            .complete(CompleteRequestParam {
+                r#ref: Reference::for_prompt("create_org_by_orgId_resource"),
+                argument: ArgumentInfo {
+                    name: "orgId".to_string(),
+                    value: value.to_string(),
+                },
+                context: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.completion.values, expected);
+    }
+    client.cancel().await.unwrap();
+}
@@ -0,0 +1,309 @@
+//! End-to-end tests for the workflow prompts
+//!
+//! Run with: cargo test --test mcp_prompts
+
+#[path = "../src/audit.rs"]
+mod audit;
+
+#[path = "../src/cache.rs"]
+mod cache;
+
+#[path = "../src/completions.rs"]
+mod completions;
+
+#[path = "../src/mcp_logging.rs"]
+mod mcp_logging;
+
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+#[path = "../src/prompts.rs"]
+mod prompts;
+
+#[path = "../src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../src/redact.rs"]
+mod redact;
+
+#[path = "../src/resources.rs"]
+mod resources;
+
+#[path = "../src/search.rs"]
+mod search;
+
+#[path = "../src/service.rs"]
+mod service;
+
+#[path = "../src/stats.rs"]
+mod stats;
+
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[path = "../src/truncate.rs"]
+mod truncate;
+
+#[path = "../src/types.rs"]
+mod types;
+
+#[path = "../src/workflows.rs"]
+mod workflows;
+
+mod common;
+
+use rmcp::model::{GetPromptRequestParam, PromptMessageContent};
+use serde_json::json;
+use service::ServiceConfig;
+
+/// Spec with the site/resource/target endpoints the workflows reference
+fn get_workflow_swagger_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test Pangolin API", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/sites": {"get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}},
+            "/org/{orgId}/site": {"put": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}},
+            "/org/{orgId}/resources": {"get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}},
+            "/org/{orgId}/resource": {"put": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}},
+            "/resource/{resourceId}": {"get": {"parameters": [{"name": "resourceId", "in": "path", "required": true}]}},
+            "/resource/{resourceId}/targets": {"get": {"parameters": [{"name": "resourceId", "in": "path", "required": true}]}},
+            "/resource/{resourceId}/target": {"put": {"parameters": [{"name": "resourceId", "in": "path", "required": true}]}}
+        }
+    }"#
+}
+
+async fn render(
+    config: ServiceConfig,
+    name: &str,
+    arguments: serde_json::Value,
+) -> Result<String, rmcp::ServiceError> {
+    let service = common::build_service(get_workflow_swagger_spec(), "http://localhost", config);
+    let client = common::connect(service).await;
+    let result = client
+        .get_prompt(GetPromptRequestParam {
+            name: name.to_string(),
+            arguments: arguments.as_object().cloned(),
+        })
+        .await;
+    client.cancel().await.unwrap();
+
+    let result = result?;
+    match &result.messages[0].content {
+        PromptMessageContent::Text { text } => Ok(text.clone()),
+        other => panic!("Expected text prompt, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_list_prompts_declares_arguments() {
+    let service = common::build_service(
+        get_workflow_swagger_spec(),
+        "http://localhost",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let prompts = client.list_all_prompts().await.unwrap();
+    let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "pangolin_getting_started",
+            "onboard_new_resource",
+            "audit_exposed_resources",
+            "rotate_site"
+        ]
+    );
+    assert!(prompts[0].arguments.is_none());
+
+    let onboard = &prompts[1];
+    let arguments = serde_json::to_value(onboard.arguments.as_ref().unwrap()).unwrap();
+    assert_eq!(arguments[0]["name"], "orgId");
+    assert_eq!(arguments[0]["required"], true);
+    assert_eq!(arguments[1]["name"], "domain");
+    assert_eq!(arguments[1]["required"], true);
+    assert_eq!(arguments[2]["name"], "siteId");
+    assert_eq!(arguments[2]["required"], false);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_prompt_renders_arguments_and_tool_names() {
+    let config = ServiceConfig {
+        tool_prefix: Some("pangolin_".to_string()),
+        ..Default::default()
+    };
+    let text = render(
+        config,
+        "onboard_new_resource",
+        json!({"orgId": "acme", "domain": "app.acme.io"}),
+    )
+    .await
+    .unwrap();
+
+    assert!(
+        text.starts_with(
+            "Onboard a new proxied resource in organization 'acme' served on 'app.acme.io'."
+        ),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("`pangolin_create_org_by_orgId_site`"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("`pangolin_create_org_by_orgId_resource`"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("`pangolin_create_resource_by_resourceId_target`"),
+        "{}",
+        text
+    );
+    assert!(text.contains("Site (given: (not provided))"), "{}", text);
+    // The rules endpoint is not in this spec
+    assert!(
+        text.contains("(no tool available for PUT /resource/{resourceId}/rule)"),
+        "{}",
+        text
+    );
+    assert!(!text.contains("{{"), "{}", text);
+}
+
+#[tokio::test]
+async fn test_prompt_omits_write_tools_in_read_only_mode() {
+    let config = ServiceConfig {
+        read_only: true,
+        ..Default::default()
+    };
+    let text = render(
+        config,
+        "onboard_new_resource",
+        json!({"orgId": "acme", "domain": "app.acme.io"}),
+    )
+    .await
+    .unwrap();
+
+    assert!(text.contains("`org_by_orgId_sites`"), "{}", text);
+    assert!(
+        text.contains("(no tool available for PUT /org/{orgId}/site)"),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn test_prompt_requires_declared_arguments() {
+    let err = render(
+        ServiceConfig::default(),
+        "rotate_site",
+        json!({"orgId": "acme"}),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing required prompt arguments: siteId"),
+        "{}",
+        err
+    );
+
+    let err = render(ServiceConfig::default(), "unknown", json!({}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Unknown prompt"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_getting_started_prompt_is_generated_from_spec() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+    let result = client
+        .get_prompt(GetPromptRequestParam {
+            name: "pangolin_getting_started".to_string(),
+            arguments: None,
+        })
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+
+    let PromptMessageContent::Text { text } = &result.messages[0].content else {
+        panic!("Expected text prompt");
+    };
+    assert!(
+        text.starts_with("This server exposes 5 Pangolin API tools."),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("- **Organization** (4 tools): `orgs`, `org_by_orgId`, `update_org_by_orgId`, `delete_org_by_orgId`"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("- **Site** (1 tool): `site_by_siteId`"),
+        "{}",
+        text
+    );
+    assert!(text.contains("`onboard_new_resource`"), "{}", text);
+    // None of the setup endpoints are in this spec
+    assert!(!text.contains("Setting up access"), "{}", text);
+}
+
+#[tokio::test]
+async fn test_getting_started_lists_available_setup_steps() {
+    let text = render(
+        ServiceConfig::default(),
+        "pangolin_getting_started",
+        json!({}),
+    )
+    .await
+    .unwrap();
+    assert!(
+        text.contains(
+            "1. Create a site (the tunnel into a network) with `create_org_by_orgId_site`"
+        ),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains(
+            "3. Point the resource at an upstream host with `create_resource_by_resourceId_target`"
+        ),
+        "{}",
+        text
+    );
+    assert!(text.contains("- **Other** (7 tools)"), "{}", text);
+
+    let text = render(
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+        "pangolin_getting_started",
+        json!({}),
+    )
+    .await
+    .unwrap();
+    assert!(
+        text.contains("4 Pangolin API tools in read-only mode"),
+        "{}",
+        text
+    );
+    assert!(!text.contains("Setting up access"), "{}", text);
+}
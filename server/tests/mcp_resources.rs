@@ -0,0 +1,554 @@
+//! End-to-end tests for the MCP resource handlers
+//!
+//! Run with: cargo test --test mcp_resources
+
+#[path = "../src/audit.rs"]
+mod audit;
+
+#[path = "../src/cache.rs"]
+mod cache;
+
+#[path = "../src/completions.rs"]
+mod completions;
+
+#[path = "../src/mcp_logging.rs"]
+mod mcp_logging;
+
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+#[path = "../src/prompts.rs"]
+mod prompts;
+
+#[path = "../src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../src/redact.rs"]
+mod redact;
+
+#[path = "../src/resources.rs"]
+mod resources;
+
+#[path = "../src/search.rs"]
+mod search;
+
+#[path = "../src/service.rs"]
+mod service;
+
+#[path = "../src/stats.rs"]
+mod stats;
+
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[path = "../src/truncate.rs"]
+mod truncate;
+
+#[path = "../src/types.rs"]
+mod types;
+
+#[path = "../src/workflows.rs"]
+mod workflows;
+
+mod common;
+
+use rmcp::model::{ReadResourceRequestParam, ResourceContents};
+use serde_json::json;
+use service::ServiceConfig;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Spec documenting two outbound webhooks
+fn get_webhook_swagger_spec() -> &'static str {
+    r#"{
+        "openapi": "3.1.0",
+        "info": {"title": "Test Pangolin API", "version": "v1"},
+        "paths": {
+            "/orgs": {
+                "get": {"description": "List all organizations", "responses": {}}
+            }
+        },
+        "webhooks": {
+            "siteOnline": {
+                "post": {
+                    "description": "Sent when a site comes online",
+                    "tags": ["Site"],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "siteId": {"type": "integer"},
+                                        "online": {"type": "boolean"}
+                                    },
+                                    "required": ["siteId"]
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "orgDeleted": {
+                "post": {
+                    "summary": "Sent when an organization is deleted",
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {"orgId": {"type": "string"}}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+fn resource_json(contents: &ResourceContents) -> serde_json::Value {
+    match contents {
+        ResourceContents::TextResourceContents { text, .. } => {
+            serde_json::from_str(text).expect("Resource text should be JSON")
+        }
+        other => panic!("Expected text contents, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_webhooks_are_listed_as_resources() {
+    let service = common::build_service(
+        get_webhook_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let info = client.peer_info().expect("Server info should be available");
+    assert!(info.capabilities.resources.is_some());
+    assert!(info
+        .instructions
+        .as_deref()
+        .unwrap()
+        .contains("Webhook docs: 2"));
+
+    let resources = client.list_all_resources().await.unwrap();
+    let uris: Vec<_> = resources.iter().map(|r| r.uri.as_str()).collect();
+    assert!(uris.contains(&"pangolin://webhook/siteOnline"));
+    assert!(uris.contains(&"pangolin://webhook/orgDeleted"));
+
+    // Webhooks are documentation only, never tools
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "orgs",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_webhook_resources_can_be_read() {
+    let service = common::build_service(
+        get_webhook_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://webhook/siteOnline".to_string(),
+        })
+        .await
+        .unwrap();
+    let doc = resource_json(&result.contents[0]);
+    assert_eq!(doc["method"], "POST");
+    assert_eq!(doc["description"], "Sent when a site comes online");
+    assert_eq!(
+        doc["payloadSchema"]["properties"]["siteId"]["type"],
+        "integer"
+    );
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://webhook/orgDeleted".to_string(),
+        })
+        .await
+        .unwrap();
+    let doc = resource_json(&result.contents[0]);
+    assert_eq!(doc["description"], "Sent when an organization is deleted");
+    assert_eq!(
+        doc["payloadSchema"]["properties"]["orgId"]["type"],
+        "string"
+    );
+
+    let missing = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://webhook/unknown".to_string(),
+        })
+        .await;
+    assert!(missing.is_err());
+
+    client.cancel().await.unwrap();
+}
+
+/// Spec with one documented GET and one documented POST endpoint
+fn get_documented_swagger_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test Pangolin API", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "get": {
+                    "description": "Get an organization",
+                    "tags": ["Organization"],
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "description": "Organization ID", "schema": {"type": "string"}}
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "The organization",
+                            "content": {
+                                "application/json": {
+                                    "schema": {"type": "object", "properties": {"orgId": {"type": "string"}}}
+                                }
+                            }
+                        },
+                        "404": {"description": "Organization not found"}
+                    }
+                },
+                "post": {
+                    "description": "Update an organization",
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string", "description": "Display name", "maxLength": 64},
+                                        "subnet": {"type": "string", "pattern": "^\\d+\\.\\d+\\.\\d+\\.\\d+/\\d+$"}
+                                    },
+                                    "required": ["name"]
+                                }
+                            }
+                        }
+                    },
+                    "responses": {"200": {"description": "Organization updated"}}
+                }
+            }
+        }
+    }"#
+}
+
+fn resource_text(contents: &ResourceContents) -> &str {
+    match contents {
+        ResourceContents::TextResourceContents { text, .. } => text,
+        other => panic!("Expected text contents, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_endpoint_docs_are_readable_resources() {
+    let service = common::build_service(
+        get_documented_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let resources = client.list_all_resources().await.unwrap();
+    let uris: Vec<_> = resources.iter().map(|r| r.uri.as_str()).collect();
+    assert!(uris.contains(&"pangolin://endpoint/org_by_orgId"));
+    assert!(uris.contains(&"pangolin://endpoint/update_org_by_orgId"));
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://endpoint/org_by_orgId".to_string(),
+        })
+        .await
+        .unwrap();
+    let doc = resource_text(&result.contents[0]);
+    assert!(doc.starts_with("# org_by_orgId\n"), "{}", doc);
+    assert!(doc.contains("`GET /org/{orgId}`"));
+    assert!(doc.contains("Tags: Organization"));
+    assert!(doc.contains("## Path parameters"));
+    assert!(doc.contains("| `orgId` | string | yes | Organization ID |  |"));
+    assert!(doc.contains("- `200` The organization"));
+    assert!(doc.contains("\"orgId\": {"), "response schema: {}", doc);
+    assert!(doc.contains("- `404` Organization not found"));
+    assert!(!doc.contains("Blocked"));
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://endpoint/update_org_by_orgId".to_string(),
+        })
+        .await
+        .unwrap();
+    let doc = resource_text(&result.contents[0]);
+    assert!(doc.contains("`POST /org/{orgId}`"));
+    assert!(doc.contains("## Request body (`application/json`)"));
+    assert!(doc.contains("| `name` | string | yes | Display name | max length: 64 |"));
+    assert!(
+        doc.contains("| `subnet` | string | no |  | pattern: `^\\d+\\.\\d+\\.\\d+\\.\\d+/\\d+$` |")
+    );
+    assert!(doc.contains("- `200` Organization updated"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_write_endpoint_docs_are_marked_blocked_in_read_only_mode() {
+    let service = common::build_service(
+        get_documented_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let resources = client.list_all_resources().await.unwrap();
+    let update = resources
+        .iter()
+        .find(|r| r.uri == "pangolin://endpoint/update_org_by_orgId")
+        .expect("write endpoints stay documented in read-only mode");
+    assert!(update
+        .description
+        .as_deref()
+        .unwrap()
+        .contains("blocked in read-only mode"));
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://endpoint/update_org_by_orgId".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(resource_text(&result.contents[0]).contains("> **Blocked:**"));
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://endpoint/org_by_orgId".to_string(),
+        })
+        .await
+        .unwrap();
+    assert!(!resource_text(&result.contents[0]).contains("Blocked"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_endpoints_are_listed_as_resource_templates() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let templates = client.list_all_resource_templates().await.unwrap();
+    let uris: Vec<_> = templates.iter().map(|t| t.uri_template.as_str()).collect();
+    assert_eq!(
+        uris,
+        vec!["pangolin://org/{orgId}", "pangolin://site/{siteId}"]
+    );
+
+    // Tools stay available alongside the templates
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(tools.iter().any(|t| t.name == "org_by_orgId"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_resource_template_reads_call_the_api() {
+    let upstream = MockServer::start().await;
+    let org = json!({"orgId": "my-org", "name": "My Org", "subnet": "100.90.128.0/24"});
+    Mock::given(method("GET"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(org.clone()))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://org/my-org".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(resource_json(&result.contents[0]), org);
+    match &result.contents[0] {
+        ResourceContents::TextResourceContents { uri, mime_type, .. } => {
+            assert_eq!(uri, "pangolin://org/my-org");
+            assert_eq!(mime_type.as_deref(), Some("application/json"));
+        }
+        other => panic!("Expected text contents, got {:?}", other),
+    }
+
+    let missing = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://org/my-org/unknown".to_string(),
+        })
+        .await;
+    assert!(missing.is_err());
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_resource_template_reads_are_redacted_and_truncated() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "orgId": "my-org",
+            "apiSecret": "s3cr3t",
+            "sites": (0..200).map(|i| json!({"siteId": i})).collect::<Vec<_>>(),
+        })))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            redact_fields: vec!["secret".to_string()],
+            max_response_bytes: Some(1000),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://org/my-org".to_string(),
+        })
+        .await
+        .unwrap();
+    let org = resource_json(&result.contents[0]);
+    assert_eq!(org["apiSecret"], "***REDACTED***");
+    assert!(org["sites"].as_array().unwrap().len() < 200);
+    let ResourceContents::TextResourceContents { meta, .. } = &result.contents[0] else {
+        panic!("Expected text contents");
+    };
+    let meta = meta.as_ref().unwrap();
+    assert_eq!(meta["redacted"], 1);
+    assert!(meta["truncated"]["original_bytes"].as_u64().unwrap() > 1000);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_filtered_get_endpoints_are_neither_listed_nor_readable() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"secret": "x"})))
+        .expect(0)
+        .mount(&upstream)
+        .await;
+
+    for config in [
+        ServiceConfig {
+            deny_tools: vec!["org_by_orgId".to_string(), "site_by_siteId".to_string()],
+            ..Default::default()
+        },
+        ServiceConfig {
+            read_only: true,
+            read_only_overrides: [
+                ("org_by_orgId".to_string(), false),
+                ("site_by_siteId".to_string(), false),
+            ]
+            .into(),
+            ..Default::default()
+        },
+    ] {
+        let service =
+            common::build_service(common::get_test_swagger_spec(), &upstream.uri(), config);
+        let client = common::connect(service).await;
+
+        let templates = client.list_all_resource_templates().await.unwrap();
+        assert!(templates.is_empty(), "{:?}", templates);
+
+        for uri in ["pangolin://org/acme", "pangolin://site/7"] {
+            let err = client
+                .read_resource(ReadResourceRequestParam {
+                    uri: uri.to_string(),
+                })
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("Unknown resource"), "{}", err);
+        }
+
+        client.cancel().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_status_resource_reports_mode_and_counts() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let resources = client.list_all_resources().await.unwrap();
+    assert_eq!(resources[0].uri, "pangolin://status");
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "pangolin://status".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        resource_json(&result.contents[0]),
+        json!({
+            "server": "mcp-pangolin",
+            "version": env!("CARGO_PKG_VERSION"),
+            "mode": "read-only",
+            "baseUrl": "http://localhost:1",
+            "apiVersion": "v1",
+            "toolPrefix": "pangolin_",
+            "endpoints": 5,
+            "availableEndpoints": 3,
+            "filteredEndpoints": 2,
+            "webhooks": 0
+        })
+    );
+
+    client.cancel().await.unwrap();
+}
@@ -0,0 +1,5675 @@
+//! End-to-end tests for the MCP tool handlers
+//!
+//! These tests serve `PangolinService` over an in-memory transport, connect
+//! an MCP client to it, and point the service at a mock Pangolin API.
+//!
+//! Run with: cargo test --test mcp_tools
+
+#[path = "../src/audit.rs"]
+mod audit;
+
+#[path = "../src/cache.rs"]
+mod cache;
+
+#[path = "../src/completions.rs"]
+mod completions;
+
+#[path = "../src/mcp_logging.rs"]
+mod mcp_logging;
+
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+#[path = "../src/prompts.rs"]
+mod prompts;
+
+#[path = "../src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../src/redact.rs"]
+mod redact;
+
+#[path = "../src/resources.rs"]
+mod resources;
+
+#[path = "../src/search.rs"]
+mod search;
+
+#[path = "../src/service.rs"]
+mod service;
+
+#[path = "../src/stats.rs"]
+mod stats;
+
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[path = "../src/truncate.rs"]
+mod truncate;
+
+#[path = "../src/types.rs"]
+mod types;
+
+#[path = "../src/workflows.rs"]
+mod workflows;
+
+mod common;
+
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParam, ClientCapabilities, ClientInfo, ClientRequest,
+    CreateElicitationRequestParam, CreateElicitationResult, ElicitationAction, LoggingLevel,
+    LoggingMessageNotificationParam, ProgressNotificationParam, SetLevelRequestParam,
+};
+use rmcp::service::{NotificationContext, PeerRequestOptions, RoleClient};
+use rmcp::ClientHandler;
+use serde_json::json;
+use service::{BoolQueryStyle, ServiceConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn call(name: &str, arguments: serde_json::Value) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.to_string().into(),
+        arguments: arguments.as_object().cloned(),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_prefix_is_applied_to_listed_tools() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+
+    assert!(names.contains(&"pangolin_orgs"));
+    assert!(names.contains(&"pangolin_org_by_orgId"));
+    assert!(
+        names.iter().all(|n| n.starts_with("pangolin_")),
+        "Every tool should carry the prefix: {:?}",
+        names
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_prefixed_tool_is_callable() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/my-org"))
+        .and(header("Authorization", "Bearer test-api-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "my-org"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("pangolin_org_by_orgId", json!({"orgId": "my-org"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert!(common::result_text(&result).contains("my-org"));
+
+    // The unprefixed name is not a valid tool when a prefix is configured
+    let err = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "my-org"})))
+        .await;
+    assert!(err.is_err(), "Unprefixed tool name should be rejected");
+
+    client.cancel().await.unwrap();
+}
+
+/// Tool names listed for the shared test spec under the given filters
+async fn filtered_tool_names(allow: &[&str], deny: &[&str]) -> Vec<String> {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            allow_tools: allow.iter().map(|p| p.to_string()).collect(),
+            deny_tools: deny.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let tools = client.list_all_tools().await.unwrap();
+    client.cancel().await.unwrap();
+    tools.iter().map(|t| t.name.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_allow_tools_keeps_only_matching_names() {
+    // Patterns match whole names: `org_by_orgId` does not pull in its write variants
+    assert_eq!(
+        filtered_tool_names(&["orgs", "org_by_orgId"], &[]).await,
+        vec!["orgs", "org_by_orgId"]
+    );
+    assert_eq!(
+        filtered_tool_names(&["site_.*"], &[]).await,
+        vec!["site_by_siteId"]
+    );
+}
+
+#[tokio::test]
+async fn test_deny_tools_hides_matching_names() {
+    assert_eq!(
+        filtered_tool_names(&[], &["delete_.*", "update_.*"]).await,
+        vec![
+            "orgs",
+            "org_by_orgId",
+            "site_by_siteId",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_deny_tools_wins_over_allow_tools() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            tool_prefix: Some("pangolin_".to_string()),
+            allow_tools: vec![".*org.*".to_string()],
+            deny_tools: vec!["delete_org_by_orgId".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "pangolin_orgs",
+            "pangolin_org_by_orgId",
+            "pangolin_update_org_by_orgId"
+        ]
+    );
+
+    // Filtered tools cannot be called either
+    let err = client
+        .call_tool(call(
+            "pangolin_delete_org_by_orgId",
+            json!({"orgId": "acme"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Tool disabled: pangolin_delete_org_by_orgId exists but it is excluded by --allow-tools/--deny-tools"
+        ),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+/// Spec with a multi-tag endpoint and an untagged one
+const MULTI_TAG_SPEC: &str = r#"{
+    "openapi": "3.0.0",
+    "info": {"title": "Test", "version": "v1"},
+    "paths": {
+        "/site/{siteId}": {
+            "get": {"tags": ["Site"], "parameters": [{"name": "siteId", "in": "path", "required": true}]},
+            "delete": {"tags": ["Site"], "parameters": [{"name": "siteId", "in": "path", "required": true}]}
+        },
+        "/role/{roleId}/site": {
+            "get": {"tags": ["Role", "Site"], "parameters": [{"name": "roleId", "in": "path", "required": true}]}
+        },
+        "/user/{userId}": {
+            "get": {"tags": ["User"], "parameters": [{"name": "userId", "in": "path", "required": true}]}
+        },
+        "/health": {"get": {}}
+    }
+}"#;
+
+/// Tool names listed for `MULTI_TAG_SPEC` under the given tag filters
+async fn tag_filtered_tool_names(expose: &[&str], hide: &[&str], read_only: bool) -> Vec<String> {
+    let service = common::build_service(
+        MULTI_TAG_SPEC,
+        "http://localhost:1",
+        ServiceConfig {
+            read_only,
+            expose_tags: expose.iter().map(|t| t.to_string()).collect(),
+            hide_tags: hide.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let tools = client.list_all_tools().await.unwrap();
+    client.cancel().await.unwrap();
+    tools.iter().map(|t| t.name.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_expose_and_hide_tags() {
+    // Any exposed tag is enough; untagged tools need no tag filter at all
+    assert_eq!(
+        tag_filtered_tool_names(&["site"], &[], false).await,
+        vec![
+            "site_by_siteId",
+            "delete_site_by_siteId",
+            "role_by_roleId_site",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+    assert_eq!(
+        tag_filtered_tool_names(&[], &["Site"], false).await,
+        vec![
+            "role_by_roleId_site",
+            "user_by_userId",
+            "health",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+    assert_eq!(
+        tag_filtered_tool_names(&["Site", "User"], &["Role"], false).await,
+        vec![
+            "site_by_siteId",
+            "delete_site_by_siteId",
+            "role_by_roleId_site",
+            "user_by_userId",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_instructions_count_tools_per_tag() {
+    let service = common::build_service(
+        MULTI_TAG_SPEC,
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+    assert!(
+        instructions.contains(
+            "Tools by tag:\n\
+             - Role (1): role_by_roleId_site\n\
+             - Site (2): site_by_siteId, role_by_roleId_site\n\
+             - User (1): user_by_userId\n\
+             - untagged (1): health\n"
+        ),
+        "{}",
+        instructions
+    );
+    assert!(
+        instructions.contains("Mode: read-only\n"),
+        "{}",
+        instructions
+    );
+    assert!(instructions.contains("Filters: none\n"), "{}", instructions);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_instructions_list_filters_and_shorten_the_tool_names() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            hide_tags: vec!["Site".to_string()],
+            deny_tools: vec!["delete_.*".to_string()],
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+    assert!(
+        instructions.contains("Filters: tags hidden: site; tools denied: delete_.*\n"),
+        "{}",
+        instructions
+    );
+    assert!(
+        instructions.contains(
+            "- Organization (3): pangolin_orgs, pangolin_org_by_orgId, pangolin_update_org_by_orgId\n"
+        ),
+        "{}",
+        instructions
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_instructions_drop_tag_lines_past_the_budget() {
+    let instructions = |max: usize| async move {
+        let service = common::build_service(
+            MULTI_TAG_SPEC,
+            "http://localhost:1",
+            ServiceConfig {
+                instructions_max_chars: Some(max),
+                ..Default::default()
+            },
+        );
+        let client = common::connect(service).await;
+        let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+        client.cancel().await.unwrap();
+        instructions
+    };
+
+    let full = instructions(0).await;
+    assert!(full.contains("- untagged (1): health\n"), "{}", full);
+
+    // Room for every line but the last two tag lines
+    let budget = full.chars().count()
+        - "- User (1): user_by_userId\n- untagged (1): health\n".len()
+        + "- ...and 2 more tag(s); call search_endpoints to find their tools\n".len();
+    let shortened = instructions(budget).await;
+    assert!(shortened.chars().count() <= budget);
+    assert!(
+        shortened.contains(
+            "- Site (3): site_by_siteId, delete_site_by_siteId, role_by_roleId_site\n\
+             - ...and 2 more tag(s); call search_endpoints to find their tools\n\
+             Dry run:"
+        ),
+        "{}",
+        shortened
+    );
+
+    // Far too small a budget still yields cut, bounded text
+    let cut = instructions(40).await;
+    assert_eq!(cut.chars().count(), 40);
+    assert!(cut.ends_with('…'));
+}
+
+#[tokio::test]
+async fn test_tag_filter_composes_with_read_only_mode() {
+    assert_eq!(
+        tag_filtered_tool_names(&["Site"], &[], true).await,
+        vec![
+            "site_by_siteId",
+            "role_by_roleId_site",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_tool_hidden_by_tag_is_disabled() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            expose_tags: vec!["Site".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let err = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "my-org"})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Tool disabled: org_by_orgId exists but its tags (Organization) are not exposed"
+        ),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[test]
+fn test_invalid_tool_filters_are_rejected() {
+    let build = |allow: &str, deny: &str| {
+        service::PangolinService::new(
+            swagger::SwaggerSpec::from_json(common::get_test_swagger_spec()).unwrap(),
+            "test-api-key".to_string(),
+            "http://localhost:1".to_string(),
+            ServiceConfig {
+                allow_tools: [allow]
+                    .iter()
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.to_string())
+                    .collect(),
+                deny_tools: [deny]
+                    .iter()
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.to_string())
+                    .collect(),
+                ..Default::default()
+            },
+        )
+        .err()
+        .expect("service should not build")
+        .to_string()
+    };
+
+    let err = build("org(", "");
+    assert!(
+        err.starts_with("Invalid --allow-tools pattern 'org('"),
+        "{}",
+        err
+    );
+    let err = build("", "[z-a]");
+    assert!(
+        err.starts_with("Invalid --deny-tools pattern '[z-a]'"),
+        "{}",
+        err
+    );
+    let err = build("nothing_matches", "");
+    assert_eq!(
+        err,
+        "--allow-tools matches none of the 5 tools generated from the spec"
+    );
+}
+
+#[tokio::test]
+async fn test_hidden_write_tool_is_disabled_in_read_only_mode() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let err = client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "my-org"})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Tool disabled: delete_org_by_orgId exists but it is disabled in read-only mode; \
+             restart without --read-only or add --read-only-overrides delete_org_by_orgId=allow"
+        ),
+        "{}",
+        err
+    );
+    let rmcp::ServiceError::McpError(err) = err else {
+        panic!("expected an MCP error, got {:?}", err);
+    };
+    assert_eq!(
+        err.data,
+        Some(json!({"tool": "delete_org_by_orgId", "disabledBy": "read_only"}))
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_hidden_write_tool_looks_unknown_with_hide_writes() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            read_only_hide_writes: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let blocked = client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "my-org"})))
+        .await
+        .unwrap_err();
+    let missing = client
+        .call_tool(call("delete_user_by_userId", json!({"userId": "u1"})))
+        .await
+        .unwrap_err();
+    let (rmcp::ServiceError::McpError(blocked), rmcp::ServiceError::McpError(missing)) =
+        (blocked, missing)
+    else {
+        panic!("expected MCP errors");
+    };
+    assert_eq!(
+        blocked.message,
+        "Unknown tool: delete_org_by_orgId (the API spec defines no such tool; call tools/list for the available ones)"
+    );
+    assert_eq!((blocked.code, blocked.data), (missing.code, missing.data));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_hidden_write_tool_docs_look_unknown_with_hide_writes() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            read_only_hide_writes: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let resources = client.list_all_resources().await.unwrap();
+    let uris: Vec<_> = resources.iter().map(|r| r.uri.as_str()).collect();
+    assert!(uris.contains(&"pangolin://endpoint/org_by_orgId"));
+    assert!(!uris.contains(&"pangolin://endpoint/delete_org_by_orgId"));
+
+    let read = |uri: &str| {
+        client.read_resource(rmcp::model::ReadResourceRequestParam {
+            uri: uri.to_string(),
+        })
+    };
+    let blocked = read("pangolin://endpoint/delete_org_by_orgId")
+        .await
+        .unwrap_err();
+    let rmcp::ServiceError::McpError(blocked) = blocked else {
+        panic!("expected an MCP error, got {:?}", blocked);
+    };
+    assert_eq!(blocked.code, rmcp::model::ErrorCode::RESOURCE_NOT_FOUND);
+    assert_eq!(
+        blocked.message,
+        "Unknown resource: pangolin://endpoint/delete_org_by_orgId"
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_near_miss_tool_name_suggests_the_listed_tool() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    for (called, suggestion) in [
+        ("list_orgs", "orgs"),
+        ("Org-By-OrgID", "org_by_orgId"),
+        ("site_by_siteIds", "site_by_siteId"),
+    ] {
+        let err = client.call_tool(call(called, json!({}))).await.unwrap_err();
+        assert!(
+            err.to_string().contains(&format!(
+                "Unknown tool: {}. Did you mean '{}'?",
+                called, suggestion
+            )),
+            "{}",
+            err
+        );
+        let rmcp::ServiceError::McpError(err) = err else {
+            panic!("expected an MCP error, got {:?}", err);
+        };
+        assert_eq!(err.data, Some(json!({"suggestion": suggestion})));
+    }
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_ambiguous_near_miss_tool_name_is_rejected() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/role": {"get": {"description": "Get the default role"}},
+            "/roles": {"get": {"description": "List roles"}}
+        }
+    }"#;
+    let service = common::build_service(spec, "http://localhost:1", ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let err = client
+        .call_tool(call("get_role", json!({})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Unknown tool: get_role. Several tools have a similar name (role, roles); call tools/list to pick one"
+        ),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_deny_methods_hides_and_blocks_delete_tools() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            deny_methods: vec![types::HttpMethod::Delete, types::HttpMethod::Patch],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+    assert!(
+        instructions.contains("Allowed HTTP methods: GET, POST, PUT\n"),
+        "{}",
+        instructions
+    );
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "orgs",
+            "org_by_orgId",
+            "update_org_by_orgId",
+            "site_by_siteId",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+
+    let err = client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Tool disabled: delete_org_by_orgId exists but DELETE tools are disabled by --allow-methods/--deny-methods"
+        ),
+        "{}",
+        err
+    );
+
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "acme", "name": "Acme"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_mode_wins_over_allow_methods() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            allow_methods: vec![types::HttpMethod::Get, types::HttpMethod::Post],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+    assert!(
+        instructions.contains("Allowed HTTP methods: GET\n"),
+        "{}",
+        instructions
+    );
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "orgs",
+            "org_by_orgId",
+            "site_by_siteId",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_nonexistent_tool_is_unknown() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let err = client
+        .call_tool(call("launch_rocket", json!({})))
+        .await
+        .unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.contains("Unknown tool: launch_rocket"),
+        "{}",
+        message
+    );
+    assert!(!message.contains("disabled"), "{}", message);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_overrides_allow_and_block_specific_tools() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/resources/search": {"post": {"description": "Search resources"}},
+            "/site/{siteId}/regenerate": {
+                "get": {
+                    "description": "Regenerate site credentials",
+                    "parameters": [{"name": "siteId", "in": "path", "required": true}]
+                }
+            },
+            "/orgs": {"get": {"description": "List organizations"}}
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/resources/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"resources": []})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        spec,
+        &upstream.uri(),
+        ServiceConfig {
+            read_only: true,
+            read_only_overrides: [
+                ("update_resources_search".to_string(), true),
+                ("site_by_siteId_regenerate".to_string(), false),
+            ]
+            .into(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "update_resources_search",
+            "orgs",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+
+    // The allowed POST goes through despite read-only mode
+    let result = client
+        .call_tool(call("update_resources_search", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    // The blocked GET is disabled like any other write tool
+    let err = client
+        .call_tool(call("site_by_siteId_regenerate", json!({"siteId": "1"})))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Tool disabled"), "{}", err);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_colliding_names_dispatch_the_listed_endpoint() {
+    // POST /org and GET /update_org both generate `update_org`
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org": {"post": {"description": "Update the org"}},
+            "/update_org": {"get": {"description": "Read the update log"}}
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/update_org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"updates": []})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        spec,
+        &upstream.uri(),
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("update_org", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert!(common::result_text(&result).contains("updates"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_required_order_is_path_query_then_body() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/site/{siteId}": {
+                "post": {
+                    "description": "Update a site",
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true},
+                        {"name": "siteId", "in": "path", "required": true},
+                        {"name": "dryRun", "in": "query", "required": true}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "subnet": {"type": "string"},
+                                        "type": {"type": "string"}
+                                    },
+                                    "required": ["type", "name"]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let service = common::build_service(spec, "http://localhost:1", ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let schema = &tools[0].input_schema;
+
+    let required: Vec<_> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap())
+        .collect();
+    assert_eq!(required, vec!["orgId", "siteId", "dryRun", "name", "type"]);
+
+    let properties: Vec<_> = schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    // Reserved arguments come last
+    assert_eq!(
+        properties,
+        vec!["orgId", "siteId", "dryRun", "name", "subnet", "type", "_filter", "_fields"]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+/// A body accepted as JSON or form encoded
+const DUAL_CONTENT_SPEC: &str = r#"{
+    "openapi": "3.0.0",
+    "info": {"title": "Test", "version": "v1"},
+    "paths": {
+        "/org/{orgId}": {
+            "post": {
+                "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                "requestBody": {
+                    "content": {
+                        "application/json": {
+                            "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                        },
+                        "application/x-www-form-urlencoded": {
+                            "schema": {"type": "object", "properties": {"name": {"type": "string"}}}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}"#;
+
+#[tokio::test]
+async fn test_form_content_type_override_is_sent_form_encoded() {
+    let spec = DUAL_CONTENT_SPEC;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .and(header("Content-Type", "application/x-www-form-urlencoded"))
+        .and(wiremock::matchers::body_string("name=New+name"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let mut config = ServiceConfig::default();
+    config.content_type_overrides.insert(
+        "update_org_by_orgId".to_string(),
+        "application/x-www-form-urlencoded".to_string(),
+    );
+    let service = common::build_service(spec, &upstream.uri(), config);
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "my-org", "name": "New name"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_string_body_values_are_coerced_to_declared_types() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/site/{siteId}": {
+                "post": {
+                    "parameters": [{"name": "siteId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "port": {"type": "integer"},
+                                        "weight": {"type": "number"},
+                                        "enabled": {"type": "boolean"},
+                                        "ports": {"type": "array", "items": {"type": "integer"}}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/site/42"))
+        .and(wiremock::matchers::body_json(json!({
+            "name": "123",
+            "port": 8080,
+            "weight": 0.5,
+            "enabled": true,
+            "ports": [80, 443]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "update_site_by_siteId",
+            json!({
+                "siteId": "42",
+                "name": "123",
+                "port": "8080",
+                "weight": "0.5",
+                "enabled": "TRUE",
+                "ports": ["80", "443"]
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_omitted_query_param_sends_default() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/sites": {
+                "get": {
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true},
+                        {"name": "limit", "in": "query", "schema": {"type": "integer", "default": 1000}},
+                        {"name": "offset", "in": "query", "schema": {"type": "integer", "default": 0}},
+                        {"name": "filter", "in": "query", "schema": {"type": "string"}}
+                    ]
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/my-org/sites"))
+        .and(wiremock::matchers::query_param("limit", "1000"))
+        .and(wiremock::matchers::query_param("offset", "5"))
+        .and(wiremock::matchers::query_param_is_missing("filter"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "org_by_orgId_sites",
+            json!({"orgId": "my-org", "offset": 5}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_default_page_size_fills_omitted_limit() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/sites": {
+                "get": {
+                    "parameters": [
+                        {"name": "pageSize", "in": "query", "schema": {"type": "integer"}},
+                        {"name": "page", "in": "query", "schema": {"type": "integer"}}
+                    ]
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/sites"))
+        .and(wiremock::matchers::query_param("pageSize", "25"))
+        .and(wiremock::matchers::query_param_is_missing("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/sites"))
+        .and(wiremock::matchers::query_param("pageSize", "500"))
+        .and(wiremock::matchers::query_param("page", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        spec,
+        &upstream.uri(),
+        ServiceConfig {
+            default_page_size: Some(25),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    for args in [json!({}), json!({"pageSize": 500, "page": 2})] {
+        let result = client.call_tool(call("sites", args)).await.unwrap();
+        assert_eq!(
+            result.is_error,
+            Some(false),
+            "{}",
+            common::result_text(&result)
+        );
+    }
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_read_only_properties_are_excluded_and_stripped() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "post": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "id": {"type": "string", "readOnly": true},
+                                        "createdAt": {"type": "string", "readOnly": true},
+                                        "name": {"type": "string"},
+                                        "secret": {"type": "string", "writeOnly": true}
+                                    },
+                                    "required": ["id", "name"]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .and(wiremock::matchers::body_json(
+            json!({"name": "Acme", "secret": "s3cr3t"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let schema = &tools[0].input_schema;
+    let properties = schema["properties"].as_object().unwrap();
+    assert!(!properties.contains_key("id"));
+    assert!(!properties.contains_key("createdAt"));
+    assert!(properties.contains_key("secret"));
+    assert_eq!(schema["required"], json!(["orgId", "name"]));
+
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({
+                "orgId": "my-org",
+                "id": "org_123",
+                "createdAt": "2024-01-01T00:00:00Z",
+                "name": "Acme",
+                "secret": "s3cr3t"
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+    let meta = result
+        .meta
+        .expect("dropped fields should be reported in meta");
+    assert_eq!(
+        meta.get("dropped_read_only_fields"),
+        Some(&json!(["createdAt", "id"]))
+    );
+
+    client.cancel().await.unwrap();
+}
+
+fn discriminated_resource_spec() -> &'static str {
+    r##"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/resource": {
+                "put": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "oneOf": [
+                                        {"$ref": "#/components/schemas/HttpResource"},
+                                        {
+                                            "type": "object",
+                                            "properties": {
+                                                "type": {"type": "string", "enum": ["raw"]},
+                                                "name": {"type": "string"},
+                                                "protocol": {"type": "string"},
+                                                "proxyPort": {"type": "integer"}
+                                            },
+                                            "required": ["type", "name", "protocol", "proxyPort"]
+                                        }
+                                    ],
+                                    "discriminator": {
+                                        "propertyName": "type",
+                                        "mapping": {"http": "#/components/schemas/HttpResource"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "components": {
+            "schemas": {
+                "HttpResource": {
+                    "type": "object",
+                    "properties": {
+                        "type": {"type": "string"},
+                        "name": {"type": "string"},
+                        "subdomain": {"type": "string"},
+                        "http": {"type": "boolean"}
+                    },
+                    "required": ["type", "name", "subdomain"]
+                }
+            }
+        }
+    }"##
+}
+
+#[tokio::test]
+async fn test_all_read_only_body_stays_closed() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "post": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "id": {"type": "string", "readOnly": true},
+                                        "createdAt": {"type": "string", "readOnly": true}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    // Not a free-form `body` argument
+    let tools = client.list_all_tools().await.unwrap();
+    let properties = tools[0].input_schema["properties"].as_object().unwrap();
+    assert!(!properties.contains_key("body"));
+    assert!(!properties.contains_key("id"));
+
+    let err = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "my-org", "anything": true}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unexpected arguments for update_org_by_orgId: anything"),
+        "{}",
+        err
+    );
+
+    // readOnly fields are still dropped rather than rejected
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "my-org", "id": "org_123"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let requests = upstream.received_requests().await.unwrap();
+    assert!(requests[0].body.is_empty());
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_discriminated_body_is_exposed_as_one_of() {
+    let tool = common::get_tool(
+        discriminated_resource_spec(),
+        ServiceConfig::default(),
+        "create_org_by_orgId_resource",
+    )
+    .await;
+    let schema = &tool.input_schema;
+
+    assert_eq!(schema["properties"]["type"]["enum"], json!(["http", "raw"]));
+    assert_eq!(schema["required"], json!(["orgId", "type", "name"]));
+    assert_eq!(
+        schema["oneOf"],
+        json!([
+            {
+                "properties": {
+                    "type": {"type": "string", "enum": ["http"]},
+                    "name": {"type": "string"},
+                    "subdomain": {"type": "string"},
+                    "http": {"type": "boolean"}
+                },
+                "required": ["type", "name", "subdomain"]
+            },
+            {
+                "properties": {
+                    "type": {"type": "string", "enum": ["raw"]},
+                    "name": {"type": "string"},
+                    "protocol": {"type": "string"},
+                    "proxyPort": {"type": "integer"}
+                },
+                "required": ["type", "name", "protocol", "proxyPort"]
+            }
+        ])
+    );
+    assert!(tool
+        .description
+        .unwrap()
+        .contains("Body depends on 'type': type=http (name, subdomain, http); type=raw"));
+}
+
+#[tokio::test]
+async fn test_discriminated_body_is_validated_against_selected_branch() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/org/my-org/resource"))
+        .and(wiremock::matchers::body_json(
+            json!({"type": "http", "name": "web", "subdomain": "www"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/org/my-org/resource"))
+        .and(wiremock::matchers::body_json(json!({
+            "type": "raw",
+            "name": "ssh",
+            "protocol": "tcp",
+            "proxyPort": 2222
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        discriminated_resource_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    for arguments in [
+        json!({"orgId": "my-org", "type": "http", "name": "web", "subdomain": "www"}),
+        json!({
+            "orgId": "my-org",
+            "type": "raw",
+            "name": "ssh",
+            "protocol": "tcp",
+            "proxyPort": "2222"
+        }),
+    ] {
+        let result = client
+            .call_tool(call("create_org_by_orgId_resource", arguments))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.is_error,
+            Some(false),
+            "{}",
+            common::result_text(&result)
+        );
+    }
+
+    // Mixing fields from both branches never reaches the API
+    let err = client
+        .call_tool(call(
+            "create_org_by_orgId_resource",
+            json!({
+                "orgId": "my-org",
+                "type": "http",
+                "name": "web",
+                "subdomain": "www",
+                "proxyPort": 2222
+            }),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Field 'proxyPort' is not allowed when type is 'http'"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+fn targets_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/resource/{resourceId}/targets": {
+                "post": {
+                    "parameters": [{"name": "resourceId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "targets": {
+                                            "type": "array",
+                                            "items": {"type": "string"},
+                                            "minItems": 1,
+                                            "maxItems": 3,
+                                            "uniqueItems": true
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+#[tokio::test]
+async fn test_array_constraints_are_emitted_in_schema() {
+    let tool = common::get_tool(
+        targets_spec(),
+        ServiceConfig::default(),
+        "update_resource_by_resourceId_targets",
+    )
+    .await;
+
+    assert_eq!(
+        tool.input_schema["properties"]["targets"],
+        json!({"type": "array", "minItems": 1, "maxItems": 3, "uniqueItems": true})
+    );
+}
+
+#[tokio::test]
+async fn test_array_constraints_are_enforced_locally() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(targets_spec(), &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let cases = [
+        (json!([]), "at least 1 item(s) (minItems)"),
+        (json!(["a", "b", "c", "d"]), "at most 3 item(s) (maxItems)"),
+        (json!(["a", "b", "a"]), "duplicate items (uniqueItems)"),
+    ];
+    for (targets, expected) in cases {
+        let err = client
+            .call_tool(call(
+                "update_resource_by_resourceId_targets",
+                json!({"resourceId": "1", "targets": targets}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(expected), "{}", err);
+    }
+
+    let result = client
+        .call_tool(call(
+            "update_resource_by_resourceId_targets",
+            json!({"resourceId": "1", "targets": ["a", "b"]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await.unwrap();
+}
+
+fn rule_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/resource/{resourceId}/rule": {
+                "put": {
+                    "parameters": [{"name": "resourceId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "match": {"type": "string", "const": "wildcard"},
+                                        "value": {"type": "string"},
+                                        "priority": {"type": "integer", "multipleOf": 10}
+                                    },
+                                    "required": ["match", "value"]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+#[tokio::test]
+async fn test_const_and_multiple_of_are_emitted_in_schema() {
+    let tool = common::get_tool(
+        rule_spec(),
+        ServiceConfig::default(),
+        "create_resource_by_resourceId_rule",
+    )
+    .await;
+    let properties = &tool.input_schema["properties"];
+
+    assert_eq!(
+        properties["match"],
+        json!({"type": "string", "const": "wildcard"})
+    );
+    assert_eq!(
+        properties["priority"],
+        json!({"type": "integer", "multipleOf": 10.0})
+    );
+}
+
+#[tokio::test]
+async fn test_const_and_multiple_of_are_enforced_locally() {
+    let service =
+        common::build_service(rule_spec(), "http://localhost:1", ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let cases = [
+        (
+            json!({"resourceId": "1", "match": "path", "value": "*"}),
+            "Field 'match' must be \"wildcard\" (const)",
+        ),
+        (
+            json!({"resourceId": "1", "value": "*", "priority": 15}),
+            "Field 'priority' must be a multiple of 10 (multipleOf)",
+        ),
+    ];
+    for (arguments, expected) in cases {
+        let err = client
+            .call_tool(call("create_resource_by_resourceId_rule", arguments))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(expected), "{}", err);
+    }
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_required_const_is_auto_filled() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/resource/1/rule"))
+        .and(wiremock::matchers::body_json(
+            json!({"match": "wildcard", "value": "*.example.com", "priority": 20}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(rule_spec(), &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "create_resource_by_resourceId_rule",
+            json!({"resourceId": "1", "value": "*.example.com", "priority": 20}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_free_form_object_body_is_sent_whole() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/metadata": {
+                "post": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {"type": "object", "additionalProperties": true}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org/metadata"))
+        .and(wiremock::matchers::body_json(
+            json!({"team": "infra", "tier": 2, "labels": ["eu"]}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert_eq!(
+        tools[0].input_schema["properties"]["body"],
+        json!({"type": "object", "description": "Request body (free-form JSON object)"})
+    );
+
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId_metadata",
+            json!({
+                "orgId": "my-org",
+                "body": {"team": "infra", "tier": 2, "labels": ["eu"]}
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+fn nullable_auth_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/resource/{resourceId}/auth": {
+                "post": {
+                    "parameters": [
+                        {"name": "resourceId", "in": "path", "required": true},
+                        {"name": "mode", "in": "query", "schema": {"type": "string"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "password": {"type": "string", "nullable": true},
+                                        "method": {"type": "string", "enum": ["pin", "sso"], "nullable": true}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+#[tokio::test]
+async fn test_nullable_properties_are_type_unions() {
+    let tool = common::get_tool(
+        nullable_auth_spec(),
+        ServiceConfig::default(),
+        "update_resource_by_resourceId_auth",
+    )
+    .await;
+    let properties = &tool.input_schema["properties"];
+
+    assert_eq!(properties["password"], json!({"type": ["string", "null"]}));
+    assert_eq!(
+        properties["method"],
+        json!({"type": ["string", "null"], "enum": ["pin", "sso", null]})
+    );
+    assert_eq!(properties["mode"], json!({"type": "string"}));
+}
+
+#[tokio::test]
+async fn test_optional_body_is_omitted_when_empty() {
+    let body = r#"{
+        "content": {
+            "application/json": {
+                "schema": {"type": "object", "properties": {"reason": {"type": "string"}}}
+            }
+        }
+    }"#;
+    let spec = format!(
+        r#"{{
+            "openapi": "3.0.0",
+            "info": {{"title": "Test", "version": "v1"}},
+            "paths": {{
+                "/org/{{orgId}}/restart": {{"post": {{"parameters": [{{"name": "orgId", "in": "path", "required": true}}], "requestBody": {body}}}}},
+                "/org/{{orgId}}/rotate": {{"post": {{"parameters": [{{"name": "orgId", "in": "path", "required": true}}], "requestBody": {required}}}}}
+            }}
+        }}"#,
+        body = body,
+        required = body.replacen('{', r#"{"required": true,"#, 1),
+    );
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(3)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(&spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    for (tool, args) in [
+        ("update_org_by_orgId_restart", json!({"orgId": "acme"})),
+        (
+            "update_org_by_orgId_restart",
+            json!({"orgId": "acme", "reason": "drift"}),
+        ),
+        ("update_org_by_orgId_rotate", json!({"orgId": "acme"})),
+    ] {
+        let result = client.call_tool(call(tool, args)).await.unwrap();
+        assert_eq!(
+            result.is_error,
+            Some(false),
+            "{}",
+            common::result_text(&result)
+        );
+    }
+
+    let requests = upstream.received_requests().await.unwrap();
+    let bodies: Vec<_> = requests
+        .iter()
+        .map(|r| (r.url.path(), String::from_utf8_lossy(&r.body).into_owned()))
+        .collect();
+    assert_eq!(
+        bodies,
+        vec![
+            // Optional and empty: no body at all
+            ("/org/acme/restart", String::new()),
+            ("/org/acme/restart", r#"{"reason":"drift"}"#.to_string()),
+            // Required: sent even with no fields set
+            ("/org/acme/rotate", "{}".to_string()),
+        ]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_null_body_values_reach_the_api() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/resource/7/auth"))
+        .and(wiremock::matchers::query_param_is_missing("mode"))
+        .and(wiremock::matchers::body_json(
+            json!({"password": null, "method": null}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        nullable_auth_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "update_resource_by_resourceId_auth",
+            json!({"resourceId": "7", "mode": null, "password": null, "method": null}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_tool_annotations_follow_http_method() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "get": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "post": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "put": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "patch": {"parameters": [{"name": "orgId", "in": "path", "required": true}]},
+                "delete": {"parameters": [{"name": "orgId", "in": "path", "required": true}]}
+            }
+        }
+    }"#;
+
+    let service = common::build_service(spec, "http://localhost:1", ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let annotations = |name: &str| {
+        let tool = tools.iter().find(|t| t.name == name).unwrap();
+        serde_json::to_value(tool.annotations.as_ref().unwrap()).unwrap()
+    };
+
+    assert_eq!(
+        annotations("org_by_orgId"),
+        json!({"readOnlyHint": true, "openWorldHint": true})
+    );
+    assert_eq!(
+        annotations("update_org_by_orgId"),
+        json!({"readOnlyHint": false, "idempotentHint": false, "openWorldHint": true})
+    );
+    assert_eq!(
+        annotations("patch_org_by_orgId"),
+        json!({"readOnlyHint": false, "idempotentHint": false, "openWorldHint": true})
+    );
+    assert_eq!(
+        annotations("create_org_by_orgId"),
+        json!({
+            "readOnlyHint": false,
+            "destructiveHint": false,
+            "idempotentHint": true,
+            "openWorldHint": true
+        })
+    );
+    assert_eq!(
+        annotations("delete_org_by_orgId"),
+        json!({
+            "readOnlyHint": false,
+            "destructiveHint": true,
+            "idempotentHint": true,
+            "openWorldHint": true
+        })
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_tools_list_is_paginated() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            tools_page_size: Some(2),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = client
+            .list_tools(Some(rmcp::model::PaginatedRequestParam { cursor }))
+            .await
+            .unwrap();
+        pages.push(
+            page.tools
+                .iter()
+                .map(|t| t.name.to_string())
+                .collect::<Vec<_>>(),
+        );
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+
+    assert_eq!(pages.len(), 5);
+    assert!(pages.iter().all(|page| page.len() == 2));
+
+    // Every tool shows up exactly once across pages
+    let mut paged: Vec<String> = pages.into_iter().flatten().collect();
+    let mut all: Vec<String> = client
+        .list_all_tools()
+        .await
+        .unwrap()
+        .iter()
+        .map(|t| t.name.to_string())
+        .collect();
+    paged.sort();
+    all.sort();
+    assert_eq!(paged, all);
+    assert_eq!(all.len(), 10);
+
+    let err = client
+        .list_tools(Some(rmcp::model::PaginatedRequestParam {
+            cursor: Some("bogus".to_string()),
+        }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid cursor"), "{}", err);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_array_body_is_sent_as_raw_array() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/resource/{resourceId}/whitelist": {
+                "put": {
+                    "parameters": [{"name": "resourceId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "array",
+                                    "items": {"type": "integer"}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/resource/9/whitelist"))
+        .and(wiremock::matchers::body_json(json!([1, 2, 3])))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let schema = &tools[0].input_schema;
+    assert_eq!(
+        schema["properties"]["items"],
+        json!({"type": "array", "description": "Request body items (sent as a JSON array)"})
+    );
+    assert_eq!(schema["required"], json!(["resourceId", "items"]));
+
+    let result = client
+        .call_tool(call(
+            "create_resource_by_resourceId_whitelist",
+            json!({"resourceId": "9", "items": [1, "2", 3]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    let err = client
+        .call_tool(call(
+            "create_resource_by_resourceId_whitelist",
+            json!({"resourceId": "9", "items": {"id": 1}}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("must be a JSON array"), "{}", err);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_json_responses_are_returned_as_structured_content() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}": {
+                "get": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "responses": {
+                        "200": {
+                            "description": "The organization",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "orgId": {"type": "string"},
+                                            "siteCount": {"type": "integer"}
+                                        },
+                                        "required": ["orgId", "name"]
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "/orgs": {"get": {}}
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme", "siteCount": "3"})),
+        )
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"orgId": "acme"}])))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(
+        result.structured_content,
+        Some(json!({"orgId": "acme", "siteCount": "3"}))
+    );
+    // Schema mismatches are reported without failing the call
+    assert_eq!(
+        result.meta.unwrap().0["output_schema_mismatches"],
+        json!([
+            "$: missing required property 'name'",
+            "$.siteCount: expected integer"
+        ])
+    );
+
+    // Non-object responses are wrapped, since structured content is an object
+    let result = client.call_tool(call("orgs", json!({}))).await.unwrap();
+    assert_eq!(
+        result.structured_content,
+        Some(json!({"result": [{"orgId": "acme"}]}))
+    );
+    assert!(result
+        .meta
+        .unwrap()
+        .get("output_schema_mismatches")
+        .is_none());
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_empty_delete_reports_what_happened() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "delete_org_by_orgId",
+            json!({"orgId": "acme", "_confirm": true}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let expected = json!({
+        "status": "success",
+        "httpStatus": 204,
+        "method": "DELETE",
+        "path": "/org/acme",
+        "message": "Deleted org acme (HTTP 204)",
+    });
+    assert_eq!(result.structured_content, Some(expected.clone()));
+    let text: serde_json::Value = serde_json::from_str(&common::result_text(&result)).unwrap();
+    assert_eq!(text, expected);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_object_responses_with_a_result_field_are_checked_whole() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/jobs": {
+                "get": {
+                    "responses": {
+                        "200": {
+                            "description": "Job summary",
+                            "content": {
+                                "application/json": {
+                                    "schema": {
+                                        "type": "object",
+                                        "properties": {
+                                            "result": {"type": "string"},
+                                            "count": {"type": "integer"}
+                                        },
+                                        "required": ["result", "count"]
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/jobs"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"result": "done", "count": "2"})),
+        )
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client.call_tool(call("jobs", json!({}))).await.unwrap();
+    assert_eq!(
+        result.meta.unwrap().0["output_schema_mismatches"],
+        json!(["$.count: expected integer"])
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_error_results_carry_structured_status() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(
+            ResponseTemplate::new(400)
+                .insert_header("x-request-id", "req-42")
+                .set_body_json(json!({"message": "Invalid name", "field": "name"})),
+        )
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("upstream exploded"))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "acme", "name": "?"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "status": 400,
+            "method": "POST",
+            "path": "/org/acme",
+            "message": "Invalid name",
+            "details": {"message": "Invalid name", "field": "name"},
+            "request_id": "req-42"
+        }))
+    );
+    let text = common::result_text(&result);
+    assert!(
+        text.starts_with(
+            "Error: Pangolin API error (400 Bad Request): Invalid name\n\
+             Request: POST /org/acme (request ID req-42)\n\
+             Details: {"
+        ),
+        "{}",
+        text
+    );
+
+    // Non-JSON bodies become the message, without details
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "status": 500,
+            "method": "GET",
+            "path": "/org/acme",
+            "message": "upstream exploded",
+            "details": null,
+            "request_id": null
+        }))
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_auth_and_not_found_statuses_are_structured_results() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/missing"))
+        .respond_with(
+            ResponseTemplate::new(404).set_body_json(json!({"message": "Organization not found"})),
+        )
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({"message": "Invalid key"})))
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/site/7"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(json!({"message": "Forbidden"})))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "missing"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "status": 404,
+            "method": "GET",
+            "path": "/org/missing",
+            "message": "Organization not found",
+            "details": {"message": "Organization not found"},
+            "request_id": null,
+            "kind": "not_found"
+        }))
+    );
+    assert!(common::result_text(&result)
+        .starts_with("Error: Pangolin API error (404 Not Found): Organization not found\n"));
+
+    // A rejected key also points the operator at PANGOLIN_API_KEY
+    let result = client.call_tool(call("orgs", json!({}))).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.structured_content,
+        Some(json!({
+            "status": 401,
+            "method": "GET",
+            "path": "/orgs",
+            "message": "Invalid key",
+            "details": {"message": "Invalid key"},
+            "request_id": null,
+            "kind": "unauthorized"
+        }))
+    );
+    assert!(common::result_text(&result).starts_with(
+        "Error: Pangolin API error (401 Unauthorized): Invalid key (check PANGOLIN_API_KEY)\n"
+    ));
+
+    let result = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let content = result.structured_content.unwrap();
+    assert_eq!(content["status"], 403);
+    assert_eq!(content["kind"], "forbidden");
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_closed_body_rejects_undeclared_fields() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/role/{roleId}": {
+                "post": {
+                    "parameters": [{"name": "roleId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "additionalProperties": false,
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "description": {"type": "string"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/role/7"))
+        .and(wiremock::matchers::body_json(json!({"name": "Ops"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"roleId": 7})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let err = client
+        .call_tool(call(
+            "update_role_by_roleId",
+            json!({"roleId": "7", "name": "Ops", "isAdmin": true, "orgId": "acme"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Unexpected arguments for update_role_by_roleId: isAdmin, orgId"),
+        "{}",
+        err
+    );
+
+    let result = client
+        .call_tool(call(
+            "update_role_by_roleId",
+            json!({"roleId": "7", "name": "Ops"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_body_fields_colliding_with_params_are_aliased() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/target/{id}": {
+                "post": {
+                    "parameters": [{"name": "id", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "id": {"type": "integer", "description": "New target ID"},
+                                        "ip": {"type": "string"}
+                                    },
+                                    "required": ["id"]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/target/7"))
+        .and(wiremock::matchers::body_json(
+            json!({"id": 42, "ip": "10.0.0.1"}),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"targetId": 42})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "update_target_by_id").await;
+    let schema = &tool.input_schema;
+    assert_eq!(schema["properties"]["id"]["type"], "string");
+    assert_eq!(schema["properties"]["body_id"]["type"], "integer");
+    assert_eq!(
+        schema["properties"]["body_id"]["description"],
+        "New target ID (body field 'id')"
+    );
+    assert_eq!(schema["required"], json!(["id", "body_id"]));
+
+    let service = common::build_service(spec, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "update_target_by_id",
+            json!({"id": "7", "body_id": "42", "ip": "10.0.0.1"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await.unwrap();
+}
+
+fn validated_target_spec() -> &'static str {
+    r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/site/{siteId}/target": {
+                "put": {
+                    "parameters": [
+                        {"name": "siteId", "in": "path", "required": true, "schema": {"type": "integer"}},
+                        {"name": "mode", "in": "query", "schema": {"type": "string", "enum": ["strict", "lenient"]}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string", "minLength": 3, "maxLength": 10, "pattern": "^[a-z-]+$"},
+                                        "port": {"type": "integer", "minimum": 1, "maximum": 65535},
+                                        "protocol": {"type": "string", "enum": ["tcp", "udp"]},
+                                        "enabled": {"type": "boolean"}
+                                    },
+                                    "required": ["name", "port"]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#
+}
+
+/// Violation messages attached to an `invalid_params` error
+fn violations(err: rmcp::ServiceError) -> Vec<String> {
+    let rmcp::ServiceError::McpError(error) = err else {
+        panic!("Expected an MCP error, got {:?}", err);
+    };
+    error.data.unwrap()["violations"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["message"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_empty_path_parameters_are_rejected() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .expect(0)
+        .mount(&upstream)
+        .await;
+
+    for skip_arg_validation in [false, true] {
+        let service = common::build_service(
+            common::get_test_swagger_spec(),
+            &upstream.uri(),
+            ServiceConfig {
+                skip_arg_validation,
+                ..Default::default()
+            },
+        );
+        let client = common::connect(service).await;
+
+        // "/org/{orgId}" with an empty orgId would otherwise call "/org/"
+        let err = client
+            .call_tool(call("org_by_orgId", json!({"orgId": ""})))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Path parameter 'orgId' must not be empty"),
+            "{}",
+            err
+        );
+
+        client.cancel().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_arguments_are_validated_against_input_schema() {
+    let service = common::build_service(
+        validated_target_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let cases = [
+        (
+            json!({"name": "web"}),
+            vec![
+                "Missing required path parameter: siteId (expected integer)",
+                "Missing required body field: port (expected integer)",
+            ],
+        ),
+        (
+            json!({"siteId": "abc", "name": "web", "port": "eighty", "enabled": "yes"}),
+            vec![
+                "Parameter 'siteId' must be an integer, got \"abc\"",
+                "Field 'port' must be an integer, got \"eighty\"",
+                "Field 'enabled' must be a boolean, got \"yes\"",
+            ],
+        ),
+        (
+            json!({"siteId": 1, "mode": "loose", "name": "web", "port": 80, "protocol": "http"}),
+            vec![
+                "Parameter 'mode' must be one of: strict, lenient; got \"loose\"",
+                "Field 'protocol' must be one of: tcp, udp; got \"http\"",
+            ],
+        ),
+        (
+            json!({"siteId": 1, "name": "web", "port": 0}),
+            vec!["Field 'port' must be at least 1 (minimum), got 0"],
+        ),
+        (
+            json!({"siteId": 1, "name": "web", "port": "70000"}),
+            vec!["Field 'port' must be at most 65535 (maximum), got 70000"],
+        ),
+        (
+            json!({"siteId": 1, "name": "Web_Server_01", "port": 80}),
+            vec![
+                "Field 'name' must be at most 10 character(s) long (maxLength), got 13",
+                "Field 'name' must match ^[a-z-]+$ (pattern), got \"Web_Server_01\"",
+            ],
+        ),
+    ];
+    for (arguments, expected) in cases {
+        let err = client
+            .call_tool(call("create_site_by_siteId_target", arguments.clone()))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid arguments for create_site_by_siteId_target:\n- "),
+            "{}",
+            err
+        );
+        assert_eq!(violations(err), expected, "{}", arguments);
+    }
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_argument_validation_can_be_skipped() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/site/1/target"))
+        .and(wiremock::matchers::body_json(
+            json!({"name": "Web_Server_01", "port": 0}),
+        ))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({"message": "bad port"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        validated_target_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            skip_arg_validation: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "create_site_by_siteId_target",
+            json!({"siteId": 1, "name": "Web_Server_01", "port": 0}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(common::result_text(&result).contains("bad port"));
+
+    client.cancel().await.unwrap();
+}
+
+/// Client handler counting `tools/list_changed` notifications
+#[derive(Clone, Default)]
+struct ListChangedCounter(Arc<AtomicUsize>);
+
+impl ClientHandler for ListChangedCounter {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_reload_sends_one_debounced_list_changed_notification() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let handle = service.clone();
+    let counter = ListChangedCounter::default();
+    let client = common::connect_with(service, counter.clone()).await;
+
+    let info = client.peer_info().unwrap();
+    assert_eq!(
+        info.capabilities.tools.as_ref().unwrap().list_changed,
+        Some(true)
+    );
+    assert_eq!(client.list_all_tools().await.unwrap().len(), 10);
+
+    let reloaded = swagger::SwaggerSpec::from_json(
+        r#"{
+            "openapi": "3.0.0",
+            "info": {"title": "Test", "version": "v2"},
+            "paths": {"/orgs": {"get": {}}}
+        }"#,
+    )
+    .unwrap();
+    // Changes in quick succession collapse into a single notification
+    handle.reload(&reloaded).unwrap();
+    handle.reload(&reloaded).unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "orgs",
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_keeps_the_current_spec_when_checks_fail() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            allow_tools: vec!["org_by_orgId".to_string()],
+            ..Default::default()
+        },
+    );
+    let handle = service.clone();
+    let client = common::connect(service).await;
+
+    let reloaded = swagger::SwaggerSpec::from_json(
+        r#"{
+            "openapi": "3.0.0",
+            "info": {"title": "Test", "version": "v2"},
+            "paths": {"/orgs": {"get": {}}}
+        }"#,
+    )
+    .unwrap();
+    let err = handle.reload(&reloaded).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--allow-tools matches none of the 1 tools generated from the spec"
+    );
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(tools.iter().any(|t| t.name == "org_by_orgId"));
+    client.cancel().await.unwrap();
+
+    // Workflow steps must still name tools of the new spec
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            workflows: workflows::parse(WORKFLOWS).unwrap(),
+            ..Default::default()
+        },
+    );
+    let err = service.reload(&reloaded).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Workflow 'rename_org_and_get_site' step 'rename' uses unknown tool 'update_org_by_orgId'"
+    );
+    assert!(service.tools().iter().any(|t| t.name == "site_by_siteId"));
+}
+
+/// Client handler recording progress notification messages
+#[derive(Clone, Default)]
+struct ProgressRecorder(Arc<std::sync::Mutex<Vec<String>>>);
+
+impl ClientHandler for ProgressRecorder {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.0
+            .lock()
+            .unwrap()
+            .push(params.message.unwrap_or_default());
+    }
+}
+
+#[tokio::test]
+async fn test_slow_calls_send_progress_notifications() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([]))
+                .set_delay(Duration::from_millis(350)),
+        )
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            progress_interval: Some(Duration::from_millis(100)),
+            ..Default::default()
+        },
+    );
+    let recorder = ProgressRecorder::default();
+    let client = common::connect_with(service, recorder.clone()).await;
+
+    let result = client.call_tool(call("orgs", json!({}))).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let messages = recorder.0.lock().unwrap().clone();
+    assert!(!messages.is_empty(), "expected progress notifications");
+    assert!(
+        messages[0].starts_with("Waiting for Pangolin API..."),
+        "{:?}",
+        messages
+    );
+
+    // Ticking stops once the call has resolved
+    let count = messages.len();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(recorder.0.lock().unwrap().len(), count);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_cancelled_call_stops_waiting_for_the_api() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([]))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            progress_interval: Some(Duration::from_millis(50)),
+            ..Default::default()
+        },
+    );
+    let recorder = ProgressRecorder::default();
+    let client = common::connect_with(service, recorder.clone()).await;
+
+    // The client cancels the request once its timeout elapses
+    let handle = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(CallToolRequest::new(call("orgs", json!({})))),
+            PeerRequestOptions {
+                timeout: Some(Duration::from_millis(200)),
+                meta: None,
+            },
+        )
+        .await
+        .unwrap();
+    let started = std::time::Instant::now();
+    assert!(handle.await_response().await.is_err());
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    // The handler stopped: no more progress ticks while the mock still delays
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let count = recorder.0.lock().unwrap().len();
+    assert!(count > 0);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(recorder.0.lock().unwrap().len(), count);
+
+    client.cancel().await.unwrap();
+}
+
+/// Client handler recording `notifications/message` payloads
+#[derive(Clone, Default)]
+struct LogRecorder(Arc<std::sync::Mutex<Vec<LoggingMessageNotificationParam>>>);
+
+impl ClientHandler for LogRecorder {
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.0.lock().unwrap().push(params);
+    }
+}
+
+#[tokio::test]
+async fn test_log_events_are_forwarded_after_set_level() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(500).set_body_json(json!({"message": "bad key test-api-key"})),
+        )
+        .mount(&upstream)
+        .await;
+
+    let forwarder = Arc::new(mcp_logging::LogForwarder::default());
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(forwarder.layer()));
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            log_forwarder: forwarder,
+            ..Default::default()
+        },
+    );
+    let recorder = LogRecorder::default();
+    let client = common::connect_with(service, recorder.clone()).await;
+    assert!(client.peer_info().unwrap().capabilities.logging.is_some());
+
+    // Nothing is forwarded before the client picks a level
+    client.call_tool(call("orgs", json!({}))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(recorder.0.lock().unwrap().is_empty());
+
+    client
+        .set_level(SetLevelRequestParam {
+            level: LoggingLevel::Debug,
+        })
+        .await
+        .unwrap();
+    client.call_tool(call("orgs", json!({}))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let records = recorder.0.lock().unwrap().clone();
+    let calling = records
+        .iter()
+        .find(|r| r.data["message"] == "Calling tool: orgs")
+        .expect("tool call log record");
+    assert_eq!(calling.level, LoggingLevel::Debug);
+    assert_eq!(calling.data["tool"], "orgs");
+    assert!(calling.logger.as_deref().unwrap().ends_with("::service"));
+
+    let failed = records
+        .iter()
+        .find(|r| r.level == LoggingLevel::Warning)
+        .expect("tool failure log record");
+    assert_eq!(failed.data["tool"], "orgs");
+    assert_eq!(failed.data["status"], 500);
+
+    let all = serde_json::to_string(&records).unwrap();
+    assert!(!all.contains("test-api-key"), "{}", all);
+    assert!(all.contains("[REDACTED]"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_keys_from_the_key_command_are_masked_in_forwarded_logs() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(500).set_body_json(json!({"message": "bad key cmd-key-123"})),
+        )
+        .mount(&upstream)
+        .await;
+
+    let forwarder = Arc::new(mcp_logging::LogForwarder::default());
+    let _guard =
+        tracing::subscriber::set_default(tracing_subscriber::registry().with(forwarder.layer()));
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            log_forwarder: forwarder,
+            client: pangolin_client::ClientConfig {
+                api_key_command: Some("echo cmd-key-123".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+    let recorder = LogRecorder::default();
+    let client = common::connect_with(service, recorder.clone()).await;
+    client
+        .set_level(SetLevelRequestParam {
+            level: LoggingLevel::Debug,
+        })
+        .await
+        .unwrap();
+    client.call_tool(call("orgs", json!({}))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let all = serde_json::to_string(&*recorder.0.lock().unwrap()).unwrap();
+    assert!(all.contains("bad key [REDACTED]"), "{}", all);
+    assert!(!all.contains("cmd-key-123"), "{}", all);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_max_concurrency_queues_excess_calls() {
+    // A bare HTTP server counting requests in flight (wiremock responders
+    // cannot observe overlap)
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let upstream = format!("http://{}", listener.local_addr().unwrap());
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let served = Arc::new(AtomicUsize::new(0));
+    {
+        let (in_flight, peak, served) = (in_flight.clone(), peak.clone(), served.clone());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let (in_flight, peak, served) = (in_flight.clone(), peak.clone(), served.clone());
+                tokio::spawn(async move {
+                    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut buf).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => request.extend_from_slice(&buf[..n]),
+                        }
+                    }
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    served.fetch_add(1, Ordering::SeqCst);
+                    let _ = socket
+                        .write_all(
+                            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                              Content-Length: 2\r\nConnection: close\r\n\r\n{}",
+                        )
+                        .await;
+                });
+            }
+        });
+    }
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream,
+        ServiceConfig {
+            max_concurrency: Some(2),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let mut calls = tokio::task::JoinSet::new();
+    for i in 0..6 {
+        let peer = client.peer().clone();
+        calls.spawn(async move {
+            peer.call_tool(call("org_by_orgId", json!({"orgId": format!("org-{}", i)})))
+                .await
+        });
+    }
+    while let Some(result) = calls.join_next().await {
+        assert_eq!(result.unwrap().unwrap().is_error, Some(false));
+    }
+
+    assert_eq!(served.load(Ordering::SeqCst), 6);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+
+    client.cancel().await.unwrap();
+}
+
+/// Client handler answering confirmation requests with a fixed decision and
+/// recording the prompts
+#[derive(Clone)]
+struct Confirmer {
+    action: ElicitationAction,
+    confirm: bool,
+    prompts: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+impl Confirmer {
+    fn new(action: ElicitationAction, confirm: bool) -> Self {
+        Self {
+            action,
+            confirm,
+            prompts: Arc::default(),
+        }
+    }
+}
+
+impl ClientHandler for Confirmer {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            capabilities: ClientCapabilities::builder().enable_elicitation().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        _context: rmcp::service::RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
+        self.prompts.lock().unwrap().push(request.message);
+        Ok(CreateElicitationResult {
+            action: self.action.clone(),
+            content: Some(json!({"confirm": self.confirm})),
+        })
+    }
+}
+
+/// Upstream expecting `count` deletions of organization `acme`
+async fn delete_upstream(count: u64) -> MockServer {
+    let upstream = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"deleted": true})))
+        .expect(count)
+        .mount(&upstream)
+        .await;
+    upstream
+}
+
+#[tokio::test]
+async fn test_delete_runs_once_the_user_confirms() {
+    let upstream = delete_upstream(1).await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let confirmer = Confirmer::new(ElicitationAction::Accept, true);
+    let client = common::connect_with(service, confirmer.clone()).await;
+
+    let result = client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let prompts = confirmer.prompts.lock().unwrap().clone();
+    assert_eq!(
+        prompts,
+        vec![format!(
+            "Allow delete_org_by_orgId to run DELETE {}/org/acme?\nArguments: orgId = \"acme\"",
+            upstream.uri()
+        )]
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_rejected_confirmation_skips_the_call() {
+    for (action, confirm, decision) in [
+        (ElicitationAction::Decline, false, "declined"),
+        (ElicitationAction::Accept, false, "declined"),
+        (ElicitationAction::Cancel, false, "cancelled"),
+    ] {
+        let upstream = delete_upstream(0).await;
+        let service = common::build_service(
+            common::get_test_swagger_spec(),
+            &upstream.uri(),
+            ServiceConfig::default(),
+        );
+        let client = common::connect_with(service, Confirmer::new(action, confirm)).await;
+
+        // The `_confirm` argument does not bypass a client that can ask
+        let err = client
+            .call_tool(call(
+                "delete_org_by_orgId",
+                json!({"orgId": "acme", "_confirm": true}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!(
+                "delete_org_by_orgId (DELETE {}/org/acme) was not run: confirmation {}",
+                upstream.uri(),
+                decision
+            )),
+            "{}",
+            err
+        );
+
+        client.cancel().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_confirmation_falls_back_to_confirm_argument() {
+    let upstream = delete_upstream(1).await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            confirm_tools: vec!["update_org_by_orgId".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let confirmable: Vec<_> = tools
+        .iter()
+        .filter(|t| t.input_schema["properties"].get("_confirm").is_some())
+        .map(|t| t.name.as_ref())
+        .collect();
+    assert_eq!(
+        confirmable,
+        vec!["update_org_by_orgId", "delete_org_by_orgId"]
+    );
+
+    let err = client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "this client cannot prompt the user for it. Check with the user, then call it again with \"_confirm\": true"
+        ),
+        "{}",
+        err
+    );
+
+    let result = client
+        .call_tool(call(
+            "delete_org_by_orgId",
+            json!({"orgId": "acme", "_confirm": true}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_require_delete_confirmation_demands_confirm_true() {
+    let upstream = delete_upstream(1).await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            require_delete_confirmation: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let delete = tools
+        .iter()
+        .find(|t| t.name == "delete_org_by_orgId")
+        .unwrap();
+    assert_eq!(
+        delete.input_schema["properties"]["confirm"],
+        json!({"type": "boolean", "description": "Must be true to proceed"})
+    );
+    assert!(delete.input_schema["required"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("confirm")));
+    let update = tools
+        .iter()
+        .find(|t| t.name == "update_org_by_orgId")
+        .unwrap();
+    assert!(update.input_schema["properties"].get("confirm").is_none());
+
+    for (args, got) in [
+        (json!({"orgId": "acme"}), "no value"),
+        (json!({"orgId": "acme", "confirm": false}), "false"),
+        (json!({"orgId": "acme", "confirm": "true"}), "\"true\""),
+    ] {
+        let err = client
+            .call_tool(call("delete_org_by_orgId", args))
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(&format!(
+                "delete_org_by_orgId deletes data and only runs with \"confirm\": true (got {})",
+                got
+            )),
+            "{}",
+            err
+        );
+    }
+
+    // `confirm: true` also stands in for `_confirm` on a client that cannot ask
+    let result = client
+        .call_tool(call(
+            "delete_org_by_orgId",
+            json!({"orgId": "acme", "confirm": true}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let requests = upstream.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.path(), "/org/acme");
+    assert_eq!(requests[0].url.query(), None);
+    assert!(requests[0].body.is_empty());
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_dry_run_renders_request_without_sending_it() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/site": {
+                "post": {
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                        {"name": "notify", "in": "query", "schema": {"type": "boolean"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {"name": {"type": "string"}, "port": {"type": "integer"}}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&upstream)
+        .await;
+
+    for (config, extra) in [
+        (
+            ServiceConfig {
+                dry_run: true,
+                ..Default::default()
+            },
+            json!({}),
+        ),
+        (ServiceConfig::default(), json!({"_dry_run": true})),
+    ] {
+        let service = common::build_service(spec, &upstream.uri(), config);
+        let client = common::connect(service).await;
+
+        let mut arguments =
+            json!({"orgId": "acme", "notify": true, "name": "edge", "port": "8080"});
+        arguments
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        let result = client
+            .call_tool(call("update_org_by_orgId_site", arguments))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(false));
+
+        let url = format!("{}/org/acme/site?notify=true", upstream.uri());
+        let text = common::result_text(&result);
+        assert!(
+            text.starts_with(&format!("DRY RUN - NOT EXECUTED: POST {}\n", url)),
+            "{}",
+            text
+        );
+        assert_eq!(
+            result.structured_content,
+            Some(json!({
+                "dryRun": true,
+                "executed": false,
+                "request": {
+                    "method": "POST",
+                    "url": url,
+                    "headers": {
+                        "Authorization": "[REDACTED]",
+                        "content-type": "application/json"
+                    },
+                    "body": {"name": "edge", "port": 8080}
+                }
+            }))
+        );
+        assert!(!text.contains("test-api-key"));
+
+        client.cancel().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_oversized_response_is_truncated_at_array_elements() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/resources": {
+                "get": {
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                        {"name": "limit", "in": "query", "schema": {"type": "integer"}},
+                        {"name": "offset", "in": "query", "schema": {"type": "integer"}}
+                    ]
+                }
+            }
+        }
+    }"#;
+
+    let resources: Vec<_> = (0..342)
+        .map(|i| json!({"resourceId": i, "name": format!("resource-{}", i), "ssl": true}))
+        .collect();
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme/resources"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {"resources": resources, "orgId": "acme"},
+            "success": true
+        })))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        spec,
+        &upstream.uri(),
+        ServiceConfig {
+            max_response_bytes: Some(8 * 1024),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("org_by_orgId_resources", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    // The JSON stays valid and keeps its scalar fields; only trailing
+    // resources were dropped
+    let text = result.content[0].as_text().unwrap().text.clone();
+    assert!(text.len() <= 8 * 1024, "{} bytes", text.len());
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(body["success"], json!(true));
+    assert_eq!(body["data"]["orgId"], json!("acme"));
+    let shown = body["data"]["resources"].as_array().unwrap().len();
+    assert!(shown > 0 && shown < 342, "{}", shown);
+    assert_eq!(body["data"]["resources"][0]["name"], json!("resource-0"));
+
+    let note = result.content[1].as_text().unwrap().text.clone();
+    assert!(
+        note.starts_with(&format!(
+            "truncated: showing {} of 342 items of $.data.resources (",
+            shown
+        )),
+        "{}",
+        note
+    );
+    assert!(note.ends_with("refine with limit/offset"), "{}", note);
+
+    let meta = result.meta.unwrap();
+    assert_eq!(
+        meta.get("truncated").unwrap()["arrays"],
+        json!([{"path": "$.data.resources", "shown": shown, "total": 342}])
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_long_strings_are_shortened_once_arrays_are_empty() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "orgId": "acme",
+            "notes": "x".repeat(4000),
+            "tags": ["a", "b"]
+        })))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            max_response_bytes: Some(1024),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    let text = result.content[0].as_text().unwrap().text.clone();
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(body["orgId"], json!("acme"));
+    assert_eq!(body["tags"], json!([]));
+    assert!(body["notes"].as_str().unwrap().ends_with('…'));
+    assert!(text.len() <= 1024, "{} bytes", text.len());
+
+    let note = result.content[1].as_text().unwrap().text.clone();
+    assert!(
+        note.contains("showing 0 of 2 items of $.tags, 1 long strings shortened"),
+        "{}",
+        note
+    );
+    assert!(
+        note.ends_with("narrow the request to see the rest"),
+        "{}",
+        note
+    );
+
+    client.cancel().await.unwrap();
+}
+
+/// Upstream answering `GET /org/acme` with a site listing
+async fn sites_upstream(expected_calls: u64) -> MockServer {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": {
+                "orgId": "acme",
+                "sites": [
+                    {"siteId": 1, "niceId": "brave-fox"},
+                    {"siteId": 2, "niceId": "calm-owl"}
+                ]
+            }
+        })))
+        .expect(expected_calls)
+        .mount(&upstream)
+        .await;
+    upstream
+}
+
+#[tokio::test]
+async fn test_filter_selects_part_of_the_response() {
+    let upstream = sites_upstream(3).await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(tools
+        .iter()
+        .filter(|t| ![
+            "get_server_stats",
+            "search_endpoints",
+            "describe_endpoint",
+            "batch_call",
+            "pangolin_get_openapi"
+        ]
+        .contains(&t.name.as_ref()))
+        .all(|t| t.input_schema["properties"].get("_filter").is_some()));
+
+    for (filter, expected) in [
+        ("/data/sites/0/niceId", json!("brave-fox")),
+        (
+            "$.data.sites[1]",
+            json!({"siteId": 2, "niceId": "calm-owl"}),
+        ),
+        ("$.data.sites[*].niceId", json!(["brave-fox", "calm-owl"])),
+    ] {
+        let result = client
+            .call_tool(call(
+                "org_by_orgId",
+                json!({"orgId": "acme", "_filter": filter}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(false));
+        let text = common::result_text(&result);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&text).unwrap(),
+            expected,
+            "{}",
+            filter
+        );
+
+        let filtered = &result.meta.unwrap()["filtered"];
+        assert_eq!(filtered["filter"], json!(filter));
+        assert!(filtered["original_bytes"].as_u64().unwrap() > text.len() as u64);
+    }
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_filter_is_rejected_before_calling_the_api() {
+    let upstream = sites_upstream(1).await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    for (filter, message) in [
+        (
+            "data.niceId",
+            "Invalid _filter 'data.niceId': expected a JSON pointer (/data/niceId) or a JSONPath ($.data.niceId)",
+        ),
+        ("$.data[?(@.x)]", "Invalid _filter '$.data[?(@.x)]': Unsupported JSONPath selector"),
+    ] {
+        let err = client
+            .call_tool(call(
+                "org_by_orgId",
+                json!({"orgId": "acme", "_filter": filter}),
+            ))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(message), "{}", err);
+    }
+
+    // A valid expression that matches nothing reports what is there
+    let result = client
+        .call_tool(call(
+            "org_by_orgId",
+            json!({"orgId": "acme", "_filter": "/site"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        common::result_text(&result),
+        "Error: _filter '/site' matched nothing in the response (top-level keys: data)"
+    );
+
+    client.cancel().await.unwrap();
+}
+
+async fn project(
+    response: serde_json::Value,
+    fields: serde_json::Value,
+) -> rmcp::model::CallToolResult {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "org_by_orgId",
+            json!({"orgId": "acme", "_fields": fields}),
+        ))
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+    result
+}
+
+#[tokio::test]
+async fn test_fields_projects_object_responses() {
+    let result = project(
+        json!({"name": "Acme", "orgId": "acme", "subnet": "100.90.128.0/24", "createdAt": 1}),
+        json!(["name", "orgId", "missing"]),
+    )
+    .await;
+
+    assert_eq!(
+        result.structured_content.unwrap(),
+        json!({"name": "Acme", "orgId": "acme"})
+    );
+    assert_eq!(
+        result.meta.unwrap()["projected"],
+        json!({"fields": ["name", "orgId", "missing"], "dropped_keys": 2})
+    );
+}
+
+#[tokio::test]
+async fn test_fields_projects_every_element_of_array_responses() {
+    let result = project(
+        json!([
+            {"resourceId": 1, "name": "web", "fullDomain": "web.example.com", "ssl": true},
+            {"resourceId": 2, "name": "api", "fullDomain": "api.example.com", "ssl": false}
+        ]),
+        json!(["name", "resourceId", "fullDomain"]),
+    )
+    .await;
+
+    assert_eq!(
+        result.structured_content.unwrap()["result"],
+        json!([
+            {"resourceId": 1, "name": "web", "fullDomain": "web.example.com"},
+            {"resourceId": 2, "name": "api", "fullDomain": "api.example.com"}
+        ])
+    );
+    assert_eq!(result.meta.unwrap()["projected"]["dropped_keys"], 2);
+}
+
+#[tokio::test]
+async fn test_fields_projects_nested_keys() {
+    let result = project(
+        json!([
+            {"resourceId": 1, "site": {"name": "hq", "siteId": 7}, "ssl": true},
+            {"resourceId": 2, "site": {"name": "lab", "siteId": 8}, "ssl": false}
+        ]),
+        json!(["resourceId", "site.name", "ssl.enabled"]),
+    )
+    .await;
+
+    // A nested field under a scalar keeps the scalar
+    assert_eq!(
+        result.structured_content.unwrap()["result"],
+        json!([
+            {"resourceId": 1, "site": {"name": "hq"}, "ssl": true},
+            {"resourceId": 2, "site": {"name": "lab"}, "ssl": false}
+        ])
+    );
+    assert_eq!(result.meta.unwrap()["projected"]["dropped_keys"], 2);
+}
+
+/// Audit log lines, waiting for the background writer to catch up
+async fn audit_lines(path: &std::path::Path, expected: usize) -> Vec<serde_json::Value> {
+    for _ in 0..100 {
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        if text.lines().count() >= expected {
+            return text
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("audit log never reached {} lines", expected);
+}
+
+#[tokio::test]
+async fn test_audit_log_records_every_call() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(json!({"orgId": "acme"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let log = std::env::temp_dir().join(format!("mcp-pangolin-audit-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&log);
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            read_only: true,
+            read_only_overrides: std::collections::HashMap::from([(
+                "update_org_by_orgId".to_string(),
+                true,
+            )]),
+            audit_log: Some(log.clone()),
+            audit_redact_fields: vec!["NAME".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "acme", "name": "Acme Corp"}),
+        ))
+        .await
+        .unwrap();
+    client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap_err();
+    client
+        .call_tool(call(
+            "org_by_orgId",
+            json!({"orgId": "acme", "_dry_run": true}),
+        ))
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+
+    let lines = audit_lines(&log, 4).await;
+    std::fs::remove_file(&log).unwrap();
+    for line in &lines {
+        assert!(chrono::DateTime::parse_from_rfc3339(line["timestamp"].as_str().unwrap()).is_ok());
+        assert!(line["duration_ms"].is_u64());
+    }
+
+    let summary: Vec<_> = lines
+        .iter()
+        .map(|l| {
+            json!([
+                l["tool"],
+                l["method"],
+                l["path"],
+                l["status"],
+                l["blocked"],
+                l["arguments"]
+            ])
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            json!(["org_by_orgId", "GET", "/org/acme", 200, null, {"orgId": "acme"}]),
+            json!(["update_org_by_orgId", "POST", "/org/acme", 201, null, {"orgId": "acme", "name": "***"}]),
+            json!(["delete_org_by_orgId", "DELETE", null, null, "read_only", {"orgId": "acme"}]),
+            json!(["org_by_orgId", "GET", "/org/acme", null, "dry_run", {"orgId": "acme", "_dry_run": true}]),
+        ]
+    );
+    assert!(lines[2]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Tool disabled: delete_org_by_orgId exists but"));
+    assert_eq!(lines[0]["error"], json!(null));
+}
+
+#[test]
+fn test_unwritable_audit_log_is_rejected_at_startup() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let spec = swagger::SwaggerSpec::from_json(common::get_test_swagger_spec()).unwrap();
+    let err = service::PangolinService::new(
+        spec,
+        "key".to_string(),
+        "http://localhost".to_string(),
+        ServiceConfig {
+            audit_log: Some("/nonexistent/dir/audit.jsonl".into()),
+            ..Default::default()
+        },
+    )
+    .err()
+    .unwrap();
+    assert!(err
+        .to_string()
+        .starts_with("Failed to open audit log /nonexistent/dir/audit.jsonl"));
+}
+
+#[tokio::test]
+async fn test_server_stats_count_calls_per_tool() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/org/gone"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({"message": "Gone"})))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    // Listed despite read-only mode, and read-only itself
+    let tools = client.list_all_tools().await.unwrap();
+    let stats_tool = tools.iter().find(|t| t.name == "get_server_stats").unwrap();
+    assert_eq!(
+        stats_tool.annotations.as_ref().unwrap().read_only_hint,
+        Some(true)
+    );
+
+    for org in ["acme", "acme", "gone"] {
+        client
+            .call_tool(call("org_by_orgId", json!({"orgId": org})))
+            .await
+            .unwrap();
+    }
+    client
+        .call_tool(call("delete_org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap_err();
+    client
+        .call_tool(call("no_such_tool", json!({})))
+        .await
+        .unwrap_err();
+
+    let result = client
+        .call_tool(call("get_server_stats", json!({})))
+        .await
+        .unwrap();
+    client.cancel().await.unwrap();
+
+    let stats = result.structured_content.unwrap();
+    assert_eq!(stats["mode"], "read-only");
+    assert_eq!(stats["endpoints"], 5);
+    assert_eq!(stats["availableEndpoints"], 3);
+    assert!(stats["uptimeSecs"].is_u64());
+
+    // Unknown tools and the stats tool itself are not tracked
+    let tools = stats["tools"].as_object().unwrap();
+    assert_eq!(
+        tools.keys().collect::<Vec<_>>(),
+        vec!["delete_org_by_orgId", "org_by_orgId"]
+    );
+    let org = &tools["org_by_orgId"];
+    assert_eq!(
+        (&org["calls"], &org["errors"], &org["blockedByReadOnly"]),
+        (&json!(3), &json!(1), &json!(0))
+    );
+    let histogram = org["latencyMs"]["histogram"].as_array().unwrap();
+    assert_eq!(histogram.len(), 9);
+    assert_eq!(histogram.last().unwrap()["le"], json!(null));
+    assert_eq!(
+        histogram
+            .iter()
+            .map(|b| b["count"].as_u64().unwrap())
+            .sum::<u64>(),
+        3
+    );
+    let delete = &tools["delete_org_by_orgId"];
+    assert_eq!(
+        (
+            &delete["calls"],
+            &delete["errors"],
+            &delete["blockedByReadOnly"]
+        ),
+        (&json!(1), &json!(1), &json!(1))
+    );
+}
+
+async fn search(
+    client: &rmcp::service::RunningService<RoleClient, ()>,
+    args: serde_json::Value,
+) -> serde_json::Value {
+    client
+        .call_tool(call("search_endpoints", args))
+        .await
+        .unwrap()
+        .structured_content
+        .unwrap()
+}
+
+fn result_names(found: &serde_json::Value) -> Vec<&str> {
+    found["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_endpoints_ranks_matches() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let found = search(&client, json!({"query": "delete organization"})).await;
+    assert_eq!(
+        result_names(&found),
+        vec![
+            "delete_org_by_orgId",
+            "org_by_orgId",
+            "update_org_by_orgId",
+            "orgs"
+        ]
+    );
+    assert_eq!(found["total"], 4);
+    assert_eq!(
+        found["results"][0],
+        json!({
+            "name": "delete_org_by_orgId",
+            "method": "DELETE",
+            "path": "/org/{orgId}",
+            "description": "Delete an organization",
+            "tags": ["Organization"],
+            "score": found["results"][0]["score"],
+        })
+    );
+
+    let found = search(&client, json!({"query": "site"})).await;
+    assert_eq!(result_names(&found), vec!["site_by_siteId"]);
+
+    // The spelled-out tool name comes first
+    let found = search(&client, json!({"query": "org by orgId"})).await;
+    assert_eq!(result_names(&found)[0], "org_by_orgId");
+
+    let found = search(&client, json!({"query": "organization", "method": "post"})).await;
+    assert_eq!(result_names(&found), vec!["update_org_by_orgId"]);
+    let found = search(&client, json!({"query": "get", "tag": "site"})).await;
+    assert_eq!(result_names(&found), vec!["site_by_siteId"]);
+    let found = search(&client, json!({"query": "organization", "limit": 2})).await;
+    assert_eq!(found["total"], 4);
+    assert_eq!(result_names(&found).len(), 2);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_search_endpoints_respects_visibility_filters() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            deny_tools: vec!["org_by_orgId".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let found = search(&client, json!({"query": "organization"})).await;
+    assert_eq!(result_names(&found), vec!["orgs"]);
+
+    let err = client
+        .call_tool(call(
+            "search_endpoints",
+            json!({"query": "site", "limit": 0}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Argument 'limit' must be an integer between 1 and 50, got 0"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_describe_endpoint_returns_the_full_contract() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/site": {
+                "put": {
+                    "description": "Create a site.",
+                    "tags": ["Site"],
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                        {"name": "mode", "in": "query", "required": true, "schema": {"type": "string", "enum": ["newt", "wireguard"]}},
+                        {"name": "notify", "in": "query", "schema": {"type": "boolean", "default": false}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string", "example": "Main office", "maxLength": 64},
+                                        "exitNodeId": {"type": "integer", "minimum": 1},
+                                        "subnet": {"type": "string"}
+                                    },
+                                    "required": ["name", "exitNodeId"]
+                                }
+                            }
+                        }
+                    },
+                    "responses": {"201": {"description": "Site created"}}
+                }
+            }
+        }
+    }"#;
+    let service = common::build_service(spec, "http://localhost:1", ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "describe_endpoint",
+            json!({"name": "create_org_by_orgId_site"}),
+        ))
+        .await
+        .unwrap();
+    let text = common::result_text(&result);
+    for expected in [
+        "`PUT /org/{orgId}/site`",
+        "## Path parameters",
+        "| `orgId` | string | yes |",
+        "## Query parameters",
+        "| `mode` | string | yes |  | one of: newt, wireguard |",
+        "| `notify` | boolean | no |  | default: false |",
+        "## Request body (`application/json`)",
+        "| `name` | string | yes |  | max length: 64; example: \"Main office\" |",
+        "| `exitNodeId` | integer | yes |  | min: 1 |",
+        "| `subnet` | string | no |",
+        "- `201` Site created",
+        "## Example",
+    ] {
+        assert!(
+            text.contains(expected),
+            "missing {:?} in:\n{}",
+            expected,
+            text
+        );
+    }
+
+    let described = result.structured_content.unwrap();
+    assert_eq!(
+        described["example"],
+        json!({
+            "orgId": "<orgId>",
+            "mode": "newt",
+            "name": "Main office",
+            "exitNodeId": 1
+        })
+    );
+    assert_eq!(
+        described["inputSchema"]["required"],
+        json!(["orgId", "mode", "name", "exitNodeId"])
+    );
+
+    let err = client
+        .call_tool(call(
+            "describe_endpoint",
+            json!({"name": "create_org_by_orgid_site"}),
+        ))
+        .await
+        .unwrap_err();
+    let rmcp::ServiceError::McpError(err) = err else {
+        panic!("expected an MCP error, got {:?}", err);
+    };
+    assert_eq!(
+        err.message,
+        "Unknown tool: create_org_by_orgid_site. Did you mean create_org_by_orgId_site?"
+    );
+    assert_eq!(
+        err.data,
+        Some(json!({"candidates": ["create_org_by_orgId_site"]}))
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_openapi_returns_the_loaded_spec() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("pangolin_get_openapi", json!({})))
+        .await
+        .unwrap();
+    let spec = result.structured_content.unwrap();
+    assert_eq!(spec["info"]["title"], "Test Pangolin API");
+    assert_eq!(spec["info"]["version"], "v1");
+    assert!(spec["paths"].get("/orgs").is_some());
+
+    // A path narrows the spec down to that path
+    let result = client
+        .call_tool(call("pangolin_get_openapi", json!({"path": "/orgs"})))
+        .await
+        .unwrap();
+    let spec = result.structured_content.unwrap();
+    assert_eq!(spec["info"]["version"], "v1");
+    assert_eq!(
+        spec["paths"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        vec!["/orgs"]
+    );
+
+    let err = client
+        .call_tool(call("pangolin_get_openapi", json!({"path": "/nope"})))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Path '/nope' is not in the OpenAPI spec"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_openapi_leaves_out_hidden_operations() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            deny_tools: vec!["site_by_siteId".to_string()],
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("pangolin_get_openapi", json!({})))
+        .await
+        .unwrap();
+    let spec = result.structured_content.unwrap();
+    let org = spec["paths"]["/org/{orgId}"].as_object().unwrap();
+    assert!(org.contains_key("get"));
+    assert!(!org.contains_key("post"));
+    assert!(!org.contains_key("delete"));
+    assert!(spec["paths"].get("/site/{siteId}").is_none());
+    assert!(spec["paths"].get("/orgs").is_some());
+
+    let result = client
+        .call_tool(call(
+            "pangolin_get_openapi",
+            json!({"path": "/org/{orgId}"}),
+        ))
+        .await
+        .unwrap();
+    let spec = result.structured_content.unwrap();
+    let org = spec["paths"]["/org/{orgId}"].as_object().unwrap();
+    assert!(org.contains_key("get"));
+    assert!(!org.contains_key("post"));
+
+    // A path whose operations are all hidden looks absent
+    let err = client
+        .call_tool(call(
+            "pangolin_get_openapi",
+            json!({"path": "/site/{siteId}"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Path '/site/{siteId}' is not in the OpenAPI spec"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_get_openapi_can_be_disabled() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            disable_openapi_tool: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(tools.iter().all(|t| t.name != "pangolin_get_openapi"));
+    assert!(client
+        .call_tool(call("pangolin_get_openapi", json!({})))
+        .await
+        .is_err());
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_call_reports_each_entry() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/org-a"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "org-a"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/org/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({"message": "Not found"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/site/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"siteId": 7})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "batch_call",
+            json!({"calls": [
+                {"tool": "org_by_orgId", "arguments": {"orgId": "org-a"}},
+                {"tool": "org_by_orgId", "arguments": {"orgId": "missing"}},
+                {"tool": "no_such_tool"},
+                {"tool": "site_by_siteId", "arguments": {"siteId": "7"}},
+            ]}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let batch = result.structured_content.unwrap();
+    assert_eq!(batch["succeeded"], 2);
+    assert_eq!(batch["failed"], 2);
+    assert_eq!(batch["skipped"], 0);
+    let statuses: Vec<_> = batch["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["ok", "error", "error", "ok"]);
+    assert_eq!(batch["results"][0]["result"]["orgId"], "org-a");
+    assert_eq!(batch["results"][3]["result"]["siteId"], 7);
+    assert!(batch["results"][2]["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("Unknown tool: no_such_tool"));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_call_stops_on_error_when_asked() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500).set_body_json(json!({"message": "boom"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "batch_call",
+            json!({
+                "calls": [
+                    {"tool": "update_org_by_orgId", "arguments": {"orgId": "a", "name": "A"}},
+                    {"tool": "update_org_by_orgId", "arguments": {"orgId": "b", "name": "B"}},
+                ],
+                "stop_on_error": true,
+            }),
+        ))
+        .await
+        .unwrap();
+    let batch = result.structured_content.unwrap();
+    assert_eq!(batch["failed"], 1);
+    assert_eq!(batch["skipped"], 1);
+    assert_eq!(batch["results"][1]["status"], "skipped");
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_batch_call_applies_read_only_mode_per_entry() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/org-a"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "org-a"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            read_only: true,
+            batch_max: Some(2),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "batch_call",
+            json!({"calls": [
+                {"tool": "update_org_by_orgId", "arguments": {"orgId": "org-a", "name": "x"}},
+                {"tool": "org_by_orgId", "arguments": {"orgId": "org-a"}},
+            ]}),
+        ))
+        .await
+        .unwrap();
+    let batch = result.structured_content.unwrap();
+    assert_eq!(batch["results"][0]["status"], "error");
+    assert!(batch["results"][0]["error"]
+        .as_str()
+        .unwrap()
+        .contains("disabled in read-only mode"));
+    assert_eq!(batch["results"][1]["status"], "ok");
+
+    // --batch-max caps the number of entries
+    let err = client
+        .call_tool(call(
+            "batch_call",
+            json!({"calls": [{"tool": "orgs"}, {"tool": "orgs"}, {"tool": "orgs"}]}),
+        ))
+        .await
+        .unwrap_err();
+    let rmcp::ServiceError::McpError(err) = err else {
+        panic!("expected an MCP error, got {:?}", err);
+    };
+    assert_eq!(
+        err.message,
+        "batch_call takes 1 to 2 calls (--batch-max), got 3"
+    );
+
+    client.cancel().await.unwrap();
+}
+
+const WORKFLOWS: &str = r#"{
+    "workflows": [
+        {
+            "name": "rename_org_and_get_site",
+            "description": "Rename an organization, then read its default site.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"orgId": {"type": "string"}, "name": {"type": "string"}},
+                "required": ["orgId", "name"]
+            },
+            "steps": [
+                {
+                    "id": "rename",
+                    "tool": "update_org_by_orgId",
+                    "arguments": {"orgId": "{{inputs.orgId}}", "name": "{{ inputs.name }} (renamed)"}
+                },
+                {
+                    "id": "site",
+                    "tool": "site_by_siteId",
+                    "arguments": {"siteId": "{{steps.rename.response.data.defaultSiteId}}"}
+                }
+            ]
+        }
+    ]
+}"#;
+
+#[tokio::test]
+async fn test_workflow_feeds_step_responses_into_later_steps() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .and(wiremock::matchers::body_json(
+            json!({"name": "Acme (renamed)"}),
+        ))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({"data": {"defaultSiteId": 42}})),
+        )
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/site/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"siteId": 42})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            workflows: workflows::parse(WORKFLOWS).unwrap(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let tool = tools
+        .iter()
+        .find(|t| t.name == "rename_org_and_get_site")
+        .unwrap();
+    assert_eq!(
+        tool.description.as_deref(),
+        Some("Rename an organization, then read its default site. Steps: update_org_by_orgId, site_by_siteId.")
+    );
+    assert_eq!(
+        tool.annotations.as_ref().unwrap().read_only_hint,
+        Some(false)
+    );
+
+    let result = client
+        .call_tool(call(
+            "rename_org_and_get_site",
+            json!({"orgId": "my-org", "name": "Acme"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let report = result.structured_content.unwrap();
+    assert_eq!(report["steps"][0]["id"], "rename");
+    assert_eq!(report["steps"][1]["response"], json!({"siteId": 42}));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_failed_workflow_step_reports_partial_results() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": {}})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            workflows: workflows::parse(WORKFLOWS).unwrap(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call(
+            "rename_org_and_get_site",
+            json!({"orgId": "my-org", "name": "Acme"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let report = result.structured_content.unwrap();
+    assert_eq!(report["failedStep"], "site");
+    assert_eq!(
+        report["message"],
+        "Workflow rename_org_and_get_site stopped at step site (site_by_siteId): \
+         '{{steps.rename.response.data.defaultSiteId}}' has no value"
+    );
+    assert_eq!(report["completed"][0]["response"], json!({"data": {}}));
+
+    let err = client
+        .call_tool(call("rename_org_and_get_site", json!({"orgId": "my-org"})))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Missing required argument: name"));
+
+    client.cancel().await.unwrap();
+}
+
+#[test]
+fn test_workflow_steps_only_reference_earlier_steps() {
+    let err = workflows::parse(
+        r#"{"workflows": [{"name": "w", "steps": [
+            {"id": "a", "tool": "orgs", "arguments": {"x": "{{steps.b.response.id}}"}},
+            {"id": "b", "tool": "orgs"}
+        ]}]}"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        format!("{:#}", err),
+        "workflow 'w': step 'a' references 'b' before it has run"
+    );
+}
+
+#[tokio::test]
+async fn test_group_by_tag_dispatches_operations() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/my-org"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "my-org"})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            group_by_tag: true,
+            read_only: true,
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "pangolin_organization",
+            "pangolin_site",
+            "pangolin_get_server_stats",
+            "pangolin_search_endpoints",
+            "pangolin_describe_endpoint",
+            "pangolin_batch_call",
+            "pangolin_pangolin_get_openapi"
+        ]
+    );
+    let organization = &tools[0];
+    assert_eq!(
+        organization.input_schema["properties"]["operation"]["enum"],
+        json!(["orgs", "org_by_orgId"])
+    );
+    let description = organization.description.as_deref().unwrap();
+    assert!(
+        description.contains("- org_by_orgId: ") && description.contains("(GET /org/{orgId})"),
+        "{}",
+        description
+    );
+
+    let result = client
+        .call_tool(call(
+            "pangolin_organization",
+            json!({"operation": "org_by_orgId", "arguments": {"orgId": "my-org"}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert!(common::result_text(&result).contains("my-org"));
+
+    // Inner operations keep their own checks
+    let err = client
+        .call_tool(call(
+            "pangolin_organization",
+            json!({"operation": "update_org_by_orgId", "arguments": {"orgId": "my-org", "name": "x"}}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("disabled in read-only mode"),
+        "{}",
+        err
+    );
+
+    let err = client
+        .call_tool(call(
+            "pangolin_organization",
+            json!({"operation": "site_by_siteId"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "Unknown operation 'site_by_siteId' for pangolin_organization; expected one of:"
+        ),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_generic_mode_searches_then_calls_endpoints() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/site/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"siteId": 7})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            generic_mode: true,
+            read_only: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, vec!["search_endpoints", "call_endpoint"]);
+
+    let found = client
+        .call_tool(call("search_endpoints", json!({"query": "site"})))
+        .await
+        .unwrap()
+        .structured_content
+        .unwrap();
+    let name = found["results"][0]["name"].as_str().unwrap();
+    assert_eq!(name, "site_by_siteId");
+
+    let result = client
+        .call_tool(call(
+            "call_endpoint",
+            json!({"name": name, "arguments": {"siteId": 7}}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(result.structured_content, Some(json!({"siteId": 7})));
+
+    // Validation and read-only mode apply as for a dedicated tool
+    let err = client
+        .call_tool(call(
+            "call_endpoint",
+            json!({"name": "site_by_siteId", "arguments": {"siteId": "seven"}}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("siteId"), "{}", err);
+    let err = client
+        .call_tool(call(
+            "call_endpoint",
+            json!({"name": "delete_org_by_orgId", "arguments": {"orgId": "a"}}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("disabled in read-only mode"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+/// Cache meta of a tool result
+#[tokio::test]
+async fn test_results_carry_upstream_call_meta() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/orgs"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"orgId": "acme"}]))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(
+            ResponseTemplate::new(400)
+                .set_body_json(json!({"message": "name is taken"}))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            cache_ttl: Some(Duration::from_secs(60)),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let upstream_meta = |result: &rmcp::model::CallToolResult| {
+        result
+            .meta
+            .as_ref()
+            .unwrap()
+            .get("upstream")
+            .cloned()
+            .unwrap()
+    };
+
+    // The URL is the path alone, without the query string
+    let result = client
+        .call_tool(call("orgs", json!({"limit": 5})))
+        .await
+        .unwrap();
+    let meta = upstream_meta(&result);
+    assert!(meta["duration_ms"].as_u64().unwrap() >= 200, "{}", meta);
+    assert_eq!(meta["http_status"], 200);
+    assert_eq!(meta["url"], "/orgs");
+    assert_eq!(meta["cached"], false);
+    assert_eq!(meta["attempts"], 1);
+
+    let result = client
+        .call_tool(call("orgs", json!({"limit": 5})))
+        .await
+        .unwrap();
+    let meta = upstream_meta(&result);
+    assert_eq!(meta["cached"], true);
+    assert_eq!(meta["http_status"], 200);
+    assert_eq!(meta["attempts"], 0);
+
+    // Error results carry it too
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "acme", "name": "Acme"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let meta = upstream_meta(&result);
+    assert!(meta["duration_ms"].as_u64().unwrap() >= 200, "{}", meta);
+    assert_eq!(meta["http_status"], 400);
+    assert_eq!(meta["url"], "/org/acme");
+    assert_eq!(meta["cached"], false);
+    assert_eq!(meta["attempts"], 1);
+
+    client.cancel().await.unwrap();
+}
+
+fn cache_meta(result: &rmcp::model::CallToolResult) -> serde_json::Value {
+    result.meta.as_ref().unwrap().get("cache").cloned().unwrap()
+}
+
+#[tokio::test]
+async fn test_get_responses_are_cached_until_refreshed_or_expired() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .expect(3)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            cache_ttl: Some(Duration::from_millis(300)),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let get = |args: serde_json::Value| client.call_tool(call("org_by_orgId", args));
+
+    // Miss, then hit
+    let result = get(json!({"orgId": "acme"})).await.unwrap();
+    assert_eq!(cache_meta(&result), json!({"hit": false}));
+    let result = get(json!({"orgId": "acme"})).await.unwrap();
+    assert_eq!(cache_meta(&result)["hit"], true);
+    assert_eq!(result.structured_content, Some(json!({"orgId": "acme"})));
+
+    // _no_cache goes to the API and refreshes the entry
+    let result = get(json!({"orgId": "acme", "_no_cache": true}))
+        .await
+        .unwrap();
+    assert_eq!(cache_meta(&result), json!({"hit": false}));
+    let result = get(json!({"orgId": "acme"})).await.unwrap();
+    assert_eq!(cache_meta(&result)["hit"], true);
+
+    // Expired entries are fetched again
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    let result = get(json!({"orgId": "acme"})).await.unwrap();
+    assert_eq!(cache_meta(&result), json!({"hit": false}));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_writes_invalidate_cached_responses_of_their_path() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .expect(2)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/site/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"siteId": 7})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            cache_ttl: Some(Duration::from_secs(60)),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    for _ in 0..2 {
+        client
+            .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+            .await
+            .unwrap();
+        client
+            .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+            .await
+            .unwrap();
+    }
+    let result = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "acme", "name": "Acme"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    // The updated organization is fetched again; the unrelated site is not
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(cache_meta(&result), json!({"hit": false}));
+    let result = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap();
+    assert_eq!(cache_meta(&result)["hit"], true);
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_whole_float_integer_path_param_has_no_decimal_point() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/site/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"siteId": 123})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 123.0})))
+        .await
+        .unwrap();
+    assert_eq!(
+        result.is_error,
+        Some(false),
+        "{}",
+        common::result_text(&result)
+    );
+
+    // A fractional value is still no integer
+    let err = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 123.5})))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("siteId"), "{}", err);
+
+    client.cancel().await.unwrap();
+}
+
+fn redact_fields() -> Vec<String> {
+    ["secret", "token", "password", "apiKey"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// A site whose response nests credentials in objects and arrays
+async fn mount_site_with_secrets(upstream: &MockServer) {
+    Mock::given(method("GET"))
+        .and(path("/site/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "siteId": 7,
+            "newt": {"newtId": "n1", "Secret": "s3cr3t"},
+            "users": [
+                {"email": "a@example.com", "PASSWORD": "hunter2"},
+                {"email": "b@example.com", "accessToken": "tok"}
+            ],
+            "X-API-Key": "k",
+            "api_key": "k2"
+        })))
+        .mount(upstream)
+        .await;
+}
+
+#[tokio::test]
+async fn test_response_secrets_are_redacted() {
+    let upstream = MockServer::start().await;
+    mount_site_with_secrets(&upstream).await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            redact_fields: redact_fields(),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap();
+    let masked = json!({
+        "siteId": 7,
+        "newt": {"newtId": "n1", "Secret": "***REDACTED***"},
+        "users": [
+            {"email": "a@example.com", "PASSWORD": "***REDACTED***"},
+            {"email": "b@example.com", "accessToken": "***REDACTED***"}
+        ],
+        "X-API-Key": "***REDACTED***",
+        "api_key": "***REDACTED***"
+    });
+    assert_eq!(result.structured_content, Some(masked));
+    let text = common::result_text(&result);
+    assert!(
+        !text.contains("s3cr3t") && !text.contains("hunter2"),
+        "{}",
+        text
+    );
+    assert_eq!(result.meta.as_ref().unwrap()["redacted"], 5);
+
+    // A filter cannot select a value before it is masked
+    let result = client
+        .call_tool(call(
+            "site_by_siteId",
+            json!({"siteId": 7, "_filter": "$.newt.Secret"}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(common::result_text(&result), "\"***REDACTED***\"");
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_responses_are_untouched_without_redact_fields() {
+    let upstream = MockServer::start().await;
+    mount_site_with_secrets(&upstream).await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap();
+    let structured = result.structured_content.clone().unwrap();
+    assert_eq!(structured["newt"]["Secret"], "s3cr3t");
+    assert_eq!(structured["users"][0]["PASSWORD"], "hunter2");
+    assert!(result
+        .meta
+        .as_ref()
+        .is_none_or(|meta| !meta.contains_key("redacted")));
+
+    client.cancel().await.unwrap();
+}
+
+fn org_scoped_config() -> ServiceConfig {
+    ServiceConfig {
+        org_id: Some("acme".to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_org_id_is_dropped_from_schemas_and_cross_org_tools_hidden() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        org_scoped_config(),
+    );
+    let client = common::connect(service).await;
+
+    // Tools not addressed by orgId could reach other organizations
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(tools.iter().all(|t| t.name != "orgs"));
+    assert!(tools.iter().all(|t| t.name != "site_by_siteId"));
+    let error = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("it is not addressed by orgId"),
+        "{}",
+        error
+    );
+    let update = tools
+        .iter()
+        .find(|t| t.name == "update_org_by_orgId")
+        .unwrap();
+    assert!(update.input_schema["properties"].get("orgId").is_none());
+    assert_eq!(update.input_schema["required"], json!(["name"]));
+
+    let error = client.call_tool(call("orgs", json!({}))).await.unwrap_err();
+    assert!(
+        error.to_string().contains("limits this server to 'acme'"),
+        "{}",
+        error
+    );
+
+    let instructions = client.peer_info().unwrap().instructions.clone().unwrap();
+    assert!(
+        instructions.contains("Organization: scoped to 'acme'"),
+        "{}",
+        instructions
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_org_id_is_filled_in_and_other_orgs_are_refused() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"orgId": "acme"})))
+        .expect(2)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/org/beta"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        org_scoped_config(),
+    );
+    let client = common::connect(service).await;
+
+    let result = client
+        .call_tool(call("org_by_orgId", json!({})))
+        .await
+        .unwrap();
+    assert_eq!(result.structured_content, Some(json!({"orgId": "acme"})));
+
+    // Naming the scoped organization explicitly is fine
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let error = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "beta"})))
+        .await
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("scoped to organization 'acme'; orgId 'beta' is not allowed"),
+        "{}",
+        error
+    );
+
+    // Resource templates are held to the same scope
+    let error = client
+        .read_resource(rmcp::model::ReadResourceRequestParam {
+            uri: "pangolin://org/beta".to_string(),
+        })
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("orgId 'beta' is not allowed"),
+        "{}",
+        error
+    );
+
+    client.cancel().await.unwrap();
+}
+
+const BOOL_QUERY_SPEC: &str = r#"{
+    "openapi": "3.0.0",
+    "info": {"title": "Test", "version": "1.0.0"},
+    "paths": {
+        "/sites": {
+            "get": {
+                "parameters": [
+                    {"name": "online", "in": "query", "schema": {"type": "boolean"}},
+                    {"name": "name", "in": "query", "schema": {"type": "string"}}
+                ],
+                "responses": {}
+            }
+        }
+    }
+}"#;
+
+#[tokio::test]
+async fn test_bool_query_style_controls_boolean_query_values() {
+    for (style, expected) in [
+        (BoolQueryStyle::TrueFalse, ["?online=true", "?online=false"]),
+        (BoolQueryStyle::Numeric, ["?online=1", "?online=0"]),
+        (BoolQueryStyle::Presence, ["?online=", ""]),
+    ] {
+        let service = common::build_service(
+            BOOL_QUERY_SPEC,
+            "http://localhost:1",
+            ServiceConfig {
+                bool_query_style: style,
+                dry_run: true,
+                ..Default::default()
+            },
+        );
+        let client = common::connect(service).await;
+
+        for (flag, query) in [true, false].into_iter().zip(expected) {
+            let result = client
+                .call_tool(call("sites", json!({"online": flag})))
+                .await
+                .unwrap();
+            let url = &result.structured_content.unwrap()["request"]["url"];
+            assert_eq!(
+                url,
+                &json!(format!("http://localhost:1/sites{}", query)),
+                "{:?}",
+                style
+            );
+        }
+
+        // Strings that merely look boolean are left alone
+        let result = client
+            .call_tool(call("sites", json!({"name": "true"})))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.structured_content.unwrap()["request"]["url"],
+            "http://localhost:1/sites?name=true"
+        );
+
+        client.cancel().await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_upstream_failures_follow_the_error_policy() {
+    let upstream = MockServer::start().await;
+    for (site, status) in [(400, 400), (403, 403), (502, 502), (503, 503)] {
+        Mock::given(method("GET"))
+            .and(path(format!("/site/{}", site)))
+            .respond_with(
+                ResponseTemplate::new(status).set_body_json(json!({"message": "upstream says no"})),
+            )
+            .mount(&upstream)
+            .await;
+    }
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+    let get_site = |site: u16| client.call_tool(call("site_by_siteId", json!({"siteId": site})));
+    let mcp_error = |err: rmcp::ServiceError| match err {
+        rmcp::ServiceError::McpError(err) => err,
+        other => panic!("expected an MCP error, got {:?}", other),
+    };
+
+    // Answers of the API stay tool results
+    let result = get_site(400).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.structured_content.unwrap()["status"], 400);
+
+    let result = get_site(403).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(common::result_text(&result)
+        .contains("(the key in PANGOLIN_API_KEY lacks permission for this operation)"));
+    assert_eq!(result.structured_content.unwrap()["status"], 403);
+
+    for status in [502, 503] {
+        let err = mcp_error(get_site(status).await.unwrap_err());
+        assert_eq!(err.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+        assert!(
+            err.message
+                .ends_with("the Pangolin API is unavailable, retry later"),
+            "{}",
+            err.message
+        );
+        let data = err.data.unwrap();
+        assert_eq!(data["status"], status);
+        assert_eq!(data["kind"], "unavailable");
+        assert_eq!(data["retriable"], true);
+    }
+    client.cancel().await.unwrap();
+
+    // Nothing listening: a network failure
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://127.0.0.1:1",
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+    let err = client
+        .call_tool(call("site_by_siteId", json!({"siteId": 7})))
+        .await
+        .unwrap_err();
+    let err = mcp_error(err);
+    assert_eq!(err.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+    assert!(err.message.ends_with("; retry later"), "{}", err.message);
+    let data = err.data.unwrap();
+    assert_eq!(data["kind"], "unreachable");
+    assert_eq!(data["retriable"], true);
+    assert_eq!(data["status"], json!(null));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_write_rate_limit_rejects_writes_until_capacity_returns() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(3)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&upstream)
+        .await;
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &upstream.uri(),
+        ServiceConfig {
+            write_rate_limit: Some(2),
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let update = |args: serde_json::Value| client.call_tool(call("update_org_by_orgId", args));
+    let edit = json!({"orgId": "acme", "name": "Acme"});
+
+    for _ in 0..2 {
+        assert_eq!(update(edit.clone()).await.unwrap().is_error, Some(false));
+    }
+    // Dry runs and GETs are never limited
+    let result = update(json!({"orgId": "acme", "name": "Acme", "_dry_run": true}))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let result = client
+        .call_tool(call("org_by_orgId", json!({"orgId": "acme"})))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let result = update(edit.clone()).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let rejection = result.structured_content.unwrap();
+    assert_eq!(
+        rejection["message"],
+        "Write rate limit reached (2 per minute); capacity returns in 30s"
+    );
+    assert_eq!(rejection["retry_after_secs"], 30);
+
+    // One token is back half a minute later
+    tokio::time::pause();
+    tokio::time::advance(Duration::from_secs(30)).await;
+    tokio::time::resume();
+    assert_eq!(update(edit.clone()).await.unwrap().is_error, Some(false));
+    assert_eq!(update(edit).await.unwrap().is_error, Some(true));
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_content_type_argument_picks_a_declared_content_type() {
+    let upstream = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .and(header("Content-Type", "application/x-www-form-urlencoded"))
+        .and(wiremock::matchers::body_string("name=Form"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/org/my-org"))
+        .and(header("Content-Type", "application/json"))
+        .and(wiremock::matchers::body_json(json!({"name": "Json"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&upstream)
+        .await;
+
+    let service =
+        common::build_service(DUAL_CONTENT_SPEC, &upstream.uri(), ServiceConfig::default());
+    let client = common::connect(service).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert_eq!(
+        tools[0].input_schema["properties"]["_content_type"],
+        json!({
+            "type": "string",
+            "enum": ["application/json", "application/x-www-form-urlencoded"],
+            "description": "Request content type (default: application/json)"
+        })
+    );
+
+    for args in [
+        json!({"orgId": "my-org", "name": "Form", "_content_type": "application/x-www-form-urlencoded"}),
+        json!({"orgId": "my-org", "name": "Json"}),
+    ] {
+        let result = client
+            .call_tool(call("update_org_by_orgId", args))
+            .await
+            .unwrap();
+        assert_eq!(
+            result.is_error,
+            Some(false),
+            "{}",
+            common::result_text(&result)
+        );
+    }
+
+    let err = client
+        .call_tool(call(
+            "update_org_by_orgId",
+            json!({"orgId": "my-org", "name": "X", "_content_type": "text/plain"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "'_content_type' must be one of the content types update_org_by_orgId accepts: application/json, application/x-www-form-urlencoded"
+        ),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_base_url_override_needs_the_flag() {
+    let configured = MockServer::start().await;
+    let other = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/org/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"server": "other"})))
+        .expect(1)
+        .mount(&other)
+        .await;
+
+    let get_org = || {
+        call(
+            "org_by_orgId",
+            json!({"orgId": "acme", "_base_url": other.uri()}),
+        )
+    };
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &configured.uri(),
+        ServiceConfig::default(),
+    );
+    let client = common::connect(service).await;
+    let err = client.call_tool(get_org()).await.unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "'_base_url' is not allowed; start the server with --allow-base-url-override"
+        ),
+        "{}",
+        err
+    );
+    client.cancel().await.unwrap();
+
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        &configured.uri(),
+        ServiceConfig {
+            allow_base_url_override: true,
+            ..Default::default()
+        },
+    );
+    let client = common::connect(service).await;
+    let result = client.call_tool(get_org()).await.unwrap();
+    assert_eq!(result.structured_content, Some(json!({"server": "other"})));
+
+    let err = client
+        .call_tool(call(
+            "org_by_orgId",
+            json!({"orgId": "acme", "_base_url": "ftp://files.example.com"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("'_base_url' is invalid"),
+        "{}",
+        err
+    );
+
+    client.cancel().await.unwrap();
+    assert!(configured.received_requests().await.unwrap().is_empty());
+}
@@ -167,6 +167,12 @@ mod test_helpers {
 #[path = "../src/types.rs"]
 mod types;
 
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
 #[path = "../src/swagger.rs"]
 mod swagger;
 
@@ -0,0 +1,43 @@
+//! Tests for the signal-driven shutdown path
+//!
+//! Run with: cargo test --test shutdown
+
+#[allow(dead_code)]
+#[path = "../src/shutdown.rs"]
+mod shutdown;
+
+use shutdown::run_until_shutdown;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+#[tokio::test]
+async fn test_signal_stops_the_service_and_waits_for_it() {
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    // Stands in for the MCP service: runs until told to stop
+    let service = async move {
+        stop_rx.await.unwrap();
+        "stopped cleanly"
+    };
+
+    let (output, signal) = run_until_shutdown(service, async { "SIGTERM" }, move || {
+        stop_tx.send(()).unwrap()
+    })
+    .await;
+    assert_eq!(output, "stopped cleanly");
+    assert_eq!(signal, Some("SIGTERM"));
+}
+
+#[tokio::test]
+async fn test_service_ending_first_skips_the_shutdown_path() {
+    let (output, signal) = run_until_shutdown(
+        async { "closed" },
+        async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            "SIGINT"
+        },
+        || panic!("stop must not run when the service ends by itself"),
+    )
+    .await;
+    assert_eq!(output, "closed");
+    assert_eq!(signal, None);
+}
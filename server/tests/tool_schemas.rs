@@ -0,0 +1,533 @@
+//! Snapshot tests for the generated tool input schemas
+//!
+//! Run with: cargo test --test tool_schemas
+
+#[path = "../src/audit.rs"]
+mod audit;
+
+#[path = "../src/cache.rs"]
+mod cache;
+
+#[path = "../src/completions.rs"]
+mod completions;
+
+#[path = "../src/mcp_logging.rs"]
+mod mcp_logging;
+
+#[path = "../src/json_patch.rs"]
+mod json_patch;
+
+#[path = "../src/overlay.rs"]
+mod overlay;
+
+#[path = "../src/pangolin_client.rs"]
+mod pangolin_client;
+
+#[path = "../src/prompts.rs"]
+mod prompts;
+
+#[path = "../src/rate_limit.rs"]
+mod rate_limit;
+
+#[path = "../src/redact.rs"]
+mod redact;
+
+#[path = "../src/resources.rs"]
+mod resources;
+
+#[path = "../src/search.rs"]
+mod search;
+
+#[path = "../src/service.rs"]
+mod service;
+
+#[path = "../src/stats.rs"]
+mod stats;
+
+#[path = "../src/swagger.rs"]
+mod swagger;
+
+#[path = "../src/truncate.rs"]
+mod truncate;
+
+#[path = "../src/types.rs"]
+mod types;
+
+#[path = "../src/workflows.rs"]
+mod workflows;
+
+mod common;
+
+use serde_json::json;
+use service::ServiceConfig;
+
+#[tokio::test]
+async fn test_examples_are_emitted_in_schema() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/site": {
+                "put": {
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "description": "Organization ID",
+                            "schema": {"type": "string", "example": "acme"}
+                        }
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "subnet": {
+                                            "type": "string",
+                                            "description": "Site subnet",
+                                            "example": "100.90.128.0/24"
+                                        },
+                                        "tags": {
+                                            "type": "array",
+                                            "items": {"type": "string"},
+                                            "examples": [["edge", "eu-west"]]
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "create_org_by_orgId_site").await;
+    let properties = &tool.input_schema["properties"];
+
+    assert_eq!(
+        properties["orgId"],
+        json!({
+            "type": "string",
+            "description": "Organization ID (e.g. \"acme\")",
+            "examples": ["acme"]
+        })
+    );
+    assert_eq!(
+        properties["subnet"],
+        json!({
+            "type": "string",
+            "description": "Site subnet (e.g. \"100.90.128.0/24\")",
+            "examples": ["100.90.128.0/24"]
+        })
+    );
+    assert_eq!(
+        properties["tags"],
+        json!({
+            "type": "array",
+            "examples": [["edge", "eu-west"]]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_large_examples_are_truncated() {
+    let long_text = "x".repeat(500);
+    let long_list: Vec<String> = (0..100).map(|i| format!("item-{}", i)).collect();
+    let spec = json!({
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/blueprint": {
+                "put": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "text": {"type": "string", "example": long_text},
+                                        "list": {"type": "array", "example": long_list}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+    .to_string();
+
+    let tool = common::get_tool(&spec, ServiceConfig::default(), "create_blueprint").await;
+    let properties = &tool.input_schema["properties"];
+
+    let text_example = properties["text"]["examples"][0].as_str().unwrap();
+    assert!(text_example.ends_with("..."));
+    assert!(text_example.len() < long_text.len());
+    // Long examples are not quoted inline in the description
+    assert!(properties["text"].get("description").is_none());
+
+    let list_example = properties["list"]["examples"][0].as_array().unwrap();
+    assert!(!list_example.is_empty());
+    assert!(list_example.len() < long_list.len());
+    assert_eq!(list_example[0], "item-0");
+}
+
+#[tokio::test]
+async fn test_format_hints_are_emitted_in_schema() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/invite": {
+                "put": {
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "schema": {"type": "string", "format": "uuid"}
+                        }
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "email": {"type": "string", "format": "email"},
+                                        "expiresAt": {"type": "string", "format": "date-time"}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "create_org_by_orgId_invite").await;
+    let properties = &tool.input_schema["properties"];
+
+    assert_eq!(
+        properties["orgId"],
+        json!({"type": "string", "format": "uuid"})
+    );
+    assert_eq!(
+        properties["email"],
+        json!({"type": "string", "format": "email"})
+    );
+    assert_eq!(
+        properties["expiresAt"],
+        json!({"type": "string", "format": "date-time"})
+    );
+}
+
+#[tokio::test]
+async fn test_description_lists_required_arguments() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/site": {
+                "put": {
+                    "description": "Create a site.",
+                    "parameters": [
+                        {"name": "orgId", "in": "path", "required": true, "schema": {"type": "string"}},
+                        {"name": "dryRun", "in": "query", "required": true, "schema": {"type": "boolean"}},
+                        {"name": "notify", "in": "query", "schema": {"type": "boolean"}}
+                    ],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "name": {"type": "string"},
+                                        "subnet": {"type": "string"},
+                                        "type": {"type": "string"}
+                                    },
+                                    "required": ["name", "type"]
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "/orgs": {"get": {"description": "List organizations"}}
+        }
+    }"#;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "create_org_by_orgId_site").await;
+    assert_eq!(
+        tool.description.as_deref(),
+        Some("[PUT] Create a site. Requires: orgId, dryRun, name, type.")
+    );
+
+    // Nothing to add when every argument is optional
+    let tool = common::get_tool(spec, ServiceConfig::default(), "orgs").await;
+    assert_eq!(
+        tool.description.as_deref(),
+        Some("[GET] List organizations")
+    );
+}
+
+#[tokio::test]
+async fn test_numeric_bounds_are_emitted_in_schema() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/target": {
+                "put": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "type": "object",
+                                    "properties": {
+                                        "port": {"type": "integer", "minimum": 1, "maximum": 65535.0},
+                                        "weight": {"type": "number", "minimum": 0.5},
+                                        "quota": {"type": "integer", "maximum": 9007199254740993}
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "create_target").await;
+    let properties = &tool.input_schema["properties"];
+
+    // Integer bounds are integers, even when the spec writes them as floats
+    assert_eq!(
+        properties["port"],
+        json!({"type": "integer", "minimum": 1, "maximum": 65535})
+    );
+    assert_eq!(
+        serde_json::to_string(&properties["port"]["minimum"]).unwrap(),
+        "1"
+    );
+    assert_eq!(
+        properties["weight"],
+        json!({"type": "number", "minimum": 0.5})
+    );
+    // Beyond 2^53 an f64 would round the bound
+    assert_eq!(
+        serde_json::to_string(&properties["quota"]["maximum"]).unwrap(),
+        "9007199254740993"
+    );
+}
+
+#[tokio::test]
+async fn test_parameter_level_examples_are_emitted_in_schema() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/org/{orgId}/sites": {
+                "get": {
+                    "parameters": [
+                        {
+                            "name": "orgId",
+                            "in": "path",
+                            "required": true,
+                            "example": "acme",
+                            "schema": {"type": "string", "example": "ignored"}
+                        },
+                        {
+                            "name": "limit",
+                            "in": "query",
+                            "schema": {"type": "integer"},
+                            "examples": {
+                                "small": {"summary": "One page", "value": 25}
+                            }
+                        }
+                    ]
+                }
+            }
+        }
+    }"#;
+
+    let tool = common::get_tool(spec, ServiceConfig::default(), "org_by_orgId_sites").await;
+    let properties = &tool.input_schema["properties"];
+
+    assert_eq!(properties["orgId"]["examples"], json!(["acme"]));
+    assert_eq!(properties["limit"]["examples"], json!([25]));
+}
+
+#[tokio::test]
+async fn test_one_of_and_any_of_bodies_keep_their_variants() {
+    let spec = r#"{
+        "openapi": "3.0.0",
+        "info": {"title": "Test", "version": "v1"},
+        "paths": {
+            "/site/{siteId}/target": {
+                "put": {
+                    "parameters": [{"name": "siteId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "oneOf": [
+                                        {
+                                            "type": "object",
+                                            "properties": {"ip": {"type": "string"}, "port": {"type": "integer"}},
+                                            "required": ["ip", "port"]
+                                        },
+                                        {
+                                            "type": "object",
+                                            "properties": {"hostname": {"type": "string"}},
+                                            "required": ["hostname"]
+                                        }
+                                    ]
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "/org/{orgId}/idp": {
+                "put": {
+                    "parameters": [{"name": "orgId", "in": "path", "required": true}],
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": {
+                                    "anyOf": [
+                                        {"type": "object", "properties": {"clientId": {"type": "string"}}},
+                                        {"type": "object", "properties": {"metadataUrl": {"type": "string"}}}
+                                    ]
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"#;
+
+    let target = common::get_tool(
+        spec,
+        ServiceConfig::default(),
+        "create_site_by_siteId_target",
+    )
+    .await;
+    let schema = &target.input_schema;
+    assert_eq!(
+        schema["oneOf"],
+        json!([
+            {
+                "properties": {"ip": {"type": "string"}, "port": {"type": "integer"}},
+                "required": ["ip", "port"]
+            },
+            {
+                "properties": {"hostname": {"type": "string"}},
+                "required": ["hostname"]
+            }
+        ])
+    );
+    // Variant-specific fields are listed but not required at the top level
+    assert_eq!(schema["required"], json!(["siteId"]));
+    assert!(schema["properties"].get("hostname").is_some());
+    assert!(target
+        .description
+        .unwrap()
+        .contains("Body must match exactly one of: (ip, port); (hostname)."));
+
+    let idp = common::get_tool(spec, ServiceConfig::default(), "create_org_by_orgId_idp").await;
+    assert_eq!(
+        idp.input_schema["anyOf"],
+        json!([
+            {"properties": {"clientId": {"type": "string"}}, "required": []},
+            {"properties": {"metadataUrl": {"type": "string"}}, "required": []}
+        ])
+    );
+}
+
+#[test]
+fn test_tool_catalog_export() {
+    let service = common::build_service(
+        common::get_test_swagger_spec(),
+        "http://localhost:1",
+        ServiceConfig {
+            read_only: true,
+            tool_prefix: Some("pangolin_".to_string()),
+            ..Default::default()
+        },
+    );
+
+    let catalog = serde_json::to_value(service.tools()).unwrap();
+    let tools = catalog.as_array().unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    // Read-only mode filters out write endpoints
+    assert_eq!(
+        names,
+        vec![
+            "pangolin_orgs",
+            "pangolin_org_by_orgId",
+            "pangolin_site_by_siteId"
+        ]
+    );
+
+    let org = &tools[1];
+    assert_eq!(
+        org["description"],
+        "[GET] Get an organization. Requires: orgId. (Tags: Organization)"
+    );
+    assert_eq!(
+        org["inputSchema"],
+        json!({
+            "type": "object",
+            "properties": {
+                "orgId": {"type": "string"},
+                "_filter": {
+                    "type": "string",
+                    "description": "Return only part of the response: a JSON pointer (/data/niceId) or JSONPath ($.data.sites[*].name)"
+                },
+                "_fields": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Keep only these keys of the response object, or of every object in an array response (site.name for nested keys)"
+                }
+            },
+            "required": ["orgId"]
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_strict_tool_schemas_close_input_schemas() {
+    let spec = common::get_test_swagger_spec();
+    let open = common::get_tool(spec, ServiceConfig::default(), "update_org_by_orgId").await;
+    assert!(open.input_schema.get("additionalProperties").is_none());
+    assert!(open.input_schema["properties"].get("_dry_run").is_none());
+
+    let strict = common::get_tool(
+        spec,
+        ServiceConfig {
+            strict_tool_schemas: true,
+            ..Default::default()
+        },
+        "update_org_by_orgId",
+    )
+    .await;
+    assert_eq!(strict.input_schema["additionalProperties"], json!(false));
+    // Reserved arguments stay usable under a closed schema
+    let properties: Vec<&String> = strict.input_schema["properties"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .collect();
+    assert_eq!(
+        properties,
+        ["orgId", "name", "_filter", "_fields", "_dry_run"]
+    );
+}